@@ -0,0 +1,125 @@
+use {
+    crate::error::ErrorCode,
+    anchor_lang::{
+        prelude::*,
+        solana_program::{ed25519_program, instruction::Instruction},
+    },
+};
+
+// Layout of the ed25519 program instruction data: num_signatures (u8), padding (u8), then one offsets struct per signature
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+// Instruction index used by the offsets to point to the data of the ed25519 instruction itself
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    let bytes = data
+        .get(offset..offset + 2)
+        .ok_or(ErrorCode::InvalidVoucherSignature)?;
+
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Checks that the instruction is an ed25519 program instruction verifying a single signature of the message by the signer,
+/// the ed25519 program already failed the transaction if the signature is not valid
+pub fn verify_ed25519_instruction(instruction: &Instruction, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    if instruction.program_id != ed25519_program::ID || !instruction.accounts.is_empty() {
+        return Err(ErrorCode::InvalidVoucherSignature.into());
+    }
+
+    let data = &instruction.data;
+    if data.len() < SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN || data[0] != 1 {
+        return Err(ErrorCode::InvalidVoucherSignature.into());
+    }
+
+    let signature_offset = read_u16(data, 2)? as usize;
+    let signature_instruction_index = read_u16(data, 4)?;
+    let public_key_offset = read_u16(data, 6)? as usize;
+    let public_key_instruction_index = read_u16(data, 8)?;
+    let message_data_offset = read_u16(data, 10)? as usize;
+    let message_data_size = read_u16(data, 12)? as usize;
+    let message_instruction_index = read_u16(data, 14)?;
+
+    // The signature, public key and message must be read from this instruction, not from another one of the transaction
+    if signature_instruction_index != CURRENT_INSTRUCTION
+        || public_key_instruction_index != CURRENT_INSTRUCTION
+        || message_instruction_index != CURRENT_INSTRUCTION
+    {
+        return Err(ErrorCode::InvalidVoucherSignature.into());
+    }
+
+    if data.get(signature_offset..signature_offset + SIGNATURE_LEN).is_none() {
+        return Err(ErrorCode::InvalidVoucherSignature.into());
+    }
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + PUBKEY_LEN)
+        .ok_or(ErrorCode::InvalidVoucherSignature)?;
+    if public_key != signer.as_ref() {
+        return Err(ErrorCode::InvalidVoucherSignature.into());
+    }
+
+    let signed_message = data
+        .get(message_data_offset..message_data_offset + message_data_size)
+        .ok_or(ErrorCode::InvalidVoucherSignature)?;
+    if signed_message != message {
+        return Err(ErrorCode::InvalidVoucherSignature.into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Instruction as built by the ed25519 program client: offsets, then the public key, the signature and the message
+    fn ed25519_instruction(signer: &Pubkey, message: &[u8], instruction_index: u16) -> Instruction {
+        let public_key_offset = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN;
+        let signature_offset = public_key_offset + PUBKEY_LEN;
+        let message_data_offset = signature_offset + SIGNATURE_LEN;
+
+        let mut data = vec![1, 0];
+        for value in [
+            signature_offset as u16,
+            instruction_index,
+            public_key_offset as u16,
+            instruction_index,
+            message_data_offset as u16,
+            message.len() as u16,
+            instruction_index,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[0; SIGNATURE_LEN]);
+        data.extend_from_slice(message);
+
+        Instruction { program_id: ed25519_program::ID, accounts: vec![], data }
+    }
+
+    #[test]
+    fn verify_ed25519_instruction_accepts_the_signer_and_message() {
+        let signer = Pubkey::new_unique();
+        let instruction = ed25519_instruction(&signer, b"voucher", CURRENT_INSTRUCTION);
+        assert!(verify_ed25519_instruction(&instruction, &signer, b"voucher").is_ok());
+    }
+
+    #[test]
+    fn verify_ed25519_instruction_rejects_another_signer_message_or_instruction() {
+        let signer = Pubkey::new_unique();
+        let instruction = ed25519_instruction(&signer, b"voucher", CURRENT_INSTRUCTION);
+        assert!(verify_ed25519_instruction(&instruction, &Pubkey::new_unique(), b"voucher").is_err());
+        assert!(verify_ed25519_instruction(&instruction, &signer, b"other").is_err());
+
+        // Offsets pointing to another instruction of the transaction
+        let instruction = ed25519_instruction(&signer, b"voucher", 0);
+        assert!(verify_ed25519_instruction(&instruction, &signer, b"voucher").is_err());
+
+        let mut instruction = ed25519_instruction(&signer, b"voucher", CURRENT_INSTRUCTION);
+        instruction.program_id = Pubkey::new_unique();
+        assert!(verify_ed25519_instruction(&instruction, &signer, b"voucher").is_err());
+    }
+}
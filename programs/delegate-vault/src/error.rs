@@ -42,10 +42,142 @@ pub enum ErrorCode {
     IncorrectReceiver,
     #[msg("Incorrect payment mint")]
     IncorrectPaymentMint,
-    #[msg("Payment amount must be either monthly (49 USDC) or yearly (499 USDC)")]
+    #[msg("Payment amount does not match the plan price")]
     IncorrectPaymentAmount,
     #[msg("Arithmetic overflow occurred")]
     ArithmeticOverflow,
     #[msg("Incorrect order vault")]
     IncorrectOrderVault,
-}
\ No newline at end of file
+    #[msg("Account is already in the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Chunked liquidation config is not valid")]
+    InvalidChunkConfig,
+    #[msg("Liquidation chunk exceeds the maximum amount allowed")]
+    ChunkTooLarge,
+    #[msg("Not enough time has passed since the last liquidation chunk")]
+    ChunkTooEarly,
+    #[msg("Bracket config is not valid")]
+    InvalidBracket,
+    #[msg("None of the bracket triggers has been reached")]
+    TriggerNotReached,
+    #[msg("Could not compute the execution price of the swap")]
+    InvalidExecutionPrice,
+    #[msg("Grid config is not valid")]
+    InvalidGrid,
+    #[msg("Grid can not be changed while it has filled levels")]
+    GridLevelsFilled,
+    #[msg("Grid trading is not enabled for this order")]
+    GridNotEnabled,
+    #[msg("Swap does not correspond to an unfilled grid crossing")]
+    InvalidGridSwap,
+    #[msg("Price feed is not valid")]
+    InvalidPriceFeed,
+    #[msg("Price feed is stale")]
+    StalePrice,
+    #[msg("Portfolio config is not valid")]
+    InvalidPortfolio,
+    #[msg("Rebalance does not move the portfolio closer to the target weights")]
+    RebalanceNotCloser,
+    #[msg("High water mark account is required")]
+    HighWaterMarkRequired,
+    #[msg("Shares must add up to 10000 basis points")]
+    InvalidShares,
+    #[msg("Fee schedule is not valid")]
+    InvalidFeeSchedule,
+    #[msg("Fee schedule account is required")]
+    FeeScheduleRequired,
+    #[msg("Fee vault account is required")]
+    FeeVaultRequired,
+    #[msg("Referrer is already set")]
+    ReferrerAlreadySet,
+    #[msg("Referrer is not valid")]
+    InvalidReferrer,
+    #[msg("Referrer vault account is required")]
+    ReferrerVaultRequired,
+    #[msg("Plan config is not valid")]
+    InvalidPlan,
+    #[msg("Plan is not active")]
+    InactivePlan,
+    #[msg("Referrer wallet account is required")]
+    ReferrerWalletRequired,
+    #[msg("Manager is not subscribed")]
+    NotSubscribed,
+    #[msg("Refund accounts are required")]
+    RefundAccountsRequired,
+    #[msg("Not enough balance to pay the refund")]
+    InsufficientRefundBalance,
+    #[msg("Auto renewal is not enabled for this plan")]
+    AutoRenewDisabled,
+    #[msg("Subscription is not within the renewal window")]
+    RenewalNotDue,
+    #[msg("Voucher is not valid")]
+    InvalidVoucher,
+    #[msg("Voucher has expired")]
+    VoucherExpired,
+    #[msg("Voucher signature is not valid")]
+    InvalidVoucherSignature,
+    #[msg("Payment token account is required")]
+    PaymentAccountRequired,
+    #[msg("Discount tiers are not valid")]
+    InvalidDiscountTiers,
+    #[msg("Staked tokens are still locked")]
+    StakeLocked,
+    #[msg("Not enough staked tokens")]
+    InsufficientStake,
+    #[msg("Mints with a permanent delegate are not allowed")]
+    PermanentDelegateNotAllowed,
+    #[msg("Non-transferable mints are not allowed")]
+    NonTransferableNotAllowed,
+    #[msg("Mints with frozen default account state are not allowed")]
+    DefaultFrozenNotAllowed,
+    #[msg("Mint is not allowed")]
+    MintNotAllowed,
+    #[msg("Deposit amount is below the minimum")]
+    DepositTooSmall,
+    #[msg("Deposit exceeds the max TVL of the mint")]
+    MaxTvlExceeded,
+    #[msg("Route amount exceeds the vault balance")]
+    RouteAmountExceedsBalance,
+    #[msg("Route slippage exceeds the order limit")]
+    SlippageTooHigh,
+    #[msg("Platform fee account is not a fee vault")]
+    InvalidPlatformFeeAccount,
+    #[msg("Max slippage is not valid")]
+    InvalidMaxSlippage,
+    #[msg("Route args are not valid")]
+    InvalidRouteArgs,
+    #[msg("Swap venue is not active")]
+    InactiveSwapVenue,
+    #[msg("Route is not registered on the swap venue")]
+    UnknownRoute,
+    #[msg("Swap venue config is not valid")]
+    InvalidSwapVenue,
+    #[msg("Route legs are not valid")]
+    InvalidRouteLegs,
+    #[msg("Intermediate vault is not a token vault of the order")]
+    InvalidIntermediateVault,
+    #[msg("Order has an open resting order")]
+    RestingOrderOpen,
+    #[msg("Order has no open resting order")]
+    NoRestingOrder,
+    #[msg("Resting order is not valid")]
+    InvalidRestingOrder,
+    #[msg("Route references a manager vault that does not belong to the order")]
+    UnauthorizedVault,
+    #[msg("Route references a program not allowed by the swap venue")]
+    UnexpectedProgram,
+    #[msg("Incorrect token vault")]
+    IncorrectTokenVault,
+    #[msg("Price feeds of the mints are required")]
+    OracleRequired,
+    #[msg("Active subscription has a different performance fee rate")]
+    SubscriptionRateMismatch,
+    #[msg("Transfer hook program is not allowed")]
+    TransferHookNotAllowed,
+    #[msg("Transfer hook mints can not be used for payments")]
+    PaymentMintHookNotAllowed,
+    #[msg("Subscription was used by a withdrawal at the subscribed rates, only the config authority can refund it")]
+    SubscriptionAlreadyUsed,
+    #[msg("Refundable period has not ended")]
+    RefundPeriodActive,
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+// Events emitted for the accounting of the subscriptions, mint is the default pubkey for payments in native SOL
+
+#[event]
+pub struct SubscriptionPaid {
+    pub manager: Pubkey,
+    pub payer: Pubkey,
+    pub plan: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub gifted: bool,
+    pub end_subscription: i64,
+}
+
+#[event]
+pub struct SubscriptionCancelled {
+    pub manager: Pubkey,
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub refund_amount: u64,
+    pub previous_end_subscription: i64,
+    pub cancelled_at: i64,
+}
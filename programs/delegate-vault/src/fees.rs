@@ -0,0 +1,377 @@
+use {
+    crate::{error::ErrorCode, state::*, token_extensions::{transfer_checked_with_hook, transfer_fee}},
+    anchor_lang::{prelude::*, system_program},
+    anchor_spl::associated_token::get_associated_token_address_with_program_id,
+    spl_math::precise_number::PreciseNumber,
+};
+
+// Constants for fee calculations
+pub const BASIS_POINTS: u128 = 10000; // 100% = 10000 basis points
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+
+/// Calculates amount * fee_rate / BASIS_POINTS, fee_rate in basis points (e.g., 250 = 2.50%)
+pub fn calculate_fee(amount: u64, fee_rate: u16) -> Result<u64> {
+    calculate_fee_for_period(amount, fee_rate, 1, 1)
+}
+
+/// Calculates amount * fee_rate / BASIS_POINTS * elapsed / period, used to prorate yearly rates
+pub fn calculate_fee_for_period(amount: u64, fee_rate: u16, elapsed: u128, period: u128) -> Result<u64> {
+    if amount == 0 || fee_rate == 0 || elapsed == 0 {
+        return Ok(0);
+    }
+
+    // Convert amount and fee rate to PreciseNumber for accurate calculation
+    let amount_precise = PreciseNumber::new(amount as u128)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    let fee_rate_precise = PreciseNumber::new(fee_rate as u128)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    let basis_points_precise = PreciseNumber::new(BASIS_POINTS)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    let elapsed_precise = PreciseNumber::new(elapsed)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    let period_precise = PreciseNumber::new(period)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    // Multiply first so short periods keep their precision: amount * fee_rate * elapsed
+    let numerator = amount_precise
+        .checked_mul(&fee_rate_precise)
+        .ok_or(ErrorCode::NumericalOverflow)?
+        .checked_mul(&elapsed_precise)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    // Then divide by 10000 (basis points) and the period
+    let denominator = basis_points_precise
+        .checked_mul(&period_precise)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    let fee_amount_precise = numerator
+        .checked_div(&denominator)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    // Convert back to u64
+    fee_amount_precise
+        .to_imprecise()
+        .ok_or(ErrorCode::NumericalOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::NumericalOverflow.into())
+}
+
+/// Performance fee rate of a manager, manager override > mint override > plan or config, using the subscribed rate if subscribed
+pub fn performance_fee_rate(
+    config: &Config,
+    manager: &Manager,
+    mint_fee_override: Option<&FeeOverride>,
+    manager_fee_override: Option<&FeeOverride>,
+    current_time: i64,
+) -> u16 {
+    let is_subscribed = manager.is_subscribed(current_time);
+
+    match manager_fee_override.or(mint_fee_override) {
+        Some(fee_override) if is_subscribed => fee_override.subscribed_performance_fee,
+        Some(fee_override) => fee_override.performance_fee,
+        None if is_subscribed => manager.subscription_performance_fee,
+        None => config.performance_fee,
+    }
+}
+
+/// Reduces the performance fee rate by the discount tier reached by the protocol tokens staked by the manager
+pub fn apply_stake_discount(fee_rate: u16, discount_config: Option<&DiscountConfig>, stake: Option<&Stake>) -> Result<u16> {
+    let (Some(discount_config), Some(stake)) = (discount_config, stake) else {
+        return Ok(fee_rate);
+    };
+
+    let discount = calculate_fee(fee_rate as u64, discount_config.discount(stake.amount))?;
+
+    Ok(fee_rate - discount as u16)
+}
+
+/// Splits amount by shares in basis points (adding up to 10000), the rounding dust goes to the share at dust_index
+pub fn split_amount(amount: u64, shares: &[u16], dust_index: usize) -> Result<Vec<u64>> {
+    let total_shares = shares.iter().map(|share| *share as u128).sum::<u128>();
+    if total_shares != BASIS_POINTS || dust_index >= shares.len() {
+        return Err(ErrorCode::InvalidShares.into());
+    }
+
+    let mut amounts = shares
+        .iter()
+        .map(|share| (amount as u128 * *share as u128 / BASIS_POINTS) as u64)
+        .collect::<Vec<u64>>();
+
+    let distributed = amounts.iter().sum::<u64>();
+    amounts[dust_index] += amount - distributed;
+
+    Ok(amounts)
+}
+
+/// Accrues the management fee of the order since the last accrual, charged over the deposited amount
+pub fn accrue_management_fee(order: &mut Order, manager: &Manager, config: &Config, current_time: i64) -> Result<()> {
+    let elapsed = current_time.saturating_sub(order.last_accrual_at);
+    if elapsed <= 0 {
+        return Ok(());
+    }
+
+    // Determine fee rate based on subscription status
+    let fee_rate = if manager.is_subscribed(current_time) {
+        config.subscribed_management_fee
+    } else {
+        config.management_fee
+    };
+
+    let fee_amount = calculate_fee_for_period(order.deposit_amount, fee_rate, elapsed as u128, SECONDS_PER_YEAR)?;
+
+    order.management_fee_accrued = order.management_fee_accrued
+        .checked_add(fee_amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    order.last_accrual_at = current_time;
+
+    Ok(())
+}
+
+/// Records the realized PnL of an order and returns the profit above the high water mark, the only part charged with performance fee
+pub fn apply_high_water_mark(high_water_mark: &mut HighWaterMark, pnl: i64) -> Result<u64> {
+    high_water_mark.cumulative_pnl = high_water_mark.cumulative_pnl
+        .checked_add(pnl)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    if high_water_mark.cumulative_pnl <= high_water_mark.high_water_mark {
+        return Ok(0);
+    }
+
+    let profit = high_water_mark.cumulative_pnl - high_water_mark.high_water_mark;
+    high_water_mark.high_water_mark = high_water_mark.cumulative_pnl;
+
+    Ok(profit as u64)
+}
+
+// Accounts used to move a protocol fee out of a vault, signer_seeds is empty when the authority signs the transaction
+// extra_accounts: accounts required by the transfer hook of the mint (empty if it has none)
+pub struct FeeTransfer<'a, 'info> {
+    pub token_program: AccountInfo<'info>,
+    pub from: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub decimals: u8,
+    pub signer_seeds: &'a [&'a [&'a [u8]]],
+    pub extra_accounts: &'a [AccountInfo<'info>],
+}
+
+impl<'a, 'info> FeeTransfer<'a, 'info> {
+    pub fn transfer(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        transfer_checked_with_hook(
+            self.token_program.clone(),
+            self.from.clone(),
+            self.mint.clone(),
+            to,
+            self.authority.clone(),
+            self.extra_accounts,
+            amount,
+            self.decimals,
+            self.signer_seeds,
+        )
+    }
+
+    /// Sends the share of the fee that goes to the referrer of the manager (if any) to its vault, returns the amount sent
+    /// Nothing is sent while the referrer vault of the mint is not initialized, the whole fee is then distributed as without referrer
+    pub fn share_with_referrer(
+        &self,
+        manager: &Manager,
+        referrer_vault: AccountInfo<'info>,
+        amount: u64,
+        share: u16,
+    ) -> Result<u64> {
+        if manager.referrer == Pubkey::default() || referrer_vault.owner != self.token_program.key {
+            return Ok(0);
+        }
+
+        let referral_amount = calculate_fee(amount, share)?;
+        if referral_amount == 0 {
+            return Ok(0);
+        }
+        self.transfer(referrer_vault, referral_amount)?;
+
+        Ok(referral_amount)
+    }
+
+    /// Sends the fee to the fee vault, or splits it between the fee schedule recipients (their ATAs, in the schedule order)
+    /// Returns the amount that landed on the fee vault after the transfer fee of the mint, the only part that can be refunded from it
+    pub fn distribute(
+        &self,
+        config: &Config,
+        fee_schedule: Option<&FeeSchedule>,
+        fee_vault: Option<AccountInfo<'info>>,
+        recipients: &[AccountInfo<'info>],
+        amount: u64,
+    ) -> Result<u64> {
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        if !config.fee_schedule_enabled {
+            let fee_vault = fee_vault.ok_or(ErrorCode::FeeVaultRequired)?;
+            self.transfer(fee_vault, amount)?;
+            return Ok(amount - transfer_fee(&self.mint, amount)?);
+        }
+
+        let fee_schedule = fee_schedule.ok_or(ErrorCode::FeeScheduleRequired)?;
+        if recipients.len() != fee_schedule.recipients.len() {
+            return Err(ErrorCode::InvalidRemainingAccounts.into());
+        }
+
+        let amounts = split_amount(amount, &fee_schedule.shares(), fee_schedule.dust_recipient as usize)?;
+
+        // A recipient without an ATA for the mint does not block the payment, its share goes to the fee vault
+        let mut fee_vault_amount: u64 = 0;
+        for ((recipient, account), amount) in fee_schedule.recipients.iter().zip(recipients).zip(amounts) {
+            let expected_account = get_associated_token_address_with_program_id(
+                &recipient.wallet,
+                self.mint.key,
+                self.token_program.key,
+            );
+            if account.key() != expected_account {
+                return Err(ErrorCode::IncorrectReceiver.into());
+            }
+
+            if account.owner != self.token_program.key {
+                fee_vault_amount = fee_vault_amount
+                    .checked_add(amount)
+                    .ok_or(ErrorCode::NumericalOverflow)?;
+                continue;
+            }
+
+            self.transfer(account.clone(), amount)?;
+        }
+
+        if fee_vault_amount == 0 {
+            return Ok(0);
+        }
+
+        let fee_vault = fee_vault.ok_or(ErrorCode::FeeVaultRequired)?;
+        self.transfer(fee_vault, fee_vault_amount)?;
+
+        Ok(fee_vault_amount - transfer_fee(&self.mint, fee_vault_amount)?)
+    }
+}
+
+// Accounts used to move a protocol fee paid in SOL, always signed by the payer
+pub struct SolFeeTransfer<'info> {
+    pub system_program: AccountInfo<'info>,
+    pub from: AccountInfo<'info>,
+}
+
+impl<'info> SolFeeTransfer<'info> {
+    pub fn transfer(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        system_program::transfer(
+            CpiContext::new(
+                self.system_program.clone(),
+                system_program::Transfer {
+                    from: self.from.clone(),
+                    to,
+                },
+            ),
+            amount,
+        )
+    }
+
+    /// Sends the share of the fee that goes to the referrer of the manager (if any) straight to its wallet, returns the amount sent
+    pub fn share_with_referrer(
+        &self,
+        manager: &Manager,
+        referrer_wallet: Option<AccountInfo<'info>>,
+        amount: u64,
+        share: u16,
+    ) -> Result<u64> {
+        if manager.referrer == Pubkey::default() {
+            return Ok(0);
+        }
+
+        let referrer_wallet = referrer_wallet.ok_or(ErrorCode::ReferrerWalletRequired)?;
+        let referral_amount = calculate_fee(amount, share)?;
+        self.transfer(referrer_wallet, referral_amount)?;
+
+        Ok(referral_amount)
+    }
+
+    /// Sends the fee to the config account (claimed with claim_sol_fees), or splits it between the fee schedule recipients wallets
+    /// Returns the amount that landed on the config account, the only part that can be refunded from it
+    pub fn distribute(
+        &self,
+        config: &Config,
+        config_account: AccountInfo<'info>,
+        fee_schedule: Option<&FeeSchedule>,
+        recipients: &[AccountInfo<'info>],
+        amount: u64,
+    ) -> Result<u64> {
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        if !config.fee_schedule_enabled {
+            self.transfer(config_account, amount)?;
+            return Ok(amount);
+        }
+
+        let fee_schedule = fee_schedule.ok_or(ErrorCode::FeeScheduleRequired)?;
+        if recipients.len() != fee_schedule.recipients.len() {
+            return Err(ErrorCode::InvalidRemainingAccounts.into());
+        }
+
+        let amounts = split_amount(amount, &fee_schedule.shares(), fee_schedule.dust_recipient as usize)?;
+
+        for ((recipient, account), amount) in fee_schedule.recipients.iter().zip(recipients).zip(amounts) {
+            if account.key() != recipient.wallet {
+                return Err(ErrorCode::IncorrectReceiver.into());
+            }
+
+            self.transfer(account.clone(), amount)?;
+        }
+
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculate_fee_for_period_prorates_the_rate() {
+        let year = SECONDS_PER_YEAR;
+        assert_eq!(calculate_fee_for_period(10_000, 100, year, year).unwrap(), 100);
+        assert_eq!(calculate_fee_for_period(10_000, 100, year / 2, year).unwrap(), 50);
+        assert_eq!(calculate_fee_for_period(10_000, 100, year / 4, year).unwrap(), 25);
+        assert_eq!(calculate_fee(1_000, 250).unwrap(), 25);
+    }
+
+    #[test]
+    fn calculate_fee_for_period_is_zero_without_amount_rate_or_time() {
+        assert_eq!(calculate_fee_for_period(0, 100, 10, 100).unwrap(), 0);
+        assert_eq!(calculate_fee_for_period(1_000, 0, 10, 100).unwrap(), 0);
+        assert_eq!(calculate_fee_for_period(1_000, 100, 0, 100).unwrap(), 0);
+    }
+
+    #[test]
+    fn split_amount_gives_the_dust_to_the_dust_recipient() {
+        assert_eq!(split_amount(1_000, &[5_000, 5_000], 0).unwrap(), vec![500, 500]);
+        assert_eq!(split_amount(1_001, &[3_333, 3_333, 3_334], 2).unwrap(), vec![333, 333, 335]);
+        assert_eq!(split_amount(1, &[5_000, 5_000], 1).unwrap(), vec![0, 1]);
+    }
+
+    #[test]
+    fn split_amount_rejects_invalid_shares() {
+        assert!(split_amount(1_000, &[5_000, 4_999], 0).is_err());
+        assert!(split_amount(1_000, &[5_000, 5_001], 0).is_err());
+        assert!(split_amount(1_000, &[10_000], 1).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    crate::events::SubscriptionCancelled,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+// signer: the manager authority (user-initiated) or the config authority (admin-approved), only the config authority
+// can refund a period used by a withdrawal at the subscribed rates
+// the refund is paid from the fee vault of the refund mint, or from the config account for payments in native SOL
+#[derive(Accounts)]
+pub struct CancelSubscription<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"manager".as_ref(),
+            manager.authority.as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = signer.key() == manager.authority || signer.key() == config.authority
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    #[account(
+        mut,
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    // receives the refund in native SOL
+    #[account(
+        mut,
+        constraint = authority.key() == manager.authority @ErrorCode::IncorrectSigner,
+    )]
+    pub authority: SystemAccount<'info>,
+    // always passed, releases the refundable amount of the manager
+    /// CHECK: refund reserve PDA of the refund mint, ignored while it is not initialized
+    #[account(
+        mut,
+        seeds = [
+            b"refund_reserve".as_ref(),
+            manager.refund_mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub refund_reserve: UncheckedAccount<'info>,
+
+    // only required when the refund is paid in a token mint
+    #[account(
+        constraint = refund_mint.key() == manager.refund_mint @ErrorCode::IncorrectMint,
+    )]
+    pub refund_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        seeds = [
+            b"fee_vault".as_ref(),
+            manager.refund_mint.as_ref(),
+        ],
+        bump,
+        token::authority = config,
+    )]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        constraint = authority_ata.owner == manager.authority @ErrorCode::IncorrectReceiver,
+        constraint = authority_ata.mint == manager.refund_mint @ErrorCode::IncorrectMint,
+    )]
+    pub authority_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn handler(ctx: Context<CancelSubscription>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let manager = &mut ctx.accounts.manager;
+
+    if !manager.is_subscribed(current_time) {
+        return Err(ErrorCode::NotSubscribed.into());
+    }
+
+    let refund_amount = manager.unused_refund(current_time)?;
+    let refund_mint = manager.refund_mint;
+
+    // Withdrawals at the subscribed rates used the period, refunding it would let the manager keep the discount for free
+    let is_used = manager.subscribed_withdrawal_at >= manager.refundable_start;
+    if refund_amount > 0 && is_used && ctx.accounts.signer.key() != ctx.accounts.config.authority {
+        return Err(ErrorCode::SubscriptionAlreadyUsed.into());
+    }
+
+    // The whole refundable amount leaves the reserve, the used part becomes claimable by the protocol
+    let refund_reserve_info = ctx.accounts.refund_reserve.to_account_info();
+    if let Some(mut refund_reserve) = load_if_initialized::<RefundReserve>(&refund_reserve_info)? {
+        refund_reserve.release(manager.refundable_amount);
+        store(&refund_reserve_info, &refund_reserve)?;
+    }
+
+    if refund_amount > 0 && refund_mint == Pubkey::default() {
+        // The config is owned by the program, so lamports can be moved without a system transfer
+        let config_info = ctx.accounts.config.to_account_info();
+        let rent_exempt_balance = Rent::get()?.minimum_balance(config_info.data_len());
+        if config_info.lamports().saturating_sub(rent_exempt_balance) < refund_amount {
+            return Err(ErrorCode::InsufficientRefundBalance.into());
+        }
+
+        config_info.sub_lamports(refund_amount)?;
+        ctx.accounts.authority.add_lamports(refund_amount)?;
+    } else if refund_amount > 0 {
+        let (
+            Some(refund_mint),
+            Some(fee_vault),
+            Some(authority_ata),
+            Some(token_program),
+        ) = (
+            &ctx.accounts.refund_mint,
+            &ctx.accounts.fee_vault,
+            &ctx.accounts.authority_ata,
+            &ctx.accounts.token_program,
+        ) else {
+            return Err(ErrorCode::RefundAccountsRequired.into());
+        };
+
+        if fee_vault.amount < refund_amount {
+            return Err(ErrorCode::InsufficientRefundBalance.into());
+        }
+
+        let seeds = &[
+            b"config".as_ref(),
+            &[ctx.accounts.config.bump],
+        ];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: fee_vault.to_account_info(),
+                    to: authority_ata.to_account_info(),
+                    authority: ctx.accounts.config.to_account_info(),
+                    mint: refund_mint.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            refund_amount,
+            refund_mint.decimals,
+        )?;
+    }
+
+    // Reset the subscription, the manager goes back to the non subscribed fee rates
+    let previous_end_subscription = manager.end_subscription;
+    manager.end_subscription = current_time;
+    manager.clear_refund();
+
+    emit!(SubscriptionCancelled {
+        manager: manager.key(),
+        signer: ctx.accounts.signer.key(),
+        mint: refund_mint,
+        refund_amount,
+        previous_end_subscription,
+        cancelled_at: current_time,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use {
+    crate::{error::ErrorCode, fees::{split_amount, FeeTransfer}, state::*},
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct ClaimFees<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"fee_vault".as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = config,
+        token::token_program = token_program,
+    )]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // always passed, the refundable subscription payments of the mint stay on the fee vault
+    /// CHECK: refund reserve PDA of the mint, ignored while it is not initialized
+    #[account(
+        seeds = [
+            b"refund_reserve".as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub refund_reserve: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// remaining accounts: token accounts of the receivers, in the same order as shares (basis points adding up to 10000)
+// rounding dust goes to the first receiver, followed by the extra accounts of the transfer hook of the mint (if any)
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimFees<'info>>, shares: Vec<u16>) -> Result<()> {
+    if shares.is_empty() || ctx.remaining_accounts.len() < shares.len() {
+        return Err(ErrorCode::InvalidRemainingAccounts.into());
+    }
+    let (receivers, extra_accounts) = ctx.remaining_accounts.split_at(shares.len());
+
+    let reserved = load_if_initialized::<RefundReserve>(&ctx.accounts.refund_reserve)?
+        .map_or(0, |refund_reserve| refund_reserve.amount);
+    let claimable = ctx.accounts.fee_vault.amount.saturating_sub(reserved);
+
+    let amounts = split_amount(claimable, &shares, 0)?;
+
+    let seeds = &[
+        b"config".as_ref(),
+        &[ctx.accounts.config.bump],
+    ];
+
+    let fee_transfer = FeeTransfer {
+        token_program: ctx.accounts.token_program.to_account_info(),
+        from: ctx.accounts.fee_vault.to_account_info(),
+        authority: ctx.accounts.config.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        decimals: ctx.accounts.mint.decimals,
+        signer_seeds: &[&seeds[..]],
+        extra_accounts,
+    };
+
+    for (receiver, amount) in receivers.iter().zip(amounts) {
+        let receiver_account = InterfaceAccount::<TokenAccount>::try_from(receiver)?;
+        if receiver_account.mint != ctx.accounts.mint.key() {
+            return Err(ErrorCode::IncorrectMint.into());
+        }
+
+        fee_transfer.transfer(receiver.to_account_info(), amount)?;
+    }
+
+    Ok(())
+}
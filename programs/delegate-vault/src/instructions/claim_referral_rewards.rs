@@ -0,0 +1,73 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    crate::token_extensions::transfer_checked_with_hook,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"referrer".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = referrer.bump,
+        constraint = referrer.wallet == signer.key() @ErrorCode::IncorrectSigner,
+    )]
+    pub referrer: Box<Account<'info, Referrer>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"referrer_vault".as_ref(),
+            referrer.key().as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = referrer,
+        token::token_program = token_program,
+    )]
+    pub referrer_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = signer,
+        token::token_program = token_program,
+    )]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// remaining accounts: extra accounts of the transfer hook of the mint (if any)
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimReferralRewards<'info>>) -> Result<()> {
+    let amount = ctx.accounts.referrer_vault.amount;
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let signer_key = ctx.accounts.signer.key();
+    let seeds = &[
+        b"referrer".as_ref(),
+        signer_key.as_ref(),
+        &[ctx.accounts.referrer.bump],
+    ];
+
+    transfer_checked_with_hook(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.referrer_vault.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.destination.to_account_info(),
+        ctx.accounts.referrer.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.mint.decimals,
+        &[&seeds[..]],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use {
+    crate::{error::ErrorCode, fees::split_amount, state::*},
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct ClaimSolFees<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    // always passed, the refundable subscription payments in SOL stay on the config account
+    /// CHECK: refund reserve PDA of native SOL, ignored while it is not initialized
+    #[account(
+        seeds = [
+            b"refund_reserve".as_ref(),
+            Pubkey::default().as_ref(),
+        ],
+        bump,
+    )]
+    pub refund_reserve: UncheckedAccount<'info>,
+}
+
+// remaining accounts: wallets of the receivers, in the same order as shares (basis points adding up to 10000)
+// claims the subscription payments in SOL held by the config account above its rent exempt balance and the refund reserve
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimSolFees<'info>>, shares: Vec<u16>) -> Result<()> {
+    if shares.is_empty() || ctx.remaining_accounts.len() != shares.len() {
+        return Err(ErrorCode::InvalidRemainingAccounts.into());
+    }
+
+    let config_info = ctx.accounts.config.to_account_info();
+    let rent_exempt_balance = Rent::get()?.minimum_balance(config_info.data_len());
+    let reserved = load_if_initialized::<RefundReserve>(&ctx.accounts.refund_reserve)?
+        .map_or(0, |refund_reserve| refund_reserve.amount);
+    let claimable = config_info.lamports()
+        .saturating_sub(rent_exempt_balance)
+        .saturating_sub(reserved);
+
+    let amounts = split_amount(claimable, &shares, 0)?;
+
+    // The config is owned by the program, so lamports can be moved without a system transfer
+    for (receiver, amount) in ctx.remaining_accounts.iter().zip(amounts) {
+        if amount == 0 {
+            continue;
+        }
+
+        config_info.sub_lamports(amount)?;
+        receiver.add_lamports(amount)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use {
+    crate::{error::ErrorCode, state::*},
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct ClearRestingOrder<'info> {
+    pub signer: Signer<'info>,
+    // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
+    pub id: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump,
+        constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+        constraint = order.resting_order != Pubkey::default() @ErrorCode::NoRestingOrder,
+    )]
+    pub order: Box<Account<'info, Order>>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            manager.authority.as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.delegate == signer.key() || manager.authority == signer.key() 
+            @ErrorCode::IncorrectSigner
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    /// CHECK: resting order recorded on the order, only its owner and data are read
+    #[account(address = order.resting_order @ErrorCode::InvalidRestingOrder)]
+    pub resting_order: UncheckedAccount<'info>,
+}
+
+// Forgets a resting order that no longer exists on its venue (filled and closed by the venue keepers, or expired and closed),
+// so the order can be liquidated or withdrawn again. An open resting order has to be cancelled with jup_cancel_resting_order
+pub fn handler(ctx: Context<ClearRestingOrder>) -> Result<()> {
+    let resting_order = &ctx.accounts.resting_order;
+    let order = &mut ctx.accounts.order;
+
+    if !resting_order.data_is_empty() && resting_order.owner == &order.resting_venue {
+        return Err(ErrorCode::RestingOrderOpen.into());
+    }
+
+    order.resting_order = Pubkey::default();
+    order.resting_venue = Pubkey::default();
+
+    Ok(())
+}
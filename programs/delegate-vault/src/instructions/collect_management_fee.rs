@@ -0,0 +1,136 @@
+use {
+    crate::{error::ErrorCode, fees::{accrue_management_fee, FeeTransfer}, state::*},
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        associated_token::AssociatedToken,
+        token_interface::{Mint, TokenAccount, TokenInterface},
+    },
+};
+
+// Permissionless crank, anyone can settle the management fee accrued by an order
+#[derive(Accounts)]
+pub struct CollectManagementFee<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
+    pub id: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump = order.bump,
+        constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            manager.authority.as_ref(),
+        ],
+        bump = manager.bump,
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"order_vault".as_ref(), 
+            manager.authority.as_ref(),
+            manager.key().as_ref(),
+            order.key().as_ref(),
+            deposit_mint.key().as_ref(),
+        ],
+        bump,
+        constraint = order_vault.owner == manager.key() @ErrorCode::IncorrectOwner,
+        constraint = order_vault.key() == order.order_vault @ErrorCode::IncorrectOrderVault,
+    )]
+    pub order_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [
+            b"fee_vault".as_ref(),
+            deposit_mint.key().as_ref(),
+        ],
+        bump,
+        token::mint = deposit_mint,
+        token::authority = config,
+        token::token_program = token_program,
+    )]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // only required when the config has the fee schedule enabled, recipients ATAs are passed as remaining accounts
+    #[account(
+        seeds = [b"fee_schedule".as_ref()],
+        bump = fee_schedule.bump,
+    )]
+    pub fee_schedule: Option<Box<Account<'info, FeeSchedule>>>,
+    #[account(
+        constraint = order.deposit_mint == deposit_mint.key() @ErrorCode::IncorrectMint
+    )]
+    pub deposit_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// remaining accounts: fee schedule recipients ATAs (if enabled), then the extra accounts of the transfer hook of the deposit mint (if any)
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, CollectManagementFee<'info>>) -> Result<()> {
+    let manager = &ctx.accounts.manager;
+    let seeds = &[
+        b"manager".as_ref(),
+        manager.authority.as_ref(),
+        &[manager.bump],
+    ];
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let order = &mut ctx.accounts.order;
+    accrue_management_fee(order, manager, &ctx.accounts.config, current_time)?;
+
+    // Only what is left in the order vault can be settled, the rest stays accrued until the position is back
+    let fee_amount = order.management_fee_accrued.min(ctx.accounts.order_vault.amount);
+    if fee_amount == 0 {
+        return Ok(());
+    }
+
+    order.management_fee_accrued -= fee_amount;
+
+    // Settled as the fees of withdraw, to the fee vault or split between the fee schedule recipients
+    let recipients_count = match &ctx.accounts.fee_schedule {
+        Some(fee_schedule) if ctx.accounts.config.fee_schedule_enabled => fee_schedule.recipients.len(),
+        _ => 0,
+    };
+    if ctx.remaining_accounts.len() < recipients_count {
+        return Err(ErrorCode::InvalidRemainingAccounts.into());
+    }
+    let (recipients, extra_accounts) = ctx.remaining_accounts.split_at(recipients_count);
+
+    let fee_transfer = FeeTransfer {
+        token_program: ctx.accounts.token_program.to_account_info(),
+        from: ctx.accounts.order_vault.to_account_info(),
+        authority: ctx.accounts.manager.to_account_info(),
+        mint: ctx.accounts.deposit_mint.to_account_info(),
+        decimals: ctx.accounts.deposit_mint.decimals,
+        signer_seeds: &[&seeds[..]],
+        extra_accounts,
+    };
+
+    fee_transfer.distribute(
+        &ctx.accounts.config,
+        ctx.accounts.fee_schedule.as_deref().map(|fee_schedule| &**fee_schedule),
+        ctx.accounts.fee_vault.as_ref().map(|fee_vault| fee_vault.to_account_info()),
+        recipients,
+        fee_amount,
+    )?;
+
+    Ok(())
+}
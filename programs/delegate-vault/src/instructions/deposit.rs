@@ -1,8 +1,9 @@
 use {
     crate::state::*,
     crate::error::ErrorCode,
+    crate::token_extensions::{check_mint_extensions, transfer_checked_with_hook},
     anchor_lang::prelude::*,
-    anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount, TransferChecked, transfer_checked},
+    anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount},
 };
 
 #[derive(Accounts)]
@@ -33,6 +34,25 @@ pub struct Deposit<'info> {
     )]
     pub manager: Box<Account<'info, Manager>>,
     pub deposit_mint: InterfaceAccount<'info, Mint>,
+    // allow-list entry of the transfer hook program of the mint, required when the mint has a transfer hook
+    #[account(
+        seeds = [
+            b"hook_program".as_ref(),
+            hook_program.program_id.as_ref(),
+        ],
+        bump = hook_program.bump,
+    )]
+    pub hook_program: Option<Box<Account<'info, HookProgram>>>,
+    #[account(
+        mut,
+        seeds = [
+            b"mint_policy".as_ref(),
+            deposit_mint.key().as_ref(),
+        ],
+        bump = mint_policy.bump,
+        constraint = mint_policy.allow_deposit @ErrorCode::MintNotAllowed,
+    )]
+    pub mint_policy: Box<Account<'info, MintPolicy>>,
     #[account(
         mut,
         associated_token::mint=deposit_mint,
@@ -61,28 +81,51 @@ pub struct Deposit<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler<'info>(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+// remaining accounts: extra accounts of the transfer hook of the deposit mint (if any)
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>, amount: u64) -> Result<()> {
+    check_mint_extensions(&ctx.accounts.deposit_mint.to_account_info(), ctx.accounts.hook_program.as_deref().map(|hook_program| &**hook_program))?;
+
+    if amount < ctx.accounts.mint_policy.min_deposit {
+        return Err(ErrorCode::DepositTooSmall.into());
+    }
+
     let order = &mut ctx.accounts.order;
     order.id = ctx.accounts.id.key();
     order.manager = ctx.accounts.manager.key();
     order.deposit_mint = ctx.accounts.deposit_mint.key();
     order.order_vault = ctx.accounts.order_vault.key();
-    order.deposit_amount = amount;
     order.bump = ctx.bumps.order;
+    order.chunk_max_amount = 0; // Chunked liquidation disabled by default
+    order.chunk_interval = 0;
+    order.last_chunk_at = 0;
+    order.bracket = Bracket::default(); // No SL/TP attached by default
+    order.grid = Grid::default(); // Grid trading disabled by default
+    let current_time = Clock::get()?.unix_timestamp;
+    order.created_at = current_time;
+    order.management_fee_accrued = 0;
+    order.last_accrual_at = current_time; // Management fee accrues from the order creation
+    order.max_slippage_bps = Order::DEFAULT_MAX_SLIPPAGE_BPS;
+    order.resting_order = Pubkey::default(); // No resting order placed by default
+    order.resting_venue = Pubkey::default();
 
-    transfer_checked(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(), 
-            TransferChecked {
-                from: ctx.accounts.user_ata.to_account_info(),
-                to: ctx.accounts.order_vault.to_account_info(),
-                authority: ctx.accounts.signer.to_account_info(),
-                mint: ctx.accounts.deposit_mint.to_account_info(),
-            },
-        ),
+    let vault_amount_before = ctx.accounts.order_vault.amount;
+    transfer_checked_with_hook(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.user_ata.to_account_info(),
+        ctx.accounts.deposit_mint.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.remaining_accounts,
         amount,
         ctx.accounts.deposit_mint.decimals,
+        &[],
     )?;
 
+    // Record the amount actually received, Token-2022 mints with transfer fee deliver less than amount
+    ctx.accounts.order_vault.reload()?;
+    let received_amount = ctx.accounts.order_vault.amount - vault_amount_before;
+    ctx.accounts.order.deposit_amount = received_amount;
+    ctx.accounts.mint_policy.add_deposit(&mut ctx.accounts.order, received_amount)?;
+
     Ok(())
 }
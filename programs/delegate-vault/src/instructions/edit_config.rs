@@ -4,6 +4,18 @@ use {
     anchor_lang::prelude::*,
 };
 
+// Only the provided values are updated
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct EditConfigParams {
+    performance_fee: Option<u16>,
+    management_fee: Option<u16>,
+    subscribed_management_fee: Option<u16>,
+    high_water_mark: Option<bool>,
+    referral_fee_share: Option<u16>,
+    referral_subscription_share: Option<u16>,
+    promo_signer: Option<Pubkey>,
+}
+
 #[derive(Accounts)]
 pub struct EditConfig<'info> {
     #[account(mut)]
@@ -15,20 +27,56 @@ pub struct EditConfig<'info> {
         ],
         bump = config.bump,
         constraint = signer.key() == config.authority 
-            @ ErrorCode::IncorrectSigner,
+            @ErrorCode::IncorrectSigner,
     )]
     pub config: Box<Account<'info, Config>>,
 }
 
-pub fn handler<'info>(
+pub fn handler(
     ctx: Context<EditConfig>, 
-    performance_fee: u16,
+    params: EditConfigParams,
 ) -> Result<()> {
-    if performance_fee > 10000 {
-        return Err(ErrorCode::IncorrectFee.into());
+    let config = &mut ctx.accounts.config;
+
+    for fee in [
+        params.performance_fee,
+        params.management_fee,
+        params.subscribed_management_fee,
+        params.referral_fee_share,
+        params.referral_subscription_share,
+    ].into_iter().flatten() {
+        if fee > 10000 {
+            return Err(ErrorCode::IncorrectFee.into());
+        }
     }
 
-    (*ctx.accounts.config).performance_fee = performance_fee;
+    if let Some(performance_fee) = params.performance_fee {
+        config.performance_fee = performance_fee;
+    }
+
+    if let Some(management_fee) = params.management_fee {
+        config.management_fee = management_fee;
+    }
+
+    if let Some(subscribed_management_fee) = params.subscribed_management_fee {
+        config.subscribed_management_fee = subscribed_management_fee;
+    }
+
+    if let Some(high_water_mark) = params.high_water_mark {
+        config.high_water_mark = high_water_mark;
+    }
+
+    if let Some(referral_fee_share) = params.referral_fee_share {
+        config.referral_fee_share = referral_fee_share;
+    }
+
+    if let Some(referral_subscription_share) = params.referral_subscription_share {
+        config.referral_subscription_share = referral_subscription_share;
+    }
+
+    if let Some(promo_signer) = params.promo_signer {
+        config.promo_signer = promo_signer;
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
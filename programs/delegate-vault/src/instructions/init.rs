@@ -2,15 +2,17 @@ use {
     crate::state::*,
     anchor_lang::prelude::*,
     crate::error::ErrorCode,
-    anchor_spl::token_interface::{Mint, TokenInterface},
 };
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct InitParams {
-    subscribed_performance_fee: u16,
     performance_fee: u16,
-    monthly_amount: u64,
-    yearly_amount: u64,
+    subscribed_management_fee: u16,
+    management_fee: u16,
+    high_water_mark: bool,
+    referral_fee_share: u16,
+    referral_subscription_share: u16,
+    promo_signer: Pubkey,
 }
 
 #[derive(Accounts)]
@@ -25,32 +27,34 @@ pub struct Init<'info> {
         bump,
     )]
     pub config: Box<Account<'info, Config>>,
-    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
-    /// CHECK: initializing config with a payment receiver
-    pub payment_receiver: AccountInfo<'info>,
-    /// CHECK: initializing config with a performance receiver
-    pub performance_receiver: AccountInfo<'info>,
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
-    pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn handler<'info>(ctx: Context<Init>, params: InitParams) -> Result<()> {
-    if params.performance_fee > 10000 || params.subscribed_performance_fee > 10000 {
+pub fn handler(ctx: Context<Init>, params: InitParams) -> Result<()> {
+    if params.performance_fee > 10000 {
+        return Err(ErrorCode::IncorrectFee.into());
+    }
+
+    if params.management_fee > 10000 || params.subscribed_management_fee > 10000 {
+        return Err(ErrorCode::IncorrectFee.into());
+    }
+
+    if params.referral_fee_share > 10000 || params.referral_subscription_share > 10000 {
         return Err(ErrorCode::IncorrectFee.into());
     }
 
     let config = &mut ctx.accounts.config;
 
     config.authority = ctx.accounts.signer.key();
-    config.payment_mint = ctx.accounts.payment_mint.key();
-    config.payment_receiver = ctx.accounts.payment_receiver.key();
-    config.performance_receiver = ctx.accounts.performance_receiver.key();
-    config.monthly_amount = params.monthly_amount;
-    config.yearly_amount = params.yearly_amount;
-    config.subscribed_performance_fee = params.subscribed_performance_fee;
     config.performance_fee = params.performance_fee;
     config.bump = ctx.bumps.config;
+    config.subscribed_management_fee = params.subscribed_management_fee;
+    config.management_fee = params.management_fee;
+    config.high_water_mark = params.high_water_mark;
+    config.referral_fee_share = params.referral_fee_share;
+    config.referral_subscription_share = params.referral_subscription_share;
+    config.promo_signer = params.promo_signer;
     
     Ok(())
 }
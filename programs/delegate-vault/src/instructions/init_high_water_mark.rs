@@ -0,0 +1,47 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::Mint,
+};
+
+#[derive(Accounts)]
+pub struct InitHighWaterMark<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.authority == signer.key() @ErrorCode::IncorrectSigner
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init,
+        space = HighWaterMark::LEN,
+        payer = signer,
+        seeds = [
+            b"high_water_mark".as_ref(),
+            manager.key().as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub high_water_mark: Box<Account<'info, HighWaterMark>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitHighWaterMark>) -> Result<()> {
+    let high_water_mark = &mut ctx.accounts.high_water_mark;
+
+    high_water_mark.manager = ctx.accounts.manager.key();
+    high_water_mark.mint = ctx.accounts.mint.key();
+    high_water_mark.cumulative_pnl = 0;
+    high_water_mark.high_water_mark = 0;
+    high_water_mark.bump = ctx.bumps.high_water_mark;
+
+    Ok(())
+}
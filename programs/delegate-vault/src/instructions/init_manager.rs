@@ -1,5 +1,6 @@
 use {
     crate::state::*,
+    crate::error::ErrorCode,
     anchor_lang::prelude::*,
 };
 
@@ -20,17 +21,35 @@ pub struct InitManager<'info> {
         bump,
     )]
     pub manager: Box<Account<'info, Manager>>,
+    // optional referrer bound to the manager, it can also be set later with set_referrer
+    #[account(
+        seeds = [
+            b"referrer".as_ref(),
+            referrer.wallet.as_ref(),
+        ],
+        bump = referrer.bump,
+        constraint = referrer.wallet != signer.key() @ErrorCode::InvalidReferrer,
+    )]
+    pub referrer: Option<Box<Account<'info, Referrer>>>,
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler<'info>(ctx: Context<InitManager>) -> Result<()> {
+pub fn handler(ctx: Context<InitManager>) -> Result<()> {
     let manager = &mut ctx.accounts.manager;
 
     manager.authority = ctx.accounts.signer.key();
     manager.delegate = ctx.accounts.delegate.key();
     manager.end_subscription = 0; // Initialize with no subscription
+    manager.subscription_performance_fee = 0;
+    manager.clear_refund();
+    manager.auto_renew_plan = Pubkey::default();
+    manager.subscribed_withdrawal_at = 0;
     manager.bump = ctx.bumps.manager;
+    manager.referrer = ctx.accounts.referrer
+        .as_ref()
+        .map(|referrer| referrer.key())
+        .unwrap_or_default();
     
     Ok(())
 }
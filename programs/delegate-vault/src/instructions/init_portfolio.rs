@@ -0,0 +1,122 @@
+use {
+    crate::{error::ErrorCode, oracle::PYTH_RECEIVER_PROGRAM, state::*},
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{Mint, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct InitPortfolio<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
+    pub id: SystemAccount<'info>,
+    #[account(
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump = order.bump,
+        constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+    )]
+    pub order: Box<Account<'info, Order>>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.authority == signer.key() @ErrorCode::IncorrectSigner // only user can set the portfolio
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    #[account(
+        init,
+        payer = signer,
+        space = Portfolio::LEN,
+        seeds = [
+            b"portfolio".as_ref(),
+            order.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub portfolio: Box<Account<'info, Portfolio>>,
+    pub system_program: Program<'info, System>,
+}
+
+// remaining accounts: [vault, mint, price_feed] for each asset, in the same order as target_weights
+// the first vault has to be the order vault, the rest token vaults of the order created with init_token_vault
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, InitPortfolio<'info>>, target_weights: Vec<u16>) -> Result<()> {
+    let assets_len = target_weights.len();
+    if !(2..=Portfolio::MAX_ASSETS).contains(&assets_len) {
+        return Err(ErrorCode::InvalidPortfolio.into());
+    }
+
+    if ctx.remaining_accounts.len() != assets_len * 3 {
+        return Err(ErrorCode::InvalidRemainingAccounts.into());
+    }
+
+    let total_weight = target_weights.iter().map(|weight| *weight as u32).sum::<u32>();
+    if total_weight != 10000 {
+        return Err(ErrorCode::InvalidPortfolio.into());
+    }
+
+    let signer_key = ctx.accounts.signer.key();
+    let manager_key = ctx.accounts.manager.key();
+    let order_key = ctx.accounts.order.key();
+    let mut assets: Vec<PortfolioAsset> = Vec::with_capacity(assets_len);
+
+    for (index, accounts) in ctx.remaining_accounts.chunks(3).enumerate() {
+        let vault = InterfaceAccount::<TokenAccount>::try_from(&accounts[0])?;
+        let mint = InterfaceAccount::<Mint>::try_from(&accounts[1])?;
+        let price_feed = &accounts[2];
+
+        if vault.owner != manager_key {
+            return Err(ErrorCode::IncorrectOwner.into());
+        }
+
+        if vault.mint != mint.key() {
+            return Err(ErrorCode::IncorrectMint.into());
+        }
+
+        if price_feed.owner != &PYTH_RECEIVER_PROGRAM {
+            return Err(ErrorCode::InvalidPriceFeed.into());
+        }
+
+        if assets.iter().any(|asset| asset.mint == mint.key()) {
+            return Err(ErrorCode::DuplicateMints.into());
+        }
+
+        let expected_vault = if index == 0 {
+            ctx.accounts.order.order_vault
+        } else {
+            Pubkey::find_program_address(
+                &[
+                    b"token_vault".as_ref(),
+                    signer_key.as_ref(),
+                    manager_key.as_ref(),
+                    order_key.as_ref(),
+                    mint.key().as_ref(),
+                ],
+                ctx.program_id,
+            ).0
+        };
+        if vault.key() != expected_vault {
+            return Err(ErrorCode::IncorrectOrderVault.into());
+        }
+
+        assets.push(PortfolioAsset {
+            vault: vault.key(),
+            mint: mint.key(),
+            price_feed: price_feed.key(),
+            decimals: mint.decimals,
+            target_weight: target_weights[index],
+        });
+    }
+
+    let portfolio = &mut ctx.accounts.portfolio;
+    portfolio.order = order_key;
+    portfolio.assets = assets;
+    portfolio.bump = ctx.bumps.portfolio;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use {
+    crate::state::*,
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct InitReferrer<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        init,
+        space = Referrer::LEN,
+        payer = signer,
+        seeds = [
+            b"referrer".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub referrer: Box<Account<'info, Referrer>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitReferrer>) -> Result<()> {
+    let referrer = &mut ctx.accounts.referrer;
+
+    referrer.wallet = ctx.accounts.signer.key();
+    referrer.bump = ctx.bumps.referrer;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use {
+    crate::state::*,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount},
+};
+
+// Anyone can create the vault of a referrer for a mint, it has to exist before fees of that mint are shared with the referrer
+#[derive(Accounts)]
+pub struct InitReferrerVault<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"referrer".as_ref(),
+            referrer.wallet.as_ref(),
+        ],
+        bump = referrer.bump,
+    )]
+    pub referrer: Box<Account<'info, Referrer>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init,
+        payer = signer,
+        seeds = [
+            b"referrer_vault".as_ref(),
+            referrer.key().as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = referrer,
+        token::token_program = token_program,
+    )]
+    pub referrer_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(_ctx: Context<InitReferrerVault>) -> Result<()> {
+    Ok(())
+}
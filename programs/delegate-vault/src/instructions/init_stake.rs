@@ -0,0 +1,66 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct InitStake<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    #[account(
+        seeds = [b"discount_config".as_ref()],
+        bump = discount_config.bump,
+    )]
+    pub discount_config: Box<Account<'info, DiscountConfig>>,
+    #[account(
+        constraint = mint.key() == discount_config.mint @ErrorCode::IncorrectMint,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init,
+        space = Stake::LEN,
+        payer = signer,
+        seeds = [
+            b"stake".as_ref(),
+            manager.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub stake: Box<Account<'info, Stake>>,
+    #[account(
+        init,
+        payer = signer,
+        seeds = [
+            b"stake_vault".as_ref(),
+            manager.key().as_ref(),
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = stake,
+        token::token_program = token_program,
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitStake>) -> Result<()> {
+    let stake = &mut ctx.accounts.stake;
+
+    stake.manager = ctx.accounts.manager.key();
+    stake.amount = 0;
+    stake.unlock_at = 0;
+    stake.bump = ctx.bumps.stake;
+
+    Ok(())
+}
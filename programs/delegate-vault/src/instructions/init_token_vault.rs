@@ -1,6 +1,7 @@
 use {
     crate::state::*,
     crate::error::ErrorCode,
+    crate::token_extensions::check_mint_extensions,
     anchor_lang::prelude::*,
     anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount},
 };
@@ -32,6 +33,24 @@ pub struct InitTokenVault<'info> {
     pub manager: Box<Account<'info, Manager>>,
 
     pub mint: Box<InterfaceAccount<'info, Mint>>,
+    // allow-list entry of the transfer hook program of the mint, required when the mint has a transfer hook
+    #[account(
+        seeds = [
+            b"hook_program".as_ref(),
+            hook_program.program_id.as_ref(),
+        ],
+        bump = hook_program.bump,
+    )]
+    pub hook_program: Option<Box<Account<'info, HookProgram>>>,
+    #[account(
+        seeds = [
+            b"mint_policy".as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump = mint_policy.bump,
+        constraint = mint_policy.allow_target @ErrorCode::MintNotAllowed,
+    )]
+    pub mint_policy: Box<Account<'info, MintPolicy>>,
 
     #[account(
         init,
@@ -54,6 +73,6 @@ pub struct InitTokenVault<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler<'info>(_ctx: Context<InitTokenVault>) -> Result<()> {
-    Ok(())
+pub fn handler(ctx: Context<InitTokenVault>) -> Result<()> {
+    check_mint_extensions(&ctx.accounts.mint.to_account_info(), ctx.accounts.hook_program.as_deref().map(|hook_program| &**hook_program))
 }
@@ -0,0 +1,138 @@
+use {
+    crate::{error::ErrorCode, permission::{route_account_key, token_vault_address, verify_route_accounts}, state::*},
+    anchor_lang::{prelude::*, solana_program::{instruction::Instruction, program::invoke_signed}},
+    anchor_spl::token_interface::TokenAccount,
+};
+
+#[derive(Accounts)]
+pub struct CancelRestingOrder<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
+    pub id: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump,
+        constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+        constraint = order.resting_order != Pubkey::default() @ErrorCode::NoRestingOrder,
+    )]
+    pub order: Box<Account<'info, Order>>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            manager.authority.as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.delegate == signer.key() || manager.authority == signer.key() 
+            @ErrorCode::IncorrectSigner
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    // token vault of the order filled by the resting order, closing a DCA order sends it the output bought so far
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // venue where the resting order was placed, the route has to be one of its cancel routes
+    #[account(
+        seeds = [
+            b"swap_venue".as_ref(),
+            swap_venue.program_id.as_ref(),
+        ],
+        bump = swap_venue.bump,
+        constraint = swap_venue.program_id == order.resting_venue @ErrorCode::InvalidRestingOrder,
+    )]
+    pub swap_venue: Box<Account<'info, SwapVenue>>,
+    /// CHECK: program of the swap venue
+    #[account(executable, address = swap_venue.program_id @ErrorCode::InvalidSwapVenue)]
+    pub swap_program: UncheckedAccount<'info>,
+    // programs the routes of the venue can reference besides its own one, not required if the route only references the venue program
+    #[account(
+        seeds = [
+            b"venue_programs".as_ref(),
+            swap_venue.key().as_ref(),
+        ],
+        bump = venue_programs.bump,
+    )]
+    pub venue_programs: Option<Box<Account<'info, VenuePrograms>>>,
+}
+
+// Cancels the resting order of the order, the unfilled input is refunded to the order vault
+// The delegate can cancel it too, so the order can be liquidated
+pub fn handler(ctx: Context<CancelRestingOrder>, data: Vec<u8>) -> Result<()> {
+    let manager = &ctx.accounts.manager;
+    let order = &ctx.accounts.order;
+
+    // Look up the route on the venue registry
+    let route = ctx.accounts.swap_venue.route(&data, RouteKind::CancelRestingOrder)?;
+
+    let transfer_authority = &route_account_key(ctx.remaining_accounts, route.transfer_authority_idx)?;
+    let destination_token_account = &route_account_key(ctx.remaining_accounts, route.destination_idx)?;
+    let resting_order = &route_account_key(ctx.remaining_accounts, route.resting_order_idx)?;
+
+    // Validate that the maker is the manager
+    if transfer_authority != &manager.key() {
+        return Err(ErrorCode::InvalidTransferAuthority.into());
+    }
+
+    if resting_order != &order.resting_order {
+        return Err(ErrorCode::InvalidRestingOrder.into());
+    }
+
+    // The unfilled input goes back to the order vault
+    if destination_token_account != &order.order_vault {
+        return Err(ErrorCode::InvalidDestinationTokenAccount.into());
+    }
+
+    let token_vault = &ctx.accounts.token_vault;
+    if token_vault.key() != token_vault_address(manager, &order.key(), &token_vault.mint) {
+        return Err(ErrorCode::IncorrectTokenVault.into());
+    }
+
+    // No other manager vault or unexpected program can be reached with the manager signature
+    let allowed_vaults = [order.order_vault, token_vault.key()];
+    verify_route_accounts(ctx.remaining_accounts, &manager.key(), &allowed_vaults, &ctx.accounts.swap_venue, ctx.accounts.venue_programs.as_deref().map(|venue_programs| &**venue_programs))?;
+
+    // make sure the manager is signer in the transaction
+    let accounts: Vec<AccountMeta> = ctx
+        .remaining_accounts
+        .iter()
+        .map(|acc| {
+            let is_signer = acc.key == &ctx.accounts.manager.key() || acc.is_signer;
+            AccountMeta {
+                pubkey: *acc.key,
+                is_signer,
+                is_writable: acc.is_writable,
+            }
+        })
+        .collect();
+
+    let accounts_infos: Vec<AccountInfo> = ctx
+        .remaining_accounts
+        .iter()
+        .map(|acc| AccountInfo { ..acc.clone() })
+        .collect();
+
+    let signer_seeds = &[
+        b"manager".as_ref(),
+        manager.authority.as_ref(),
+        &[manager.bump],
+    ];
+
+    invoke_signed(
+        &Instruction {
+            program_id: ctx.accounts.swap_program.key(),
+            accounts,
+            data,
+        },
+        &accounts_infos,
+        &[&signer_seeds[..]],
+    )?;
+
+    let order = &mut ctx.accounts.order;
+    order.resting_order = Pubkey::default();
+    order.resting_venue = Pubkey::default();
+
+    Ok(())
+}
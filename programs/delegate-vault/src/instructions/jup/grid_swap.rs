@@ -0,0 +1,224 @@
+use {
+    crate::{error::ErrorCode, math::{execution_price, PRICE_PRECISION}, permission::{route_account_key, verify_route_accounts, verify_route_args, verify_order_vaults, verify_permission}, state::*, token_extensions::gross_balance},
+    anchor_lang::{prelude::*, solana_program::{instruction::Instruction, program::invoke_signed}},
+    anchor_spl::token_interface::TokenAccount,
+};
+
+#[derive(Accounts)]
+pub struct JupGridSwap<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
+    pub id: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump,
+        constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+    )]
+    pub order: Box<Account<'info, Order>>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            manager.authority.as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.delegate == signer.key() || manager.authority == signer.key() 
+            @ErrorCode::IncorrectSigner
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+
+    #[account(
+        mut,
+        constraint = manager_vault_a.owner == manager.key() @ErrorCode::IncorrectManager
+    )]
+    pub manager_vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = manager_vault_b.owner == manager.key() @ErrorCode::IncorrectManager
+    )]
+    pub manager_vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // registered venue of the route, the route discriminator has to be one of its routes
+    #[account(
+        seeds = [
+            b"swap_venue".as_ref(),
+            swap_venue.program_id.as_ref(),
+        ],
+        bump = swap_venue.bump,
+    )]
+    pub swap_venue: Box<Account<'info, SwapVenue>>,
+    /// CHECK: program of the swap venue
+    #[account(executable, address = swap_venue.program_id @ErrorCode::InvalidSwapVenue)]
+    pub swap_program: UncheckedAccount<'info>,
+    // programs the routes of the venue can reference besides its own one, not required if the route only references the venue program
+    #[account(
+        seeds = [
+            b"venue_programs".as_ref(),
+            swap_venue.key().as_ref(),
+        ],
+        bump = venue_programs.bump,
+    )]
+    pub venue_programs: Option<Box<Account<'info, VenuePrograms>>>,
+}
+
+// level: grid level crossed by the swap, buying fills the level and selling one level above releases it
+pub fn handler(ctx: Context<JupGridSwap>, level: u8, data: Vec<u8>) -> Result<()> {
+    let manager = &ctx.accounts.manager;
+    let signer = &ctx.accounts.signer;
+    let deposit_mint = &ctx.accounts.order.deposit_mint;
+    let manager_vault_a = &ctx.accounts.manager_vault_a;
+    let manager_vault_b = &ctx.accounts.manager_vault_b;
+    let order = &ctx.accounts.order;
+
+    if !order.grid.is_enabled() {
+        return Err(ErrorCode::GridNotEnabled.into());
+    }
+
+    let (deposit_vault, token_vault) = if manager_vault_a.mint == *deposit_mint {
+        (&manager_vault_a, &manager_vault_b)
+    } else if manager_vault_b.mint == *deposit_mint {
+        (&manager_vault_b, &manager_vault_a)
+    } else {
+        return Err(ErrorCode::IncorrectMint.into());
+    };
+    let token_vault_is_a = manager_vault_a.mint != *deposit_mint;
+
+    verify_order_vaults(deposit_vault, token_vault, manager, order)?;
+
+    // The delegate is allowed here as the grid bounds the swaps it can do
+    verify_permission(signer, deposit_vault, token_vault, manager, true)?;
+
+    // Look up the route on the venue registry
+    let route = ctx.accounts.swap_venue.route(&data, RouteKind::Swap)?;
+
+    let transfer_authority = &route_account_key(ctx.remaining_accounts, route.transfer_authority_idx)?;
+    let source_token_account = &route_account_key(ctx.remaining_accounts, route.source_idx)?;
+    let destination_token_account = &route_account_key(ctx.remaining_accounts, route.destination_idx)?;
+
+    // Validate that transfer authority is the manager
+    if transfer_authority != &manager.key() {
+        return Err(ErrorCode::InvalidTransferAuthority.into());
+    }
+
+    // Grid swaps always go from one order vault to the other, buying spends the deposit vault and selling the token vault
+    let is_buy = if source_token_account == &deposit_vault.key() {
+        if destination_token_account != &token_vault.key() {
+            return Err(ErrorCode::InvalidDestinationTokenAccount.into());
+        }
+        true
+    } else if source_token_account == &token_vault.key() {
+        if destination_token_account != &deposit_vault.key() {
+            return Err(ErrorCode::InvalidDestinationTokenAccount.into());
+        }
+        false
+    } else {
+        return Err(ErrorCode::InvalidSourceTokenAccount.into());
+    };
+
+    // Enforce the order policy on the decoded route args
+    verify_route_args(route, &data, ctx.remaining_accounts, order.max_slippage_bps)?;
+
+    // No other manager vault or unexpected program can be reached with the manager signature
+    let allowed_vaults = [manager_vault_a.key(), manager_vault_b.key()];
+    verify_route_accounts(ctx.remaining_accounts, &manager.key(), &allowed_vaults, &ctx.accounts.swap_venue, ctx.accounts.venue_programs.as_deref().map(|venue_programs| &**venue_programs))?;
+
+    let accounts: Vec<AccountMeta> = ctx
+        .remaining_accounts
+        .iter()
+        .map(|acc| {
+            let is_signer = acc.key == &ctx.accounts.manager.key();
+            AccountMeta {
+                pubkey: *acc.key,
+                is_signer,
+                is_writable: acc.is_writable,
+            }
+        })
+        .collect();
+
+    let accounts_infos: Vec<AccountInfo> = ctx
+        .remaining_accounts
+        .iter()
+        .map(|acc| AccountInfo { ..acc.clone() })
+        .collect();
+
+    let signer_seeds = &[
+        b"manager".as_ref(),
+        manager.authority.as_ref(),
+        &[manager.bump],
+    ];
+
+    let token_amount_before = gross_balance(token_vault)?;
+    let deposit_amount_before = gross_balance(deposit_vault)?;
+
+    invoke_signed(
+        &Instruction {
+            program_id: ctx.accounts.swap_program.key(),
+            accounts,
+            data,
+        },
+        &accounts_infos,
+        &[&signer_seeds[..]],
+    )?;
+
+    ctx.accounts.manager_vault_a.reload()?;
+    ctx.accounts.manager_vault_b.reload()?;
+    let (deposit_vault, token_vault) = if token_vault_is_a {
+        (&ctx.accounts.manager_vault_b, &ctx.accounts.manager_vault_a)
+    } else {
+        (&ctx.accounts.manager_vault_a, &ctx.accounts.manager_vault_b)
+    };
+    let token_amount_after = gross_balance(token_vault)?;
+    let deposit_amount_after = gross_balance(deposit_vault)?;
+
+    let grid = &mut ctx.accounts.order.grid;
+    let level_price = grid.level_price(level).ok_or(ErrorCode::InvalidGridSwap)?;
+
+    if is_buy {
+        // Buy at most level_size at or below the level price, the level stays filled until it is sold one level above
+        // so the top level can not be bought, there is no level above it to sell at
+        if level >= grid.count - 1 || grid.is_filled(level) {
+            return Err(ErrorCode::InvalidGridSwap.into());
+        }
+
+        let spent_amount = deposit_amount_before.saturating_sub(deposit_amount_after);
+        let bought_amount = token_amount_after.saturating_sub(token_amount_before);
+        if spent_amount > grid.level_size || execution_price(spent_amount, bought_amount)? > level_price {
+            return Err(ErrorCode::InvalidGridSwap.into());
+        }
+
+        grid.filled |= 1u64 << level;
+    } else {
+        // Sell what was bought at the level, at or above the price of the next level
+        if !grid.is_filled(level) {
+            return Err(ErrorCode::InvalidGridSwap.into());
+        }
+
+        let sell_price = grid
+            .level_price(level.checked_add(1).ok_or(ErrorCode::InvalidGridSwap)?)
+            .ok_or(ErrorCode::InvalidGridSwap)?;
+        let sold_amount = token_amount_before.saturating_sub(token_amount_after);
+        let received_amount = deposit_amount_after.saturating_sub(deposit_amount_before);
+        if execution_price(received_amount, sold_amount)? < sell_price {
+            return Err(ErrorCode::InvalidGridSwap.into());
+        }
+
+        // Max amount of tokens bought at the level: level_size / level_price
+        let max_sold_amount = (grid.level_size as u128)
+            .checked_mul(PRICE_PRECISION)
+            .ok_or(ErrorCode::NumericalOverflow)?
+            .checked_div(level_price as u128)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+        if sold_amount as u128 > max_sold_amount {
+            return Err(ErrorCode::InvalidGridSwap.into());
+        }
+
+        grid.filled &= !(1u64 << level);
+    }
+
+    Ok(())
+}
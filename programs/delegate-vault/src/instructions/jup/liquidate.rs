@@ -1,9 +1,19 @@
 use {
-    crate::{error::ErrorCode, jupiter_aggregator::program::Jupiter, permission::{verify_permission, is_jupiter_instruction, JUPITER_SHARED_ACCOUNTS_ROUTE_DISCRIMINATOR, JUPITER_SHARED_ACCOUNTS_ROUTE_WITH_TOKEN_LEDGER_DISCRIMINATOR, JUPITER_SHARED_ACCOUNTS_EXACT_OUT_ROUTE_DISCRIMINATOR}, state::*},
+    crate::{error::ErrorCode, math::{execution_price, min_execution_price}, oracle::{exchange_price, get_mint_price}, permission::{route_account_key, verify_route_accounts, verify_route_args, verify_order_vaults, verify_permission, token_vault_address}, state::*, token_extensions::{gross_balance, harvest_withheld_fees}},
     anchor_lang::{prelude::*, solana_program::{instruction::Instruction, program::invoke_signed}},
-    anchor_spl::token_interface::{close_account, CloseAccount, TokenAccount, TokenInterface},
+    anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface},
 };
 
+// A swap of the liquidation path
+// data: route instruction data, accounts_len: number of remaining accounts of the route, taken in order after the previous leg ones
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RouteLeg {
+    pub data: Vec<u8>,
+    pub accounts_len: u8,
+}
+
+pub const MAX_ROUTE_LEGS: usize = 3;
+
 #[derive(Accounts)]
 pub struct JupLiquidate<'info> {
     #[account(mut)]
@@ -13,6 +23,7 @@ pub struct JupLiquidate<'info> {
     // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
     pub id: SystemAccount<'info>,
     #[account(
+        mut,
         seeds = [
             b"order".as_ref(),
             manager.key().as_ref(),
@@ -20,6 +31,7 @@ pub struct JupLiquidate<'info> {
         ],
         bump,
         constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+        constraint = order.resting_order == Pubkey::default() @ErrorCode::RestingOrderOpen,
     )]
     pub order: Box<Account<'info, Order>>,
     #[account(
@@ -44,12 +56,60 @@ pub struct JupLiquidate<'info> {
         constraint = manager_vault_b.owner == manager.key() @ErrorCode::IncorrectManager
     )]
     pub manager_vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    // mint of the token vault, mutable to harvest the Token-2022 transfer fees withheld on the vault before closing it
+    #[account(mut)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    pub jupiter_program: Program<'info, Jupiter>,
+    // registered venue of the route, the route discriminator has to be one of its routes
+    #[account(
+        seeds = [
+            b"swap_venue".as_ref(),
+            swap_venue.program_id.as_ref(),
+        ],
+        bump = swap_venue.bump,
+    )]
+    pub swap_venue: Box<Account<'info, SwapVenue>>,
+    /// CHECK: program of the swap venue
+    #[account(executable, address = swap_venue.program_id @ErrorCode::InvalidSwapVenue)]
+    pub swap_program: UncheckedAccount<'info>,
+    // programs the routes of the venue can reference besides its own one, not required if the route only references the venue program
+    #[account(
+        seeds = [
+            b"venue_programs".as_ref(),
+            swap_venue.key().as_ref(),
+        ],
+        bump = venue_programs.bump,
+    )]
+    pub venue_programs: Option<Box<Account<'info, VenuePrograms>>>,
+    // mint oracles of the token and deposit mints, always passed so the oracle bound of the delegate can not be left out
+    /// CHECK: PDA of the token mint, the delegate is only bounded on the oracle price once initialized
+    #[account(
+        seeds = [
+            b"mint_oracle".as_ref(),
+            token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub token_mint_oracle: UncheckedAccount<'info>,
+    /// CHECK: Pyth PriceUpdateV2 account, checked against the feed of the token mint oracle
+    pub token_price_feed: Option<UncheckedAccount<'info>>,
+    /// CHECK: PDA of the deposit mint, the delegate is only bounded on the oracle price once initialized
+    #[account(
+        seeds = [
+            b"mint_oracle".as_ref(),
+            order.deposit_mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub deposit_mint_oracle: UncheckedAccount<'info>,
+    /// CHECK: Pyth PriceUpdateV2 account, checked against the feed of the deposit mint oracle
+    pub deposit_price_feed: Option<UncheckedAccount<'info>>,
     pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn handler<'info>(ctx: Context<JupLiquidate>, data: Vec<u8>) -> Result<()> {
+// legs: swaps from the token vault to the order vault, every leg but the last one lands on an intermediate token vault of the order
+// which is the source of the next leg. Vaults are only closed after the final leg lands on the order vault
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, JupLiquidate<'info>>, legs: Vec<RouteLeg>) -> Result<()> {
     let manager = &ctx.accounts.manager;
     let signer = &ctx.accounts.signer;
     let deposit_mint = &ctx.accounts.order.deposit_mint;
@@ -64,96 +124,210 @@ pub fn handler<'info>(ctx: Context<JupLiquidate>, data: Vec<u8>) -> Result<()> {
     } else {
         return Err(ErrorCode::IncorrectMint.into());
     };
+    let token_vault_is_a = manager_vault_a.mint != *deposit_mint;
+
+    verify_order_vaults(deposit_vault, token_vault, manager, order)?;
+
+    if ctx.accounts.token_mint.key() != token_vault.mint {
+        return Err(ErrorCode::IncorrectMint.into());
+    }
 
     // Verify permissions at the beginning
     verify_permission(signer, deposit_vault, token_vault, manager, true)?;
 
-    // Verify that the instruction data is a valid Jupiter instruction
-    if !is_jupiter_instruction(&data) {
-        return Err(ErrorCode::InvalidJupiterRoute.into());
+    // Validate destination mint matches the deposit mint
+    if &order.deposit_mint != deposit_mint {
+        return Err(ErrorCode::IncorrectMint.into());
     }
 
-    if ctx.remaining_accounts.len() < 6 {
-        return Err(ErrorCode::InvalidRemainingAccounts.into());
+    if legs.is_empty() || legs.len() > MAX_ROUTE_LEGS {
+        return Err(ErrorCode::InvalidRouteLegs.into());
     }
 
-    // Parse discriminator
-    let discriminator = &data[0..8];
-    let is_shared_accounts_route =
-        discriminator == JUPITER_SHARED_ACCOUNTS_ROUTE_DISCRIMINATOR ||
-        discriminator == JUPITER_SHARED_ACCOUNTS_ROUTE_WITH_TOKEN_LEDGER_DISCRIMINATOR ||
-        discriminator == JUPITER_SHARED_ACCOUNTS_EXACT_OUT_ROUTE_DISCRIMINATOR;
+    let accounts_len: usize = legs.iter().map(|leg| leg.accounts_len as usize).sum();
+    if accounts_len != ctx.remaining_accounts.len() {
+        return Err(ErrorCode::InvalidRouteLegs.into());
+    }
 
-    // Select indices based on instruction type
-    let (transfer_authority_idx, source_token_account_idx, destination_token_account_idx, destination_mint_idx) = if is_shared_accounts_route {
-        (2, 3, 6, 8)
-    } else {
-        (1, 2, 4, 5)
-    };
+    let signer_seeds = &[
+        b"manager".as_ref(),
+        manager.authority.as_ref(),
+        &[manager.bump],
+    ];
 
-    let transfer_authority = &ctx.remaining_accounts[transfer_authority_idx].key();
-    let source_token_account = &ctx.remaining_accounts[source_token_account_idx].key();
-    let destination_token_account = &ctx.remaining_accounts[destination_token_account_idx].key();
-    let destination_mint = &ctx.remaining_accounts[destination_mint_idx].key();
+    // Only the delegate is limited by the chunked liquidation and bracket configs, the user can always exit at once
+    let is_delegate = signer.key() != manager.authority;
+    let token_amount_before = gross_balance(token_vault)?;
+    let deposit_amount_before = gross_balance(deposit_vault)?;
 
-    // Validate that transfer authority is the manager
-    if transfer_authority != &manager.key() {
-        return Err(ErrorCode::InvalidTransferAuthority.into());
-    }
+    let last_leg = legs.len() - 1;
+    // intermediate vaults with their mints, the mint is passed writable by the route when it withholds transfer fees
+    let mut intermediate_vaults: Vec<(&AccountInfo<'info>, &AccountInfo<'info>)> = Vec::with_capacity(last_leg);
+    let mut previous_destination: Option<Pubkey> = None;
+    let mut remaining_accounts = ctx.remaining_accounts;
 
-    // Validate source token account matches one of the vaults
-    if source_token_account != &manager_vault_a.key() && source_token_account != &manager_vault_b.key() {
-        return Err(ErrorCode::InvalidSourceTokenAccount.into());
-    }
+    for (i, leg) in legs.into_iter().enumerate() {
+        let (route_accounts, rest) = remaining_accounts.split_at(leg.accounts_len as usize);
+        remaining_accounts = rest;
+
+        // Look up the route on the venue registry
+        let route = ctx.accounts.swap_venue.route(&leg.data, RouteKind::Swap)?;
+
+        let transfer_authority = &route_account_key(route_accounts, route.transfer_authority_idx)?;
+        let source_token_account = &route_account_key(route_accounts, route.source_idx)?;
+        let destination_token_account = &route_account_key(route_accounts, route.destination_idx)?;
+        let destination_mint = &route_account_key(route_accounts, route.destination_mint_idx)?;
 
-    // Validate destination token account matches one of the vaults
-    if destination_token_account != &order.order_vault {
-        return Err(ErrorCode::InvalidDestinationTokenAccount.into());
+        // Validate that transfer authority is the manager
+        if transfer_authority != &manager.key() {
+            return Err(ErrorCode::InvalidTransferAuthority.into());
+        }
+
+        // The first leg spends one of the vaults, the next ones spend what the previous leg received
+        match previous_destination {
+            None => {
+                if source_token_account != &manager_vault_a.key() && source_token_account != &manager_vault_b.key() {
+                    return Err(ErrorCode::InvalidSourceTokenAccount.into());
+                }
+            }
+            Some(previous_destination) => {
+                if *source_token_account != previous_destination {
+                    return Err(ErrorCode::InvalidSourceTokenAccount.into());
+                }
+            }
+        }
+
+        if i == last_leg {
+            // Validate the final leg lands on the order vault
+            if destination_token_account != &order.order_vault {
+                return Err(ErrorCode::InvalidDestinationTokenAccount.into());
+            }
+
+            // Validate the destination mint of the route
+            if destination_mint != deposit_mint {
+                return Err(ErrorCode::IncorrectMint.into());
+            }
+        } else {
+            let destination_idx = route.destination_idx as usize;
+            let intermediate_vault = &route_accounts[destination_idx];
+            verify_intermediate_vault(intermediate_vault, destination_mint, manager, order)?;
+            intermediate_vaults.push((intermediate_vault, &route_accounts[route.destination_mint_idx as usize]));
+        }
+
+        // Enforce the order policy on the decoded route args, right before the leg so the balance of an intermediate vault is known
+        verify_route_args(route, &leg.data, route_accounts, order.max_slippage_bps)?;
+
+        // No other manager vault or unexpected program can be reached with the manager signature
+        let mut allowed_vaults = vec![manager_vault_a.key(), manager_vault_b.key()];
+        allowed_vaults.extend(previous_destination);
+        if i != last_leg {
+            allowed_vaults.push(*destination_token_account);
+        }
+        verify_route_accounts(route_accounts, &manager.key(), &allowed_vaults, &ctx.accounts.swap_venue, ctx.accounts.venue_programs.as_deref().map(|venue_programs| &**venue_programs))?;
+
+        // make sure the manager is signer in the transaction
+        let accounts: Vec<AccountMeta> = route_accounts
+            .iter()
+            .map(|acc| {
+                let is_signer = acc.key == &ctx.accounts.manager.key();
+                AccountMeta {
+                    pubkey: *acc.key,
+                    is_signer,
+                    is_writable: acc.is_writable,
+                }
+            })
+            .collect();
+
+        let accounts_infos: Vec<AccountInfo> = route_accounts
+            .iter()
+            .map(|acc| AccountInfo { ..acc.clone() })
+            .collect();
+
+        invoke_signed(
+            &Instruction {
+                program_id: ctx.accounts.swap_program.key(),
+                accounts,
+                data: leg.data,
+            },
+            &accounts_infos,
+            &[&signer_seeds[..]],
+        )?;
+
+        previous_destination = Some(*destination_token_account);
     }
 
-    // Validate destination mint matches the deposit mint
-    if &order.deposit_mint != deposit_mint {
-        return Err(ErrorCode::IncorrectMint.into());
+    ctx.accounts.manager_vault_a.reload()?;
+    ctx.accounts.manager_vault_b.reload()?;
+    let (deposit_vault, token_vault) = if token_vault_is_a {
+        (&ctx.accounts.manager_vault_b, &ctx.accounts.manager_vault_a)
+    } else {
+        (&ctx.accounts.manager_vault_a, &ctx.accounts.manager_vault_b)
+    };
+    let token_amount_after = token_vault.amount;
+    let sold_amount = token_amount_before.saturating_sub(gross_balance(token_vault)?);
+    let received_amount = gross_balance(deposit_vault)?.saturating_sub(deposit_amount_before);
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let bracket_exit = is_delegate
+        && ctx.accounts.order.bracket.is_armed()
+        && ctx.accounts.order.bracket.fired == TriggerLeg::None;
+    let oracle_price = if is_delegate {
+        oracle_price(ctx.accounts, current_time)?
+    } else {
+        None
+    };
+
+    let order = &mut ctx.accounts.order;
+
+    // The delegate picks the route and its quote, so what it realised is bounded by the oracle price and the order slippage.
+    // Mints without an oracle are only bounded by the min out of the route args, checked on each leg
+    if let Some(oracle_price) = oracle_price {
+        let price = execution_price(received_amount, sold_amount)?;
+        if price < min_execution_price(oracle_price, order.max_slippage_bps)? {
+            return Err(ErrorCode::SlippageTooHigh.into());
+        }
     }
 
-    // For shared_accounts_route, check destination mint at index 8
-    if destination_mint != deposit_mint {
-        return Err(ErrorCode::IncorrectMint.into());
+    // Record which leg of the bracket allowed the delegate to exit, once fired the other leg is disarmed
+    // The triggers are evaluated on the oracle price, the route chosen by the delegate only has to fill close to it
+    if bracket_exit {
+        let oracle_price = oracle_price.ok_or(ErrorCode::OracleRequired)?;
+        let bracket = &mut order.bracket;
+        bracket.fired = if bracket.take_profit_price > 0 && oracle_price >= bracket.take_profit_price {
+            TriggerLeg::TakeProfit
+        } else if bracket.stop_loss_price > 0 && oracle_price <= bracket.stop_loss_price {
+            TriggerLeg::StopLoss
+        } else if bracket.expires_at > 0 && current_time >= bracket.expires_at {
+            TriggerLeg::Expiry
+        } else {
+            return Err(ErrorCode::TriggerNotReached.into());
+        };
     }
 
-    let accounts: Vec<AccountMeta> = ctx
-        .remaining_accounts
-        .iter()
-        .map(|acc| {
-            let is_signer = acc.key == &ctx.accounts.manager.key();
-            AccountMeta {
-                pubkey: *acc.key,
-                is_signer,
-                is_writable: acc.is_writable,
-            }
-        })
-        .collect();
+    if is_delegate && order.chunk_max_amount > 0 {
+        let next_chunk_at = order.last_chunk_at
+            .checked_add(order.chunk_interval)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        if current_time < next_chunk_at {
+            return Err(ErrorCode::ChunkTooEarly.into());
+        }
 
-    let accounts_infos: Vec<AccountInfo> = ctx
-        .remaining_accounts
-        .iter()
-        .map(|acc| AccountInfo { ..acc.clone() })
-        .collect();
+        if sold_amount > order.chunk_max_amount {
+            return Err(ErrorCode::ChunkTooLarge.into());
+        }
 
-    let signer_seeds = &[
-        b"manager".as_ref(),
-        manager.authority.as_ref(),
-        &[manager.bump],
-    ];
+        order.last_chunk_at = current_time;
 
-    invoke_signed(
-        &Instruction {
-            program_id: ctx.accounts.jupiter_program.key(),
-            accounts,
-            data,
-        },
-        &accounts_infos,
-        &[&signer_seeds[..]],
+        // Keep the token vault open until the last chunk is sold
+        if token_amount_after > 0 {
+            return Ok(());
+        }
+    }
+
+    harvest_withheld_fees(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        token_vault.to_account_info(),
     )?;
 
     close_account( 
@@ -167,5 +341,64 @@ pub fn handler<'info>(ctx: Context<JupLiquidate>, data: Vec<u8>) -> Result<()> {
         &[&signer_seeds[..]],
     ))?;
 
+    // Intermediate vaults left empty by the path are closed too, any dust keeps them open
+    for (intermediate_vault, intermediate_mint) in intermediate_vaults {
+        if intermediate_vault.owner != &ctx.accounts.token_program.key()
+            || TokenAccount::try_deserialize(&mut &intermediate_vault.try_borrow_data()?[..])?.amount > 0
+        {
+            continue;
+        }
+
+        harvest_withheld_fees(
+            ctx.accounts.token_program.to_account_info(),
+            intermediate_mint.clone(),
+            intermediate_vault.clone(),
+        )?;
+
+        close_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: intermediate_vault.clone(),
+                    destination: ctx.accounts.user.to_account_info(),
+                    authority: ctx.accounts.manager.to_account_info(),
+                },
+            &[&signer_seeds[..]],
+        ))?;
+    }
+
+    Ok(())
+}
+
+// Oracle price of the token in deposit mint units, scaled as the execution prices. None while one of the mints has no
+// mint oracle, once both exist their price feeds are required
+fn oracle_price(accounts: &JupLiquidate, current_time: i64) -> Result<Option<u64>> {
+    let token_mint_oracle = load_if_initialized::<MintOracle>(&accounts.token_mint_oracle)?;
+    let deposit_mint_oracle = load_if_initialized::<MintOracle>(&accounts.deposit_mint_oracle)?;
+    let (Some(token_mint_oracle), Some(deposit_mint_oracle)) = (token_mint_oracle, deposit_mint_oracle) else {
+        return Ok(None);
+    };
+
+    let (Some(token_price_feed), Some(deposit_price_feed)) = (
+        accounts.token_price_feed.as_ref(),
+        accounts.deposit_price_feed.as_ref(),
+    ) else {
+        return Err(ErrorCode::OracleRequired.into());
+    };
+
+    let token_price = get_mint_price(&token_price_feed.to_account_info(), &token_mint_oracle, current_time)?;
+    let deposit_price = get_mint_price(&deposit_price_feed.to_account_info(), &deposit_mint_oracle, current_time)?;
+
+    exchange_price(&token_price, token_mint_oracle.decimals, &deposit_price, deposit_mint_oracle.decimals).map(Some)
+}
+
+// An intermediate vault has to be a token vault of the order, created with init_token_vault for the intermediate mint
+fn verify_intermediate_vault(vault: &AccountInfo, mint: &Pubkey, manager: &Account<Manager>, order: &Account<Order>) -> Result<()> {
+    let expected_vault = token_vault_address(manager, &order.key(), mint);
+
+    if vault.key() != expected_vault || !vault.is_writable || *mint == order.deposit_mint {
+        return Err(ErrorCode::InvalidIntermediateVault.into());
+    }
+
     Ok(())
 }
\ No newline at end of file
@@ -1,2 +1,6 @@
 pub mod swap;
 pub mod liquidate;
+pub mod grid_swap;
+pub mod rebalance;
+pub mod open_resting_order;
+pub mod cancel_resting_order;
\ No newline at end of file
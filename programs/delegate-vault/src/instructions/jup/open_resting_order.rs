@@ -0,0 +1,180 @@
+use {
+    crate::{error::ErrorCode, permission::{route_account_key, verify_route_accounts, verify_route_args, verify_deposit_mint, verify_order_vaults, verify_permission}, state::*},
+    anchor_lang::{prelude::*, solana_program::{instruction::Instruction, program::invoke_signed}},
+    anchor_spl::token_interface::TokenAccount,
+};
+
+#[derive(Accounts)]
+pub struct OpenRestingOrder<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
+    pub id: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump,
+        constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+        constraint = order.resting_order == Pubkey::default() @ErrorCode::RestingOrderOpen,
+    )]
+    pub order: Box<Account<'info, Order>>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(), // this derivation makes only possible the manager authority to be the user
+        ],
+        bump = manager.bump,
+        constraint = manager.authority == signer.key() @ErrorCode::IncorrectSigner
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    #[account(
+        mut,
+        constraint = manager_vault_a.owner == manager.key() @ErrorCode::IncorrectManager
+    )]
+    pub manager_vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = manager_vault_b.owner == manager.key() @ErrorCode::IncorrectManager
+    )]
+    pub manager_vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    // policy of the token vault mint, it has to be allowed as target
+    #[account(
+        seeds = [
+            b"mint_policy".as_ref(),
+            mint_policy.mint.as_ref(),
+        ],
+        bump = mint_policy.bump,
+        constraint = mint_policy.allow_target @ErrorCode::MintNotAllowed,
+    )]
+    pub mint_policy: Box<Account<'info, MintPolicy>>,
+    // registered venue of the resting order (limit order, DCA), the route has to be one of its open routes
+    #[account(
+        seeds = [
+            b"swap_venue".as_ref(),
+            swap_venue.program_id.as_ref(),
+        ],
+        bump = swap_venue.bump,
+    )]
+    pub swap_venue: Box<Account<'info, SwapVenue>>,
+    /// CHECK: program of the swap venue
+    #[account(executable, address = swap_venue.program_id @ErrorCode::InvalidSwapVenue)]
+    pub swap_program: UncheckedAccount<'info>,
+    // programs the routes of the venue can reference besides its own one, not required if the route only references the venue program
+    #[account(
+        seeds = [
+            b"venue_programs".as_ref(),
+            swap_venue.key().as_ref(),
+        ],
+        bump = venue_programs.bump,
+    )]
+    pub venue_programs: Option<Box<Account<'info, VenuePrograms>>>,
+}
+
+// Places a resting order with the manager as maker, funded from the order vault and filled into the token vault
+pub fn handler(ctx: Context<OpenRestingOrder>, data: Vec<u8>) -> Result<()> {
+    let manager = &ctx.accounts.manager;
+    let signer = &ctx.accounts.signer;
+    let order = &ctx.accounts.order;
+    let deposit_mint = &ctx.accounts.order.deposit_mint;
+    let manager_vault_a = &ctx.accounts.manager_vault_a;
+    let manager_vault_b = &ctx.accounts.manager_vault_b;
+
+    verify_deposit_mint(deposit_mint, manager_vault_a, manager_vault_b, &ctx.accounts.order)?;
+
+    let (deposit_vault, token_vault) = if manager_vault_a.mint == *deposit_mint {
+        (&manager_vault_a, &manager_vault_b)
+    } else if manager_vault_b.mint == *deposit_mint {
+        (&manager_vault_b, &manager_vault_a)
+    } else {
+        return Err(ErrorCode::IncorrectMint.into());
+    };
+
+    verify_order_vaults(deposit_vault, token_vault, manager, order)?;
+
+    if ctx.accounts.mint_policy.mint != token_vault.mint {
+        return Err(ErrorCode::MintNotAllowed.into());
+    }
+
+    verify_permission(signer, deposit_vault, token_vault, manager, false)?;
+
+    // Look up the route on the venue registry
+    let route = ctx.accounts.swap_venue.route(&data, RouteKind::OpenRestingOrder)?;
+
+    let transfer_authority = &route_account_key(ctx.remaining_accounts, route.transfer_authority_idx)?;
+    let source_token_account = &route_account_key(ctx.remaining_accounts, route.source_idx)?;
+    let destination_token_account = &route_account_key(ctx.remaining_accounts, route.destination_idx)?;
+    let destination_mint = &route_account_key(ctx.remaining_accounts, route.destination_mint_idx)?;
+    let resting_order = route_account_key(ctx.remaining_accounts, route.resting_order_idx)?;
+
+    // Validate that the maker is the manager
+    if transfer_authority != &manager.key() {
+        return Err(ErrorCode::InvalidTransferAuthority.into());
+    }
+
+    // The resting order is funded from the order vault
+    if source_token_account != &order.order_vault {
+        return Err(ErrorCode::InvalidSourceTokenAccount.into());
+    }
+
+    // and its fills go to the token vault of the order
+    if destination_token_account != &token_vault.key() {
+        return Err(ErrorCode::InvalidDestinationTokenAccount.into());
+    }
+
+    if destination_mint != &token_vault.mint {
+        return Err(ErrorCode::IncorrectMint.into());
+    }
+
+    // Enforce the order policy on the decoded route args
+    verify_route_args(route, &data, ctx.remaining_accounts, order.max_slippage_bps)?;
+
+    // No other manager vault or unexpected program can be reached with the manager signature
+    let allowed_vaults = [manager_vault_a.key(), manager_vault_b.key()];
+    verify_route_accounts(ctx.remaining_accounts, &manager.key(), &allowed_vaults, &ctx.accounts.swap_venue, ctx.accounts.venue_programs.as_deref().map(|venue_programs| &**venue_programs))?;
+
+    // make sure the manager is signer in the transaction, the signer wallet can pay the resting order account rent
+    let accounts: Vec<AccountMeta> = ctx
+        .remaining_accounts
+        .iter()
+        .map(|acc| {
+            let is_signer = acc.key == &ctx.accounts.manager.key() || acc.is_signer;
+            AccountMeta {
+                pubkey: *acc.key,
+                is_signer,
+                is_writable: acc.is_writable,
+            }
+        })
+        .collect();
+
+    let accounts_infos: Vec<AccountInfo> = ctx
+        .remaining_accounts
+        .iter()
+        .map(|acc| AccountInfo { ..acc.clone() })
+        .collect();
+
+    let signer_seeds = &[
+        b"manager".as_ref(),
+        manager.authority.as_ref(),
+        &[manager.bump],
+    ];
+
+    invoke_signed(
+        &Instruction {
+            program_id: ctx.accounts.swap_program.key(),
+            accounts,
+            data,
+        },
+        &accounts_infos,
+        &[&signer_seeds[..]],
+    )?;
+
+    let order = &mut ctx.accounts.order;
+    order.resting_order = resting_order;
+    order.resting_venue = ctx.accounts.swap_venue.program_id;
+
+    Ok(())
+}
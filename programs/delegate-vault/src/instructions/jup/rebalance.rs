@@ -0,0 +1,213 @@
+use {
+    crate::{error::ErrorCode, oracle::{get_mint_price, get_value, OraclePrice}, permission::{route_account_key, verify_route_accounts, verify_route_args}, state::*},
+    anchor_lang::{prelude::*, solana_program::{instruction::Instruction, program::invoke_signed}},
+    anchor_spl::token_interface::TokenAccount,
+};
+
+#[derive(Accounts)]
+pub struct JupRebalance<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
+    pub id: SystemAccount<'info>,
+    #[account(
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump = order.bump,
+        constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+    )]
+    pub order: Box<Account<'info, Order>>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            manager.authority.as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.delegate == signer.key() || manager.authority == signer.key() 
+            @ErrorCode::IncorrectSigner
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    #[account(
+        seeds = [
+            b"portfolio".as_ref(),
+            order.key().as_ref(),
+        ],
+        bump = portfolio.bump,
+        constraint = portfolio.order == order.key() @ErrorCode::InvalidPortfolio,
+    )]
+    pub portfolio: Box<Account<'info, Portfolio>>,
+    // registered venue of the route, the route discriminator has to be one of its routes
+    #[account(
+        seeds = [
+            b"swap_venue".as_ref(),
+            swap_venue.program_id.as_ref(),
+        ],
+        bump = swap_venue.bump,
+    )]
+    pub swap_venue: Box<Account<'info, SwapVenue>>,
+    /// CHECK: program of the swap venue
+    #[account(executable, address = swap_venue.program_id @ErrorCode::InvalidSwapVenue)]
+    pub swap_program: UncheckedAccount<'info>,
+    // programs the routes of the venue can reference besides its own one, not required if the route only references the venue program
+    #[account(
+        seeds = [
+            b"venue_programs".as_ref(),
+            swap_venue.key().as_ref(),
+        ],
+        bump = venue_programs.bump,
+    )]
+    pub venue_programs: Option<Box<Account<'info, VenuePrograms>>>,
+}
+
+// remaining accounts: [vault, price_feed, mint_oracle] for each portfolio asset followed by the swap route accounts
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, JupRebalance<'info>>, data: Vec<u8>) -> Result<()> {
+    let manager = &ctx.accounts.manager;
+    let assets = &ctx.accounts.portfolio.assets;
+    let assets_accounts_len = assets.len() * 3;
+
+    if ctx.remaining_accounts.len() < assets_accounts_len {
+        return Err(ErrorCode::InvalidRemainingAccounts.into());
+    }
+
+    let (assets_accounts, route_accounts) = ctx.remaining_accounts.split_at(assets_accounts_len);
+    let current_time = Clock::get()?.unix_timestamp;
+
+    // Each feed has to carry the Pyth feed bound to the asset mint by its mint oracle
+    let mut prices: Vec<OraclePrice> = Vec::with_capacity(assets.len());
+    for (asset, accounts) in assets.iter().zip(assets_accounts.chunks(3)) {
+        if accounts[0].key() != asset.vault || accounts[1].key() != asset.price_feed {
+            return Err(ErrorCode::InvalidRemainingAccounts.into());
+        }
+
+        let mint_oracle = Account::<MintOracle>::try_from(&accounts[2])?;
+        if mint_oracle.mint != asset.mint {
+            return Err(ErrorCode::InvalidPriceFeed.into());
+        }
+        prices.push(get_mint_price(&accounts[1], &mint_oracle, current_time)?);
+    }
+
+    // Look up the route on the venue registry
+    let route = ctx.accounts.swap_venue.route(&data, RouteKind::Swap)?;
+
+    let transfer_authority = &route_account_key(route_accounts, route.transfer_authority_idx)?;
+    let source_token_account = &route_account_key(route_accounts, route.source_idx)?;
+    let destination_token_account = &route_account_key(route_accounts, route.destination_idx)?;
+
+    // Validate that transfer authority is the manager
+    if transfer_authority != &manager.key() {
+        return Err(ErrorCode::InvalidTransferAuthority.into());
+    }
+
+    // Swaps never leave the set of portfolio vaults
+    if !assets.iter().any(|asset| &asset.vault == source_token_account) {
+        return Err(ErrorCode::InvalidSourceTokenAccount.into());
+    }
+
+    if source_token_account == destination_token_account
+        || !assets.iter().any(|asset| &asset.vault == destination_token_account)
+    {
+        return Err(ErrorCode::InvalidDestinationTokenAccount.into());
+    }
+
+    // Enforce the order policy on the decoded route args
+    verify_route_args(route, &data, route_accounts, ctx.accounts.order.max_slippage_bps)?;
+
+    // No other manager vault or unexpected program can be reached with the manager signature
+    let allowed_vaults: Vec<Pubkey> = assets.iter().map(|asset| asset.vault).collect();
+    verify_route_accounts(route_accounts, &manager.key(), &allowed_vaults, &ctx.accounts.swap_venue, ctx.accounts.venue_programs.as_deref().map(|venue_programs| &**venue_programs))?;
+
+    let values_before = asset_values(assets, assets_accounts, &prices)?;
+
+    let accounts: Vec<AccountMeta> = route_accounts
+        .iter()
+        .map(|acc| {
+            let is_signer = acc.key == &manager.key();
+            AccountMeta {
+                pubkey: *acc.key,
+                is_signer,
+                is_writable: acc.is_writable,
+            }
+        })
+        .collect();
+
+    let accounts_infos: Vec<AccountInfo> = route_accounts
+        .iter()
+        .map(|acc| AccountInfo { ..acc.clone() })
+        .collect();
+
+    let signer_seeds = &[
+        b"manager".as_ref(),
+        manager.authority.as_ref(),
+        &[manager.bump],
+    ];
+
+    invoke_signed(
+        &Instruction {
+            program_id: ctx.accounts.swap_program.key(),
+            accounts,
+            data,
+        },
+        &accounts_infos,
+        &[&signer_seeds[..]],
+    )?;
+
+    let values_after = asset_values(assets, assets_accounts, &prices)?;
+    if weights_distance(assets, &values_after)? >= weights_distance(assets, &values_before)? {
+        return Err(ErrorCode::RebalanceNotCloser.into());
+    }
+
+    // Moving towards the targets is not enough, the swap can only lose the order max slippage of the portfolio value
+    let min_value_after = total_value(&values_before)?
+        .checked_mul(10000u128.saturating_sub(ctx.accounts.order.max_slippage_bps as u128))
+        .and_then(|value| value.checked_div(10000))
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    if total_value(&values_after)? < min_value_after {
+        return Err(ErrorCode::SlippageTooHigh.into());
+    }
+
+    Ok(())
+}
+
+/// USD value of each portfolio vault at the oracle prices
+fn asset_values<'info>(
+    assets: &[PortfolioAsset],
+    assets_accounts: &'info [AccountInfo<'info>],
+    prices: &[OraclePrice],
+) -> Result<Vec<u128>> {
+    let mut values: Vec<u128> = Vec::with_capacity(assets.len());
+    for ((asset, accounts), price) in assets.iter().zip(assets_accounts.chunks(3)).zip(prices) {
+        let vault = InterfaceAccount::<TokenAccount>::try_from(&accounts[0])?;
+        values.push(get_value(vault.amount, asset.decimals, price)?);
+    }
+
+    Ok(values)
+}
+
+fn total_value(values: &[u128]) -> Result<u128> {
+    values
+        .iter()
+        .try_fold(0u128, |total, value| total.checked_add(*value))
+        .ok_or(ErrorCode::NumericalOverflow.into())
+}
+
+/// Sum of the absolute differences in basis points between the current and the target weights
+fn weights_distance(assets: &[PortfolioAsset], values: &[u128]) -> Result<u128> {
+    let total_value = total_value(values)?;
+    if total_value == 0 {
+        return Err(ErrorCode::EmptyOrderVault.into());
+    }
+
+    let mut distance: u128 = 0;
+    for (asset, value) in assets.iter().zip(values) {
+        let weight = value
+            .checked_mul(10000)
+            .ok_or(ErrorCode::NumericalOverflow)?
+            / total_value;
+        distance += weight.abs_diff(asset.target_weight as u128);
+    }
+
+    Ok(distance)
+}
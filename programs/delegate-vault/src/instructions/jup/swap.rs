@@ -1,5 +1,5 @@
 use {
-    crate::{error::ErrorCode, jupiter_aggregator::program::Jupiter, permission::{verify_deposit_mint, verify_permission, is_jupiter_instruction, JUPITER_SHARED_ACCOUNTS_ROUTE_DISCRIMINATOR, JUPITER_SHARED_ACCOUNTS_ROUTE_WITH_TOKEN_LEDGER_DISCRIMINATOR, JUPITER_SHARED_ACCOUNTS_EXACT_OUT_ROUTE_DISCRIMINATOR}, state::*},
+    crate::{error::ErrorCode, permission::{route_account_key, verify_route_accounts, verify_route_args, verify_deposit_mint, verify_order_vaults, verify_permission}, state::*},
     anchor_lang::{prelude::*, solana_program::{instruction::Instruction, program::invoke_signed}},
     anchor_spl::token_interface::TokenAccount,
 };
@@ -39,10 +39,40 @@ pub struct Swap<'info> {
         constraint = manager_vault_b.owner == manager.key() @ErrorCode::IncorrectManager
     )]
     pub manager_vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
-    pub jupiter_program: Program<'info, Jupiter>,
+    // policy of the token vault mint, it has to be allowed as target
+    #[account(
+        seeds = [
+            b"mint_policy".as_ref(),
+            mint_policy.mint.as_ref(),
+        ],
+        bump = mint_policy.bump,
+        constraint = mint_policy.allow_target @ErrorCode::MintNotAllowed,
+    )]
+    pub mint_policy: Box<Account<'info, MintPolicy>>,
+    // registered venue of the route, the route discriminator has to be one of its routes
+    #[account(
+        seeds = [
+            b"swap_venue".as_ref(),
+            swap_venue.program_id.as_ref(),
+        ],
+        bump = swap_venue.bump,
+    )]
+    pub swap_venue: Box<Account<'info, SwapVenue>>,
+    /// CHECK: program of the swap venue
+    #[account(executable, address = swap_venue.program_id @ErrorCode::InvalidSwapVenue)]
+    pub swap_program: UncheckedAccount<'info>,
+    // programs the routes of the venue can reference besides its own one, not required if the route only references the venue program
+    #[account(
+        seeds = [
+            b"venue_programs".as_ref(),
+            swap_venue.key().as_ref(),
+        ],
+        bump = venue_programs.bump,
+    )]
+    pub venue_programs: Option<Box<Account<'info, VenuePrograms>>>,
 }
 
-pub fn handler<'info>(ctx: Context<Swap>, data: Vec<u8>) -> Result<()> {
+pub fn handler(ctx: Context<Swap>, data: Vec<u8>) -> Result<()> {
     let manager = &ctx.accounts.manager;
     let signer = &ctx.accounts.signer;
     let order = &ctx.accounts.order;
@@ -60,41 +90,21 @@ pub fn handler<'info>(ctx: Context<Swap>, data: Vec<u8>) -> Result<()> {
         return Err(ErrorCode::IncorrectMint.into());
     };
 
-    let order_vault = &order.order_vault;
+    verify_order_vaults(deposit_vault, token_vault, manager, order)?;
 
-    if deposit_vault.key() != *order_vault {
-        return Err(ErrorCode::IncorrectOrderVault.into());
+    if ctx.accounts.mint_policy.mint != token_vault.mint {
+        return Err(ErrorCode::MintNotAllowed.into());
     }
 
     // Verify permissions at the beginning
     verify_permission(signer, deposit_vault, token_vault, manager, false)?;
 
-    // Verify that the instruction data is a valid Jupiter instruction
-    if !is_jupiter_instruction(&data) {
-        return Err(ErrorCode::InvalidJupiterRoute.into());
-    }
-
-    if ctx.remaining_accounts.len() < 6 {
-        return Err(ErrorCode::InvalidRemainingAccounts.into());
-    }
-
-    // Parse discriminator
-    let discriminator = &data[0..8];
-    let is_shared_accounts_route =
-        discriminator == JUPITER_SHARED_ACCOUNTS_ROUTE_DISCRIMINATOR ||
-        discriminator == JUPITER_SHARED_ACCOUNTS_ROUTE_WITH_TOKEN_LEDGER_DISCRIMINATOR ||
-        discriminator == JUPITER_SHARED_ACCOUNTS_EXACT_OUT_ROUTE_DISCRIMINATOR;
+    // Look up the route on the venue registry
+    let route = ctx.accounts.swap_venue.route(&data, RouteKind::Swap)?;
 
-    // Select indices based on instruction type
-    let (transfer_authority_idx, source_token_account_idx, destination_token_account_idx) = if is_shared_accounts_route {
-        (2, 3, 6)
-    } else {
-        (1, 2, 4)
-    };
-
-    let transfer_authority = &ctx.remaining_accounts[transfer_authority_idx].key();
-    let source_token_account = &ctx.remaining_accounts[source_token_account_idx].key();
-    let destination_token_account = &ctx.remaining_accounts[destination_token_account_idx].key();
+    let transfer_authority = &route_account_key(ctx.remaining_accounts, route.transfer_authority_idx)?;
+    let source_token_account = &route_account_key(ctx.remaining_accounts, route.source_idx)?;
+    let destination_token_account = &route_account_key(ctx.remaining_accounts, route.destination_idx)?;
 
     // Validate that transfer authority is the manager
     if transfer_authority != &manager.key() {
@@ -111,6 +121,13 @@ pub fn handler<'info>(ctx: Context<Swap>, data: Vec<u8>) -> Result<()> {
         return Err(ErrorCode::InvalidDestinationTokenAccount.into());
     }
 
+    // Enforce the order policy on the decoded route args
+    verify_route_args(route, &data, ctx.remaining_accounts, order.max_slippage_bps)?;
+
+    // No other manager vault or unexpected program can be reached with the manager signature
+    let allowed_vaults = [manager_vault_a.key(), manager_vault_b.key()];
+    verify_route_accounts(ctx.remaining_accounts, &manager.key(), &allowed_vaults, &ctx.accounts.swap_venue, ctx.accounts.venue_programs.as_deref().map(|venue_programs| &**venue_programs))?;
+
     // make sure the manager is signer in the transaction
     let accounts: Vec<AccountMeta> = ctx
         .remaining_accounts
@@ -139,7 +156,7 @@ pub fn handler<'info>(ctx: Context<Swap>, data: Vec<u8>) -> Result<()> {
 
     invoke_signed(
         &Instruction {
-            program_id: ctx.accounts.jupiter_program.key(),
+            program_id: ctx.accounts.swap_program.key(),
             accounts,
             data,
         },
@@ -0,0 +1,79 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// CHECK: config in the layout of the first deployment, parsed in the handler
+    #[account(
+        mut,
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump,
+    )]
+    pub config: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Rewrites the config of the first deployment in the current layout, the fees added since start disabled
+// and the excess rent of the smaller account goes back to the authority
+pub fn handler(ctx: Context<MigrateConfig>) -> Result<()> {
+    let config_info = ctx.accounts.config.to_account_info();
+    if config_info.owner != &crate::ID || config_info.data_len() != LegacyConfig::LEN {
+        return Err(ErrorCode::AccountAlreadyMigrated.into());
+    }
+
+    let legacy_config = LegacyConfig::deserialize(&mut &config_info.try_borrow_data()?[8..])?;
+    if legacy_config.authority != ctx.accounts.signer.key() {
+        return Err(ErrorCode::IncorrectSigner.into());
+    }
+
+    let config = Config {
+        authority: legacy_config.authority,
+        performance_fee: legacy_config.performance_fee,
+        bump: legacy_config.bump,
+        subscribed_management_fee: 0,
+        management_fee: 0,
+        high_water_mark: false,
+        fee_schedule_enabled: false,
+        referral_fee_share: 0,
+        referral_subscription_share: 0,
+        promo_signer: Pubkey::default(),
+    };
+
+    resize(
+        &config_info,
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        Config::LEN,
+    )?;
+
+    let mut data = config_info.try_borrow_mut_data()?;
+    config.try_serialize(&mut &mut data[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_config_len_matches_its_layout() {
+        let legacy_config = LegacyConfig {
+            authority: Pubkey::new_unique(),
+            payment_mint: Pubkey::new_unique(),
+            payment_receiver: Pubkey::new_unique(),
+            performance_receiver: Pubkey::new_unique(),
+            monthly_amount: 1,
+            yearly_amount: 2,
+            subscribed_performance_fee: 100,
+            performance_fee: 250,
+            bump: 255,
+        };
+        assert_eq!(8 + legacy_config.try_to_vec().unwrap().len(), LegacyConfig::LEN);
+    }
+}
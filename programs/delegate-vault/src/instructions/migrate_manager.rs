@@ -0,0 +1,67 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+// subscription_performance_fee: rate of the subscription running on the manager, it was read from the config before the plans
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct MigrateManagerParams {
+    subscription_performance_fee: u16,
+}
+
+#[derive(Accounts)]
+pub struct MigrateManager<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    // authority of the manager, only a seed of the manager
+    pub user: SystemAccount<'info>,
+    /// CHECK: manager in the layout of the first deployment, resized in the handler
+    #[account(
+        mut,
+        seeds = [
+            b"manager".as_ref(),
+            user.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub manager: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Grows a manager of the first deployment to the current layout, the manager has no referrer until set_referrer
+// and a running subscription keeps the subscribed rate of the first deployment config, with nothing refundable
+pub fn handler(ctx: Context<MigrateManager>, params: MigrateManagerParams) -> Result<()> {
+    if params.subscription_performance_fee > 10000 {
+        return Err(ErrorCode::IncorrectFee.into());
+    }
+
+    let manager_info = ctx.accounts.manager.to_account_info();
+    if manager_info.owner != &crate::ID || manager_info.data_len() != Manager::LEGACY_LEN {
+        return Err(ErrorCode::AccountAlreadyMigrated.into());
+    }
+
+    resize(
+        &manager_info,
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        Manager::LEN,
+    )?;
+
+    let mut manager = Manager::try_deserialize(&mut &manager_info.try_borrow_data()?[..])?;
+    if manager.is_subscribed(Clock::get()?.unix_timestamp) {
+        manager.subscription_performance_fee = params.subscription_performance_fee;
+    }
+
+    let mut data = manager_info.try_borrow_mut_data()?;
+    manager.try_serialize(&mut &mut data[..])
+}
@@ -0,0 +1,111 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct MigrateOrder<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// CHECK: manager of the order, only a seed of the order so it can be migrated before the manager
+    pub manager: UncheckedAccount<'info>,
+    // ephemeral account used as order ID
+    pub id: SystemAccount<'info>,
+    /// CHECK: order in the layout of the first deployment, resized in the handler
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub order: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Grows an order of the first deployment to the current layout, anyone can pay for it
+// the fields appended since start are zeroed, which leaves them disabled, the management fee accrues from the migration
+// and the max slippage starts at the default of the new orders, 0 would reject every swap
+pub fn handler(ctx: Context<MigrateOrder>) -> Result<()> {
+    let order_info = ctx.accounts.order.to_account_info();
+    if order_info.owner != &crate::ID || order_info.data_len() != Order::LEGACY_LEN {
+        return Err(ErrorCode::AccountAlreadyMigrated.into());
+    }
+
+    resize(
+        &order_info,
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        Order::LEN,
+    )?;
+
+    let mut order = Order::try_deserialize(&mut &order_info.try_borrow_data()?[..])?;
+    order.last_accrual_at = Clock::get()?.unix_timestamp;
+    order.max_slippage_bps = Order::DEFAULT_MAX_SLIPPAGE_BPS;
+    let mut data = order_info.try_borrow_mut_data()?;
+    order.try_serialize(&mut &mut data[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    fn legacy_order_data(id: Pubkey, manager: Pubkey) -> Vec<u8> {
+        let mut data = Order::DISCRIMINATOR.to_vec();
+        for key in [id, manager, Pubkey::new_unique(), Pubkey::new_unique()] {
+            data.extend_from_slice(key.as_ref());
+        }
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.push(254);
+        data
+    }
+
+    #[test]
+    fn legacy_order_grown_to_the_current_layout_keeps_its_fields() {
+        let id = Pubkey::new_unique();
+        let manager = Pubkey::new_unique();
+        let mut data = legacy_order_data(id, manager);
+        assert_eq!(data.len(), Order::LEGACY_LEN);
+
+        data.resize(Order::LEN, 0);
+        let order = Order::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(order.id, id);
+        assert_eq!(order.manager, manager);
+        assert_eq!(order.deposit_amount, 1_000);
+        assert_eq!(order.bump, 254);
+        assert_eq!(order.chunk_max_amount, 0);
+        assert_eq!(order.management_fee_accrued, 0);
+        assert_eq!(order.policy_deposit, 0);
+    }
+
+    #[test]
+    fn withdrawing_a_legacy_order_keeps_the_tvl_of_the_mint_policy() {
+        let mut data = legacy_order_data(Pubkey::new_unique(), Pubkey::new_unique());
+        data.resize(Order::LEN, 0);
+        let mut legacy_order = Order::try_deserialize(&mut &data[..]).unwrap();
+        let mut order = Order::try_deserialize(&mut &data[..]).unwrap();
+        let mut mint_policy = MintPolicy {
+            mint: Pubkey::new_unique(),
+            allow_deposit: true,
+            allow_target: true,
+            min_deposit: 0,
+            max_tvl: 0,
+            tvl: 0,
+            bump: 255,
+        };
+        mint_policy.add_deposit(&mut order, 600).unwrap();
+        assert_eq!(mint_policy.tvl, 600);
+
+        // The legacy order deposited 1_000 before the policy, none of it was counted
+        mint_policy.remove_deposit(&mut legacy_order);
+        assert_eq!(mint_policy.tvl, 600);
+
+        mint_policy.remove_deposit(&mut order);
+        assert_eq!(mint_policy.tvl, 0);
+        assert_eq!(order.policy_deposit, 0);
+    }
+}
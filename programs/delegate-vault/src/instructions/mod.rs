@@ -4,8 +4,45 @@ pub mod init_manager;
 pub mod init;
 pub mod edit_config;
 pub mod jup;
+pub mod migrate_order;
+pub mod migrate_config;
+pub mod migrate_manager;
 pub mod init_token_vault;
 pub mod pay_subscription;
+pub mod set_chunked_liquidation;
+pub mod set_max_slippage;
+pub mod set_bracket;
+pub mod set_grid;
+pub mod init_portfolio;
+pub mod collect_management_fee;
+pub mod init_high_water_mark;
+pub mod init_fee_vault;
+pub mod claim_fees;
+pub mod set_fee_schedule;
+pub mod init_referrer;
+pub mod init_referrer_vault;
+pub mod set_referrer;
+pub mod claim_referral_rewards;
+pub mod set_fee_override;
+pub mod set_plan;
+pub mod set_plan_price;
+pub mod pay_subscription_sol;
+pub mod claim_sol_fees;
+pub mod gift_subscription;
+pub mod cancel_subscription;
+pub mod refund_reserve;
+pub mod set_auto_renew;
+pub mod renew_subscription;
+pub mod redeem_voucher;
+pub mod set_discount_config;
+pub mod init_stake;
+pub mod stake;
+pub mod set_mint_policy;
+pub mod set_swap_venue;
+pub mod set_venue_programs;
+pub mod set_mint_oracle;
+pub mod clear_resting_order;
+pub mod set_hook_program;
 
 pub use withdraw::*;
 pub use deposit::*;
@@ -14,5 +51,46 @@ pub use init::*;
 pub use edit_config::*;
 pub use jup::swap::*;
 pub use jup::liquidate::*;
+pub use migrate_order::*;
+pub use migrate_config::*;
+pub use migrate_manager::*;
+pub use jup::grid_swap::*;
+pub use jup::rebalance::*;
+pub use jup::open_resting_order::*;
+pub use jup::cancel_resting_order::*;
+pub use clear_resting_order::*;
 pub use init_token_vault::*;
-pub use pay_subscription::*;
\ No newline at end of file
+pub use pay_subscription::*;
+pub use set_chunked_liquidation::*;
+pub use set_max_slippage::*;
+pub use set_bracket::*;
+pub use set_grid::*;
+pub use init_portfolio::*;
+pub use collect_management_fee::*;
+pub use init_high_water_mark::*;
+pub use init_fee_vault::*;
+pub use claim_fees::*;
+pub use set_fee_schedule::*;
+pub use init_referrer::*;
+pub use init_referrer_vault::*;
+pub use set_referrer::*;
+pub use claim_referral_rewards::*;
+pub use set_fee_override::*;
+pub use set_plan::*;
+pub use set_plan_price::*;
+pub use pay_subscription_sol::*;
+pub use claim_sol_fees::*;
+pub use gift_subscription::*;
+pub use cancel_subscription::*;
+pub use refund_reserve::*;
+pub use set_auto_renew::*;
+pub use renew_subscription::*;
+pub use redeem_voucher::*;
+pub use set_discount_config::*;
+pub use init_stake::*;
+pub use stake::*;
+pub use set_mint_policy::*;
+pub use set_swap_venue::*;
+pub use set_venue_programs::*;
+pub use set_mint_oracle::*;
+pub use set_hook_program::*;
\ No newline at end of file
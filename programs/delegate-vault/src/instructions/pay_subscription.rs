@@ -1,8 +1,10 @@
 use {
     crate::state::*,
     crate::error::ErrorCode,
+    crate::events::SubscriptionPaid,
+    crate::fees::FeeTransfer,
     anchor_lang::prelude::*,
-    anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount, TransferChecked, transfer_checked},
+    anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount},
 };
 
 #[derive(Accounts)]
@@ -26,26 +28,82 @@ pub struct PaySubscription<'info> {
         bump = config.bump,
     )]
     pub config: Box<Account<'info, Config>>,
+    #[account(
+        seeds = [
+            b"plan".as_ref(),
+            plan.id.to_le_bytes().as_ref(),
+        ],
+        bump = plan.bump,
+        constraint = plan.active @ErrorCode::InactivePlan,
+    )]
+    pub plan: Box<Account<'info, Plan>>,
 
+    // only required when paying with a mint other than the plan payment_mint
     #[account(
-        mut,
-        constraint = payment_mint.key() == config.payment_mint @ErrorCode::IncorrectPaymentMint,
+        seeds = [
+            b"plan_price".as_ref(),
+            plan.key().as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump = plan_price.bump,
+        constraint = plan_price.active @ErrorCode::InactivePlan,
     )]
+    pub plan_price: Option<Box<Account<'info, PlanPrice>>>,
+
+    #[account(mut)]
     pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
-    /// CHECK: only validate address
     #[account(
         mut,
-        constraint = payment_receiver.key() == config.payment_receiver @ErrorCode::IncorrectReceiver,
+        seeds = [
+            b"fee_vault".as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump,
+        token::mint = payment_mint,
+        token::authority = config,
+        token::token_program = token_program,
     )]
-    pub payment_receiver: AccountInfo<'info>,
-
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // only required when the config has the fee schedule enabled, recipients ATAs are passed as remaining accounts
+    #[account(
+        seeds = [b"fee_schedule".as_ref()],
+        bump = fee_schedule.bump,
+    )]
+    pub fee_schedule: Option<Box<Account<'info, FeeSchedule>>>,
+    // always passed, the share of the referrer stays with the protocol while the referrer has no vault for the mint
+    /// CHECK: referrer vault PDA of the mint, only paid once initialized by init_referrer_vault
     #[account(
         mut,
-        associated_token::mint=payment_mint,
-        associated_token::authority=payment_receiver,
-        associated_token::token_program=token_program,
+        seeds = [
+            b"referrer_vault".as_ref(),
+            manager.referrer.as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub referrer_vault: UncheckedAccount<'info>,
+    // always passed, the payment is only refundable once the refund reserve of the mint is initialized
+    /// CHECK: refund reserve PDA of the payment mint, ignored while it is not initialized
+    #[account(
+        mut,
+        seeds = [
+            b"refund_reserve".as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub refund_reserve: UncheckedAccount<'info>,
+    // only required when the payment drops a refundable period of the manager paid in another mint
+    /// CHECK: refund reserve PDA of the refund mint of the manager
+    #[account(
+        mut,
+        seeds = [
+            b"refund_reserve".as_ref(),
+            manager.refund_mint.as_ref(),
+        ],
+        bump,
     )]
-    pub payment_receiver_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub previous_refund_reserve: Option<UncheckedAccount<'info>>,
 
     #[account(
         mut,
@@ -58,51 +116,103 @@ pub struct PaySubscription<'info> {
     pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn handler<'info>(ctx: Context<PaySubscription>, amount: u64) -> Result<()> {
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, PaySubscription<'info>>) -> Result<()> {
     let manager = &mut ctx.accounts.manager;
     let config = &ctx.accounts.config;
+    let plan = &ctx.accounts.plan;
+    let amount = subscription_price(plan, ctx.accounts.plan_price.as_deref().map(|plan_price| &**plan_price), ctx.accounts.payment_mint.key())?;
 
-    // Validate payment amount matches either monthly or yearly subscription
-    if amount != config.monthly_amount && amount != config.yearly_amount {
-        return Err(ErrorCode::IncorrectPaymentAmount.into());
-    }
+    // Transfer payment, the referrer gets its share and the rest goes to the protocol
+    let fee_transfer = FeeTransfer {
+        token_program: ctx.accounts.token_program.to_account_info(),
+        from: ctx.accounts.signer_ata.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+        mint: ctx.accounts.payment_mint.to_account_info(),
+        decimals: ctx.accounts.payment_mint.decimals,
+        signer_seeds: &[],
+        extra_accounts: &[],
+    };
 
-    // Transfer payment
-    transfer_checked(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(), 
-            TransferChecked {
-                from: ctx.accounts.signer_ata.to_account_info(),
-                to: ctx.accounts.payment_receiver_ata.to_account_info(),
-                authority: ctx.accounts.signer.to_account_info(),
-                mint: ctx.accounts.payment_mint.to_account_info(),
-            },
-        ),
+    let referral_amount = fee_transfer.share_with_referrer(
+        manager,
+        ctx.accounts.referrer_vault.to_account_info(),
         amount,
-        ctx.accounts.payment_mint.decimals,
+        config.referral_subscription_share,
     )?;
 
-    // Constants for time calculations
-    const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
-    const DAYS_PER_YEAR: i64 = 365;
-    const DAYS_PER_MONTH: i64 = 30;
-
-    let subscription_duration: i64 = if amount == config.yearly_amount {
-        SECONDS_PER_DAY * DAYS_PER_YEAR  // 31,536,000 seconds
-    } else {
-        SECONDS_PER_DAY * DAYS_PER_MONTH // 2,592,000 seconds
-    };
+    let refundable_amount = fee_transfer.distribute(
+        config,
+        ctx.accounts.fee_schedule.as_deref().map(|fee_schedule| &**fee_schedule),
+        ctx.accounts.fee_vault.as_ref().map(|fee_vault| fee_vault.to_account_info()),
+        ctx.remaining_accounts,
+        amount - referral_amount,
+    )?;
 
-    // Calculate subscription end date based on payment amount
+    // Extend the subscription with the plan duration, from its end if it is still active
     let current_time = Clock::get()?.unix_timestamp;
-    let start_time = if manager.end_subscription > current_time {
-        manager.end_subscription
-    } else {
-        current_time
-    };
+    let start_time = manager.extend_subscription(plan, current_time)?;
+    record_refundable_payment(
+        manager,
+        ctx.accounts.payment_mint.key(),
+        refundable_amount,
+        start_time,
+        &ctx.accounts.refund_reserve,
+        ctx.accounts.previous_refund_reserve.as_deref(),
+    )?;
+
+    emit!(SubscriptionPaid {
+        manager: manager.key(),
+        payer: ctx.accounts.signer.key(),
+        plan: plan.key(),
+        mint: ctx.accounts.payment_mint.key(),
+        amount,
+        gifted: false,
+        end_subscription: manager.end_subscription,
+    });
+
+    Ok(())
+}
+
+/// Price of the plan in the payment mint, the plan price account is only required when paying with a mint other than the plan one
+pub fn subscription_price(plan: &Plan, plan_price: Option<&PlanPrice>, payment_mint: Pubkey) -> Result<u64> {
+    match plan_price {
+        Some(plan_price) => Ok(plan_price.price),
+        None if payment_mint == plan.payment_mint => Ok(plan.price),
+        None => Err(ErrorCode::IncorrectPaymentMint.into()),
+    }
+}
+
+/// Records the payment on the refundable period of the manager and reserves it on the refund reserve of the mint,
+/// the payment is not refundable while the reserve is not initialized, the refundable amount dropped by the payment is released
+pub fn record_refundable_payment(
+    manager: &mut Manager,
+    mint: Pubkey,
+    amount: u64,
+    start_time: i64,
+    refund_reserve: &AccountInfo,
+    previous_refund_reserve: Option<&AccountInfo>,
+) -> Result<()> {
+    let mut reserve = load_if_initialized::<RefundReserve>(refund_reserve)?;
+    let refundable_amount = if reserve.is_some() { amount } else { 0 };
+
+    let previous_mint = manager.refund_mint;
+    let dropped_amount = manager.record_payment(mint, refundable_amount, start_time)?;
 
-    manager.end_subscription = start_time.checked_add(subscription_duration)
-        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    if dropped_amount > 0 && previous_mint != mint {
+        let previous_refund_reserve = previous_refund_reserve.ok_or(ErrorCode::RefundAccountsRequired)?;
+        if let Some(mut previous_reserve) = load_if_initialized::<RefundReserve>(previous_refund_reserve)? {
+            previous_reserve.release(dropped_amount);
+            store(previous_refund_reserve, &previous_reserve)?;
+        }
+    }
+
+    if let Some(reserve) = reserve.as_mut() {
+        if previous_mint == mint {
+            reserve.release(dropped_amount);
+        }
+        reserve.reserve(refundable_amount)?;
+        store(refund_reserve, reserve)?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,159 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    crate::events::SubscriptionPaid,
+    crate::fees::SolFeeTransfer,
+    super::pay_subscription::record_refundable_payment,
+    anchor_lang::prelude::*,
+    anchor_spl::token::spl_token::native_mint,
+};
+
+#[derive(Accounts)]
+pub struct PaySubscriptionSol<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    // receives the payment when the fee schedule is disabled
+    #[account(
+        mut,
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        seeds = [
+            b"plan".as_ref(),
+            plan.id.to_le_bytes().as_ref(),
+        ],
+        bump = plan.bump,
+        constraint = plan.active @ErrorCode::InactivePlan,
+    )]
+    pub plan: Box<Account<'info, Plan>>,
+    #[account(
+        seeds = [
+            b"plan_price".as_ref(),
+            plan.key().as_ref(),
+            native_mint::ID.as_ref(),
+        ],
+        bump = plan_price.bump,
+        constraint = plan_price.active @ErrorCode::InactivePlan,
+    )]
+    pub plan_price: Box<Account<'info, PlanPrice>>,
+
+    // only required when the config has the fee schedule enabled, recipients wallets are passed as remaining accounts
+    #[account(
+        seeds = [b"fee_schedule".as_ref()],
+        bump = fee_schedule.bump,
+    )]
+    pub fee_schedule: Option<Box<Account<'info, FeeSchedule>>>,
+    // only required when the manager has a referrer
+    #[account(
+        seeds = [
+            b"referrer".as_ref(),
+            referrer.wallet.as_ref(),
+        ],
+        bump = referrer.bump,
+        constraint = referrer.key() == manager.referrer @ErrorCode::InvalidReferrer,
+    )]
+    pub referrer: Option<Box<Account<'info, Referrer>>>,
+    /// CHECK: validated against the referrer wallet
+    #[account(mut)]
+    pub referrer_wallet: Option<UncheckedAccount<'info>>,
+    // always passed, the payment is only refundable once the refund reserve of the mint is initialized
+    /// CHECK: refund reserve PDA of native SOL, ignored while it is not initialized
+    #[account(
+        mut,
+        seeds = [
+            b"refund_reserve".as_ref(),
+            Pubkey::default().as_ref(),
+        ],
+        bump,
+    )]
+    pub refund_reserve: UncheckedAccount<'info>,
+    // only required when the payment drops a refundable period of the manager paid in another mint
+    /// CHECK: refund reserve PDA of the refund mint of the manager
+    #[account(
+        mut,
+        seeds = [
+            b"refund_reserve".as_ref(),
+            manager.refund_mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub previous_refund_reserve: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, PaySubscriptionSol<'info>>) -> Result<()> {
+    let manager = &mut ctx.accounts.manager;
+    let config = &ctx.accounts.config;
+    let plan = &ctx.accounts.plan;
+    let amount = ctx.accounts.plan_price.price;
+
+    let referrer_wallet = match (&ctx.accounts.referrer, &ctx.accounts.referrer_wallet) {
+        (Some(referrer), Some(referrer_wallet)) => {
+            if referrer_wallet.key() != referrer.wallet {
+                return Err(ErrorCode::InvalidReferrer.into());
+            }
+            Some(referrer_wallet.to_account_info())
+        }
+        _ => None,
+    };
+
+    // Transfer payment, the referrer gets its share and the rest goes to the protocol
+    let fee_transfer = SolFeeTransfer {
+        system_program: ctx.accounts.system_program.to_account_info(),
+        from: ctx.accounts.signer.to_account_info(),
+    };
+
+    let referral_amount = fee_transfer.share_with_referrer(
+        manager,
+        referrer_wallet,
+        amount,
+        config.referral_subscription_share,
+    )?;
+
+    let refundable_amount = fee_transfer.distribute(
+        config,
+        config.to_account_info(),
+        ctx.accounts.fee_schedule.as_deref().map(|fee_schedule| &**fee_schedule),
+        ctx.remaining_accounts,
+        amount - referral_amount,
+    )?;
+
+    // Extend the subscription with the plan duration, from its end if it is still active
+    let current_time = Clock::get()?.unix_timestamp;
+    let start_time = manager.extend_subscription(plan, current_time)?;
+    record_refundable_payment(
+        manager,
+        Pubkey::default(),
+        refundable_amount,
+        start_time,
+        &ctx.accounts.refund_reserve,
+        ctx.accounts.previous_refund_reserve.as_deref(),
+    )?;
+
+    emit!(SubscriptionPaid {
+        manager: manager.key(),
+        payer: ctx.accounts.signer.key(),
+        plan: plan.key(),
+        mint: Pubkey::default(),
+        amount,
+        gifted: false,
+        end_subscription: manager.end_subscription,
+    });
+
+    Ok(())
+}
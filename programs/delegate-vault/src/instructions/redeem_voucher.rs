@@ -0,0 +1,229 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    crate::events::SubscriptionPaid,
+    crate::fees::{calculate_fee, FeeTransfer},
+    crate::ed25519::verify_ed25519_instruction,
+    super::pay_subscription::{record_refundable_payment, subscription_price},
+    anchor_lang::{
+        prelude::*,
+        solana_program::sysvar::instructions::{self, get_instruction_relative},
+    },
+    anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount},
+};
+
+// the voucher signature is verified by an ed25519 program instruction placed right before this one
+// signer_ata is only required when the voucher is not a free trial (discount < 10000)
+#[derive(Accounts)]
+#[instruction(voucher: Voucher)]
+pub struct RedeemVoucher<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        seeds = [
+            b"plan".as_ref(),
+            plan.id.to_le_bytes().as_ref(),
+        ],
+        bump = plan.bump,
+        constraint = plan.active @ErrorCode::InactivePlan,
+        constraint = plan.key() == voucher.plan @ErrorCode::InvalidVoucher,
+    )]
+    pub plan: Box<Account<'info, Plan>>,
+    #[account(
+        init,
+        space = VoucherRedemption::LEN,
+        payer = signer,
+        seeds = [
+            b"voucher".as_ref(),
+            voucher.nonce.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub redemption: Box<Account<'info, VoucherRedemption>>,
+
+    // only required when paying with a mint other than the plan payment_mint
+    #[account(
+        seeds = [
+            b"plan_price".as_ref(),
+            plan.key().as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump = plan_price.bump,
+        constraint = plan_price.active @ErrorCode::InactivePlan,
+    )]
+    pub plan_price: Option<Box<Account<'info, PlanPrice>>>,
+
+    #[account(mut)]
+    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"fee_vault".as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump,
+        token::mint = payment_mint,
+        token::authority = config,
+        token::token_program = token_program,
+    )]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // only required when the config has the fee schedule enabled, recipients ATAs are passed as remaining accounts
+    #[account(
+        seeds = [b"fee_schedule".as_ref()],
+        bump = fee_schedule.bump,
+    )]
+    pub fee_schedule: Option<Box<Account<'info, FeeSchedule>>>,
+    // always passed, the share of the referrer stays with the protocol while the referrer has no vault for the mint
+    /// CHECK: referrer vault PDA of the mint, only paid once initialized by init_referrer_vault
+    #[account(
+        mut,
+        seeds = [
+            b"referrer_vault".as_ref(),
+            manager.referrer.as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub referrer_vault: UncheckedAccount<'info>,
+    // always passed, the payment is only refundable once the refund reserve of the mint is initialized
+    /// CHECK: refund reserve PDA of the payment mint, ignored while it is not initialized
+    #[account(
+        mut,
+        seeds = [
+            b"refund_reserve".as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub refund_reserve: UncheckedAccount<'info>,
+    // only required when the payment drops a refundable period of the manager paid in another mint
+    /// CHECK: refund reserve PDA of the refund mint of the manager
+    #[account(
+        mut,
+        seeds = [
+            b"refund_reserve".as_ref(),
+            manager.refund_mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub previous_refund_reserve: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=signer,
+        associated_token::token_program=token_program,
+    )]
+    pub signer_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK: instructions sysvar, used to load the ed25519 instruction
+    #[account(address = instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RedeemVoucher<'info>>, voucher: Voucher) -> Result<()> {
+    let manager = &mut ctx.accounts.manager;
+    let config = &ctx.accounts.config;
+    let plan = &ctx.accounts.plan;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    if config.promo_signer == Pubkey::default() || voucher.discount > 10000 || voucher.duration < 0 {
+        return Err(ErrorCode::InvalidVoucher.into());
+    }
+
+    if voucher.manager != Pubkey::default() && voucher.manager != manager.key() {
+        return Err(ErrorCode::InvalidVoucher.into());
+    }
+
+    if current_time > voucher.expires_at {
+        return Err(ErrorCode::VoucherExpired.into());
+    }
+
+    // The previous instruction must verify the signature of the promo signer over the voucher
+    let ed25519_instruction = get_instruction_relative(-1, &ctx.accounts.instructions.to_account_info())?;
+    verify_ed25519_instruction(&ed25519_instruction, &config.promo_signer, &voucher.try_to_vec()?)?;
+
+    let redemption = &mut ctx.accounts.redemption;
+    redemption.manager = manager.key();
+    redemption.redeemed_at = current_time;
+    redemption.bump = ctx.bumps.redemption;
+
+    let price = subscription_price(plan, ctx.accounts.plan_price.as_deref().map(|plan_price| &**plan_price), ctx.accounts.payment_mint.key())?;
+    let amount = price - calculate_fee(price, voucher.discount)?;
+
+    let mut refundable_amount = 0;
+    if amount > 0 {
+        let signer_ata = ctx.accounts.signer_ata.as_ref().ok_or(ErrorCode::PaymentAccountRequired)?;
+
+        // Transfer discounted payment, the referrer gets its share and the rest goes to the protocol
+        let fee_transfer = FeeTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: signer_ata.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+            mint: ctx.accounts.payment_mint.to_account_info(),
+            decimals: ctx.accounts.payment_mint.decimals,
+            signer_seeds: &[],
+            extra_accounts: &[],
+        };
+
+        let referral_amount = fee_transfer.share_with_referrer(
+            manager,
+            ctx.accounts.referrer_vault.to_account_info(),
+            amount,
+            config.referral_subscription_share,
+        )?;
+
+        refundable_amount = fee_transfer.distribute(
+            config,
+            ctx.accounts.fee_schedule.as_deref().map(|fee_schedule| &**fee_schedule),
+            ctx.accounts.fee_vault.as_ref().map(|fee_vault| fee_vault.to_account_info()),
+            ctx.remaining_accounts,
+            amount - referral_amount,
+        )?;
+    }
+
+    // Extend the subscription with the voucher duration (or the plan one), only the paid part is refundable
+    let duration = if voucher.duration > 0 { voucher.duration } else { plan.duration };
+    let start_time = manager.extend_subscription_for(duration, plan.performance_fee, current_time)?;
+    if amount > 0 {
+        record_refundable_payment(
+            manager,
+            ctx.accounts.payment_mint.key(),
+            refundable_amount,
+            start_time,
+            &ctx.accounts.refund_reserve,
+            ctx.accounts.previous_refund_reserve.as_deref(),
+        )?;
+    }
+
+    emit!(SubscriptionPaid {
+        manager: manager.key(),
+        payer: ctx.accounts.signer.key(),
+        plan: plan.key(),
+        mint: ctx.accounts.payment_mint.key(),
+        amount,
+        gifted: false,
+        end_subscription: manager.end_subscription,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+// Anyone can create the refund reserve of a payment mint, the subscription payments in the mint are refundable from then on
+#[derive(Accounts)]
+pub struct InitRefundReserve<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        init,
+        space = RefundReserve::LEN,
+        payer = signer,
+        seeds = [
+            b"refund_reserve".as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub refund_reserve: Box<Account<'info, RefundReserve>>,
+    /// CHECK: payment mint, the system program (default pubkey) for the payments in native SOL
+    pub mint: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Anyone can release the refundable amount of a manager once its refundable period ended, it is then claimable by the protocol
+#[derive(Accounts)]
+pub struct ReleaseRefund<'info> {
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"manager".as_ref(),
+            manager.authority.as_ref(),
+        ],
+        bump = manager.bump,
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    #[account(
+        mut,
+        seeds = [
+            b"refund_reserve".as_ref(),
+            manager.refund_mint.as_ref(),
+        ],
+        bump = refund_reserve.bump,
+    )]
+    pub refund_reserve: Box<Account<'info, RefundReserve>>,
+}
+
+pub fn init_handler(ctx: Context<InitRefundReserve>) -> Result<()> {
+    let refund_reserve = &mut ctx.accounts.refund_reserve;
+    refund_reserve.mint = ctx.accounts.mint.key();
+    refund_reserve.amount = 0;
+    refund_reserve.bump = ctx.bumps.refund_reserve;
+
+    Ok(())
+}
+
+pub fn release_handler(ctx: Context<ReleaseRefund>) -> Result<()> {
+    let manager = &mut ctx.accounts.manager;
+
+    if Clock::get()?.unix_timestamp < manager.refundable_end {
+        return Err(ErrorCode::RefundPeriodActive.into());
+    }
+
+    ctx.accounts.refund_reserve.release(manager.refundable_amount);
+    manager.clear_refund();
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    crate::events::SubscriptionPaid,
+    crate::fees::FeeTransfer,
+    super::pay_subscription::{record_refundable_payment, subscription_price},
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount},
+};
+
+// permissionless (crank), the plan price is pulled from the authority ATA approved to the config with set_auto_renew
+#[derive(Accounts)]
+pub struct RenewSubscription<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"manager".as_ref(),
+            manager.authority.as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.auto_renew_plan == plan.key() @ErrorCode::AutoRenewDisabled,
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        seeds = [
+            b"plan".as_ref(),
+            plan.id.to_le_bytes().as_ref(),
+        ],
+        bump = plan.bump,
+        constraint = plan.active @ErrorCode::InactivePlan,
+    )]
+    pub plan: Box<Account<'info, Plan>>,
+
+    // only required when paying with a mint other than the plan payment_mint
+    #[account(
+        seeds = [
+            b"plan_price".as_ref(),
+            plan.key().as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump = plan_price.bump,
+        constraint = plan_price.active @ErrorCode::InactivePlan,
+    )]
+    pub plan_price: Option<Box<Account<'info, PlanPrice>>>,
+
+    #[account(mut)]
+    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"fee_vault".as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump,
+        token::mint = payment_mint,
+        token::authority = config,
+        token::token_program = token_program,
+    )]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // only required when the config has the fee schedule enabled, recipients ATAs are passed as remaining accounts
+    #[account(
+        seeds = [b"fee_schedule".as_ref()],
+        bump = fee_schedule.bump,
+    )]
+    pub fee_schedule: Option<Box<Account<'info, FeeSchedule>>>,
+    // always passed, the share of the referrer stays with the protocol while the referrer has no vault for the mint
+    /// CHECK: referrer vault PDA of the mint, only paid once initialized by init_referrer_vault
+    #[account(
+        mut,
+        seeds = [
+            b"referrer_vault".as_ref(),
+            manager.referrer.as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub referrer_vault: UncheckedAccount<'info>,
+    // always passed, the payment is only refundable once the refund reserve of the mint is initialized
+    /// CHECK: refund reserve PDA of the payment mint, ignored while it is not initialized
+    #[account(
+        mut,
+        seeds = [
+            b"refund_reserve".as_ref(),
+            payment_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub refund_reserve: UncheckedAccount<'info>,
+    // only required when the payment drops a refundable period of the manager paid in another mint
+    /// CHECK: refund reserve PDA of the refund mint of the manager
+    #[account(
+        mut,
+        seeds = [
+            b"refund_reserve".as_ref(),
+            manager.refund_mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub previous_refund_reserve: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=manager.authority,
+        associated_token::token_program=token_program,
+        constraint = authority_ata.delegate.contains(&config.key()) @ErrorCode::AutoRenewDisabled,
+    )]
+    pub authority_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RenewSubscription<'info>>) -> Result<()> {
+    let manager = &mut ctx.accounts.manager;
+    let config = &ctx.accounts.config;
+    let plan = &ctx.accounts.plan;
+    let amount = subscription_price(plan, ctx.accounts.plan_price.as_deref().map(|plan_price| &**plan_price), ctx.accounts.payment_mint.key())?;
+
+    // Only renew within the window before the end of the subscription, so it does not lapse and it can not be renewed twice
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time >= manager.end_subscription || current_time < manager.end_subscription - Manager::RENEWAL_WINDOW {
+        return Err(ErrorCode::RenewalNotDue.into());
+    }
+
+    if plan.duration <= Manager::RENEWAL_WINDOW {
+        return Err(ErrorCode::InvalidPlan.into());
+    }
+
+    let seeds = &[
+        b"config".as_ref(),
+        &[config.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // Pull the payment from the authority ATA as delegate, the referrer gets its share and the rest goes to the protocol
+    let fee_transfer = FeeTransfer {
+        token_program: ctx.accounts.token_program.to_account_info(),
+        from: ctx.accounts.authority_ata.to_account_info(),
+        authority: config.to_account_info(),
+        mint: ctx.accounts.payment_mint.to_account_info(),
+        decimals: ctx.accounts.payment_mint.decimals,
+        signer_seeds,
+        extra_accounts: &[],
+    };
+
+    let referral_amount = fee_transfer.share_with_referrer(
+        manager,
+        ctx.accounts.referrer_vault.to_account_info(),
+        amount,
+        config.referral_subscription_share,
+    )?;
+
+    let refundable_amount = fee_transfer.distribute(
+        config,
+        ctx.accounts.fee_schedule.as_deref().map(|fee_schedule| &**fee_schedule),
+        ctx.accounts.fee_vault.as_ref().map(|fee_vault| fee_vault.to_account_info()),
+        ctx.remaining_accounts,
+        amount - referral_amount,
+    )?;
+
+    // Extend the subscription with the plan duration from its end, paid by the manager authority so it is refundable
+    let start_time = manager.extend_subscription(plan, current_time)?;
+    record_refundable_payment(
+        manager,
+        ctx.accounts.payment_mint.key(),
+        refundable_amount,
+        start_time,
+        &ctx.accounts.refund_reserve,
+        ctx.accounts.previous_refund_reserve.as_deref(),
+    )?;
+
+    emit!(SubscriptionPaid {
+        manager: manager.key(),
+        payer: manager.authority,
+        plan: plan.key(),
+        mint: ctx.accounts.payment_mint.key(),
+        amount,
+        gifted: false,
+        end_subscription: manager.end_subscription,
+    });
+
+    Ok(())
+}
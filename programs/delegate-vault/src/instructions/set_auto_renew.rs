@@ -0,0 +1,88 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{approve, revoke, Approve, Mint, Revoke, TokenAccount, TokenInterface},
+};
+
+// plan: plan to renew, auto renewal is disabled (and the approval revoked) when it is not passed
+// allowance: amount of the payment mint the config is approved to pull from the signer ATA for the renewals
+#[derive(Accounts)]
+pub struct SetAutoRenew<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.authority == signer.key() @ErrorCode::IncorrectSigner,
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        seeds = [
+            b"plan".as_ref(),
+            plan.id.to_le_bytes().as_ref(),
+        ],
+        bump = plan.bump,
+        constraint = plan.active @ErrorCode::InactivePlan,
+    )]
+    pub plan: Option<Box<Account<'info, Plan>>>,
+
+    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=signer,
+        associated_token::token_program=token_program,
+    )]
+    pub signer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<SetAutoRenew>, allowance: u64) -> Result<()> {
+    let Some(plan) = &ctx.accounts.plan else {
+        revoke(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Revoke {
+                    source: ctx.accounts.signer_ata.to_account_info(),
+                    authority: ctx.accounts.signer.to_account_info(),
+                },
+            ),
+        )?;
+
+        ctx.accounts.manager.auto_renew_plan = Pubkey::default();
+        return Ok(());
+    };
+
+    if plan.duration <= Manager::RENEWAL_WINDOW {
+        return Err(ErrorCode::InvalidPlan.into());
+    }
+
+    approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Approve {
+                to: ctx.accounts.signer_ata.to_account_info(),
+                delegate: ctx.accounts.config.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ),
+        allowance,
+    )?;
+
+    ctx.accounts.manager.auto_renew_plan = plan.key();
+
+    Ok(())
+}
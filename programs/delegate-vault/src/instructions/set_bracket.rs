@@ -0,0 +1,64 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct BracketParams {
+    stop_loss_price: u64,
+    take_profit_price: u64,
+    expires_at: i64,
+}
+
+#[derive(Accounts)]
+pub struct SetBracket<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
+    pub id: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump = order.bump,
+        constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+    )]
+    pub order: Box<Account<'info, Order>>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.authority == signer.key() @ErrorCode::IncorrectSigner // only user can set the triggers
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+}
+
+// Setting a new bracket re-arms both legs, passing all values as 0 removes the bracket
+pub fn handler(ctx: Context<SetBracket>, params: BracketParams) -> Result<()> {
+    if params.stop_loss_price > 0
+        && params.take_profit_price > 0
+        && params.stop_loss_price >= params.take_profit_price
+    {
+        return Err(ErrorCode::InvalidBracket.into());
+    }
+
+    let current_time = Clock::get()?.unix_timestamp;
+    if params.expires_at < 0 || (params.expires_at > 0 && params.expires_at <= current_time) {
+        return Err(ErrorCode::InvalidBracket.into());
+    }
+
+    ctx.accounts.order.bracket = Bracket {
+        stop_loss_price: params.stop_loss_price,
+        take_profit_price: params.take_profit_price,
+        expires_at: params.expires_at,
+        fired: TriggerLeg::None,
+    };
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetChunkedLiquidation<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
+    pub id: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump = order.bump,
+        constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+    )]
+    pub order: Box<Account<'info, Order>>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.authority == signer.key() @ErrorCode::IncorrectSigner // only user can configure how the delegate liquidates
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+}
+
+// max_amount: maximum amount of the token vault the delegate can sell per liquidation chunk, 0 disables chunked liquidation
+// interval: minimum seconds between two chunks executed by the delegate
+pub fn handler(ctx: Context<SetChunkedLiquidation>, max_amount: u64, interval: i64) -> Result<()> {
+    if interval < 0 || (max_amount == 0 && interval != 0) {
+        return Err(ErrorCode::InvalidChunkConfig.into());
+    }
+
+    let order = &mut ctx.accounts.order;
+    order.chunk_max_amount = max_amount;
+    order.chunk_interval = interval;
+
+    Ok(())
+}
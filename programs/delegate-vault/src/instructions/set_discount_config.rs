@@ -0,0 +1,89 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::Mint,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct DiscountConfigParams {
+    lock_duration: i64,
+    tiers: Vec<DiscountTier>,
+}
+
+// mint: protocol token staked to get the discount, it can not be changed once the discount config is created
+#[derive(Accounts)]
+pub struct InitDiscountConfig<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        init,
+        space = DiscountConfig::LEN,
+        payer = signer,
+        seeds = [b"discount_config".as_ref()],
+        bump,
+    )]
+    pub discount_config: Box<Account<'info, DiscountConfig>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EditDiscountConfig<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [b"discount_config".as_ref()],
+        bump = discount_config.bump,
+    )]
+    pub discount_config: Box<Account<'info, DiscountConfig>>,
+}
+
+pub fn init_handler(ctx: Context<InitDiscountConfig>, params: DiscountConfigParams) -> Result<()> {
+    let discount_config = &mut ctx.accounts.discount_config;
+    discount_config.mint = ctx.accounts.mint.key();
+    discount_config.bump = ctx.bumps.discount_config;
+    set_discount_config(discount_config, params)
+}
+
+pub fn edit_handler(ctx: Context<EditDiscountConfig>, params: DiscountConfigParams) -> Result<()> {
+    set_discount_config(&mut ctx.accounts.discount_config, params)
+}
+
+fn set_discount_config(discount_config: &mut DiscountConfig, params: DiscountConfigParams) -> Result<()> {
+    if params.tiers.len() > DiscountConfig::MAX_TIERS || params.lock_duration < 0 {
+        return Err(ErrorCode::InvalidDiscountTiers.into());
+    }
+
+    if params.tiers.iter().any(|tier| tier.discount > 10000) {
+        return Err(ErrorCode::InvalidDiscountTiers.into());
+    }
+
+    if params.tiers.windows(2).any(|tiers| tiers[0].min_amount >= tiers[1].min_amount) {
+        return Err(ErrorCode::InvalidDiscountTiers.into());
+    }
+
+    discount_config.lock_duration = params.lock_duration;
+    discount_config.tiers = params.tiers;
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+// target: deposit mint or manager the override applies to, depending on the kind
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct FeeOverrideParams {
+    kind: FeeOverrideKind,
+    target: Pubkey,
+    subscribed_performance_fee: u16,
+    performance_fee: u16,
+}
+
+#[derive(Accounts)]
+#[instruction(params: FeeOverrideParams)]
+pub struct InitFeeOverride<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        init,
+        space = FeeOverride::LEN,
+        payer = signer,
+        seeds = [
+            params.kind.seed(),
+            params.target.as_ref(),
+        ],
+        bump,
+    )]
+    pub fee_override: Box<Account<'info, FeeOverride>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: FeeOverrideParams)]
+pub struct EditFeeOverride<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [
+            params.kind.seed(),
+            params.target.as_ref(),
+        ],
+        bump = fee_override.bump,
+    )]
+    pub fee_override: Box<Account<'info, FeeOverride>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseFeeOverride<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        close = signer,
+    )]
+    pub fee_override: Box<Account<'info, FeeOverride>>,
+}
+
+pub fn init_handler(ctx: Context<InitFeeOverride>, params: FeeOverrideParams) -> Result<()> {
+    ctx.accounts.fee_override.bump = ctx.bumps.fee_override;
+    set_fee_override(&mut ctx.accounts.fee_override, params)
+}
+
+pub fn edit_handler(ctx: Context<EditFeeOverride>, params: FeeOverrideParams) -> Result<()> {
+    set_fee_override(&mut ctx.accounts.fee_override, params)
+}
+
+pub fn close_handler(_ctx: Context<CloseFeeOverride>) -> Result<()> {
+    Ok(())
+}
+
+fn set_fee_override(fee_override: &mut FeeOverride, params: FeeOverrideParams) -> Result<()> {
+    if params.performance_fee > 10000 || params.subscribed_performance_fee > 10000 {
+        return Err(ErrorCode::IncorrectFee.into());
+    }
+
+    fee_override.target = params.target;
+    fee_override.subscribed_performance_fee = params.subscribed_performance_fee;
+    fee_override.performance_fee = params.performance_fee;
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use {
+    crate::{error::ErrorCode, fees::split_amount, state::*},
+    anchor_lang::prelude::*,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct FeeScheduleParams {
+    recipients: Vec<FeeRecipient>,
+    dust_recipient: u8,
+    enabled: bool,
+}
+
+#[derive(Accounts)]
+pub struct InitFeeSchedule<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        init,
+        space = FeeSchedule::LEN,
+        payer = signer,
+        seeds = [b"fee_schedule".as_ref()],
+        bump,
+    )]
+    pub fee_schedule: Box<Account<'info, FeeSchedule>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EditFeeSchedule<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [b"fee_schedule".as_ref()],
+        bump = fee_schedule.bump,
+    )]
+    pub fee_schedule: Box<Account<'info, FeeSchedule>>,
+}
+
+pub fn init_handler(ctx: Context<InitFeeSchedule>, params: FeeScheduleParams) -> Result<()> {
+    ctx.accounts.fee_schedule.bump = ctx.bumps.fee_schedule;
+    set_fee_schedule(&mut ctx.accounts.config, &mut ctx.accounts.fee_schedule, params)
+}
+
+pub fn edit_handler(ctx: Context<EditFeeSchedule>, params: FeeScheduleParams) -> Result<()> {
+    set_fee_schedule(&mut ctx.accounts.config, &mut ctx.accounts.fee_schedule, params)
+}
+
+fn set_fee_schedule(config: &mut Config, fee_schedule: &mut FeeSchedule, params: FeeScheduleParams) -> Result<()> {
+    if params.recipients.is_empty() || params.recipients.len() > FeeSchedule::MAX_RECIPIENTS {
+        return Err(ErrorCode::InvalidFeeSchedule.into());
+    }
+
+    // Validates the shares add up to 10000 and the dust recipient exists
+    let shares: Vec<u16> = params.recipients.iter().map(|recipient| recipient.share).collect();
+    split_amount(0, &shares, params.dust_recipient as usize)?;
+
+    fee_schedule.recipients = params.recipients;
+    fee_schedule.dust_recipient = params.dust_recipient;
+    config.fee_schedule_enabled = params.enabled;
+
+    Ok(())
+}
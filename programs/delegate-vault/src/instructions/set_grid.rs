@@ -0,0 +1,74 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GridParams {
+    lower_price: u64,
+    upper_price: u64,
+    count: u8,
+    level_size: u64,
+}
+
+#[derive(Accounts)]
+pub struct SetGrid<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
+    pub id: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump = order.bump,
+        constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+    )]
+    pub order: Box<Account<'info, Order>>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.authority == signer.key() @ErrorCode::IncorrectSigner // only user can set the grid bounds
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+}
+
+// Passing a count of 0 disables grid trading, the grid can only be changed when no level is waiting to be sold
+pub fn handler(ctx: Context<SetGrid>, params: GridParams) -> Result<()> {
+    let order = &mut ctx.accounts.order;
+
+    if order.grid.filled != 0 {
+        return Err(ErrorCode::GridLevelsFilled.into());
+    }
+
+    if params.count == 0 {
+        order.grid = Grid::default();
+        return Ok(());
+    }
+
+    if params.count < 2
+        || params.count > Grid::MAX_LEVELS
+        || params.lower_price == 0
+        || params.lower_price >= params.upper_price
+        || params.level_size == 0
+    {
+        return Err(ErrorCode::InvalidGrid.into());
+    }
+
+    order.grid = Grid {
+        lower_price: params.lower_price,
+        upper_price: params.upper_price,
+        count: params.count,
+        level_size: params.level_size,
+        filled: 0,
+    };
+
+    Ok(())
+}
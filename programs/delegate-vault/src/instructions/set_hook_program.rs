@@ -0,0 +1,79 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct HookProgramParams {
+    allowed: bool,
+}
+
+#[derive(Accounts)]
+pub struct InitHookProgram<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        init,
+        space = HookProgram::LEN,
+        payer = signer,
+        seeds = [
+            b"hook_program".as_ref(),
+            program.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub hook_program: Box<Account<'info, HookProgram>>,
+    /// CHECK: transfer hook program
+    #[account(executable)]
+    pub program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EditHookProgram<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [
+            b"hook_program".as_ref(),
+            hook_program.program_id.as_ref(),
+        ],
+        bump = hook_program.bump,
+    )]
+    pub hook_program: Box<Account<'info, HookProgram>>,
+}
+
+pub fn init_handler(ctx: Context<InitHookProgram>, params: HookProgramParams) -> Result<()> {
+    let hook_program = &mut ctx.accounts.hook_program;
+    hook_program.program_id = ctx.accounts.program.key();
+    hook_program.allowed = params.allowed;
+    hook_program.bump = ctx.bumps.hook_program;
+
+    Ok(())
+}
+
+pub fn edit_handler(ctx: Context<EditHookProgram>, params: HookProgramParams) -> Result<()> {
+    ctx.accounts.hook_program.allowed = params.allowed;
+
+    Ok(())
+}
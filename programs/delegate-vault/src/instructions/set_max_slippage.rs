@@ -0,0 +1,44 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetMaxSlippage<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    // ephemeral account to use as order ID, should be stored on db, to build the swap, liquidate and withdraw instructions
+    pub id: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"order".as_ref(),
+            manager.key().as_ref(),
+            id.key().as_ref(),
+        ],
+        bump = order.bump,
+        constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+    )]
+    pub order: Box<Account<'info, Order>>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.authority == signer.key() @ErrorCode::IncorrectSigner // only user can configure the slippage of the routes
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+}
+
+// max_slippage_bps: max slippage of the Jupiter routes executed for the order, by the user or the delegate
+pub fn handler(ctx: Context<SetMaxSlippage>, max_slippage_bps: u16) -> Result<()> {
+    if max_slippage_bps > 10000 {
+        return Err(ErrorCode::InvalidMaxSlippage.into());
+    }
+
+    ctx.accounts.order.max_slippage_bps = max_slippage_bps;
+
+    Ok(())
+}
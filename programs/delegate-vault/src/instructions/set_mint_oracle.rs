@@ -0,0 +1,80 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::Mint,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct MintOracleParams {
+    feed_id: [u8; 32],
+}
+
+#[derive(Accounts)]
+pub struct InitMintOracle<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        init,
+        space = MintOracle::LEN,
+        payer = signer,
+        seeds = [
+            b"mint_oracle".as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub mint_oracle: Box<Account<'info, MintOracle>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EditMintOracle<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [
+            b"mint_oracle".as_ref(),
+            mint_oracle.mint.as_ref(),
+        ],
+        bump = mint_oracle.bump,
+    )]
+    pub mint_oracle: Box<Account<'info, MintOracle>>,
+}
+
+pub fn init_handler(ctx: Context<InitMintOracle>, params: MintOracleParams) -> Result<()> {
+    let mint_oracle = &mut ctx.accounts.mint_oracle;
+    mint_oracle.mint = ctx.accounts.mint.key();
+    mint_oracle.decimals = ctx.accounts.mint.decimals;
+    mint_oracle.feed_id = params.feed_id;
+    mint_oracle.bump = ctx.bumps.mint_oracle;
+
+    Ok(())
+}
+
+// Only the feed can be replaced, e.g. when Pyth migrates a feed, the decimals are the ones of the mint
+pub fn edit_handler(ctx: Context<EditMintOracle>, params: MintOracleParams) -> Result<()> {
+    ctx.accounts.mint_oracle.feed_id = params.feed_id;
+
+    Ok(())
+}
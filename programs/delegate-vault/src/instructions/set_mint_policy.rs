@@ -0,0 +1,90 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::Mint,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct MintPolicyParams {
+    allow_deposit: bool,
+    allow_target: bool,
+    min_deposit: u64,
+    max_tvl: u64,
+}
+
+#[derive(Accounts)]
+pub struct InitMintPolicy<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        init,
+        space = MintPolicy::LEN,
+        payer = signer,
+        seeds = [
+            b"mint_policy".as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub mint_policy: Box<Account<'info, MintPolicy>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EditMintPolicy<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [
+            b"mint_policy".as_ref(),
+            mint_policy.mint.as_ref(),
+        ],
+        bump = mint_policy.bump,
+    )]
+    pub mint_policy: Box<Account<'info, MintPolicy>>,
+}
+
+pub fn init_handler(ctx: Context<InitMintPolicy>, params: MintPolicyParams) -> Result<()> {
+    let mint_policy = &mut ctx.accounts.mint_policy;
+    mint_policy.mint = ctx.accounts.mint.key();
+    mint_policy.tvl = 0;
+    mint_policy.bump = ctx.bumps.mint_policy;
+    set_mint_policy(mint_policy, params);
+
+    Ok(())
+}
+
+// The tracked TVL is kept, lowering max_tvl below it only blocks new deposits
+pub fn edit_handler(ctx: Context<EditMintPolicy>, params: MintPolicyParams) -> Result<()> {
+    set_mint_policy(&mut ctx.accounts.mint_policy, params);
+
+    Ok(())
+}
+
+fn set_mint_policy(mint_policy: &mut MintPolicy, params: MintPolicyParams) {
+    mint_policy.allow_deposit = params.allow_deposit;
+    mint_policy.allow_target = params.allow_target;
+    mint_policy.min_deposit = params.min_deposit;
+    mint_policy.max_tvl = params.max_tvl;
+}
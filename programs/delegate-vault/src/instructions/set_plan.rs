@@ -0,0 +1,105 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    crate::token_extensions::check_payment_mint,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::Mint,
+};
+
+// id: identifies the plan, it can not be changed once the plan is created
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PlanParams {
+    id: u16,
+    price: u64,
+    duration: i64,
+    performance_fee: u16,
+    active: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(params: PlanParams)]
+pub struct InitPlan<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        init,
+        space = Plan::LEN,
+        payer = signer,
+        seeds = [
+            b"plan".as_ref(),
+            params.id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub plan: Box<Account<'info, Plan>>,
+    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: PlanParams)]
+pub struct EditPlan<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [
+            b"plan".as_ref(),
+            params.id.to_le_bytes().as_ref(),
+        ],
+        bump = plan.bump,
+    )]
+    pub plan: Box<Account<'info, Plan>>,
+    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+pub fn init_handler(ctx: Context<InitPlan>, params: PlanParams) -> Result<()> {
+    check_payment_mint(&ctx.accounts.payment_mint.to_account_info())?;
+
+    let plan = &mut ctx.accounts.plan;
+    plan.bump = ctx.bumps.plan;
+    set_plan(plan, ctx.accounts.payment_mint.key(), params)
+}
+
+pub fn edit_handler(ctx: Context<EditPlan>, params: PlanParams) -> Result<()> {
+    check_payment_mint(&ctx.accounts.payment_mint.to_account_info())?;
+
+    set_plan(&mut ctx.accounts.plan, ctx.accounts.payment_mint.key(), params)
+}
+
+fn set_plan(plan: &mut Plan, payment_mint: Pubkey, params: PlanParams) -> Result<()> {
+    if params.performance_fee > 10000 {
+        return Err(ErrorCode::IncorrectFee.into());
+    }
+
+    if params.duration <= 0 {
+        return Err(ErrorCode::InvalidPlan.into());
+    }
+
+    plan.id = params.id;
+    plan.price = params.price;
+    plan.payment_mint = payment_mint;
+    plan.duration = params.duration;
+    plan.performance_fee = params.performance_fee;
+    plan.active = params.active;
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    crate::token_extensions::check_payment_mint,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::Mint,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PlanPriceParams {
+    price: u64,
+    active: bool,
+}
+
+// mint: native mint to accept payments in SOL
+#[derive(Accounts)]
+pub struct InitPlanPrice<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        seeds = [
+            b"plan".as_ref(),
+            plan.id.to_le_bytes().as_ref(),
+        ],
+        bump = plan.bump,
+    )]
+    pub plan: Box<Account<'info, Plan>>,
+    #[account(
+        init,
+        space = PlanPrice::LEN,
+        payer = signer,
+        seeds = [
+            b"plan_price".as_ref(),
+            plan.key().as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub plan_price: Box<Account<'info, PlanPrice>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EditPlanPrice<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [
+            b"plan_price".as_ref(),
+            plan_price.plan.as_ref(),
+            plan_price.mint.as_ref(),
+        ],
+        bump = plan_price.bump,
+    )]
+    pub plan_price: Box<Account<'info, PlanPrice>>,
+}
+
+pub fn init_handler(ctx: Context<InitPlanPrice>, params: PlanPriceParams) -> Result<()> {
+    check_payment_mint(&ctx.accounts.mint.to_account_info())?;
+
+    let plan_price = &mut ctx.accounts.plan_price;
+    plan_price.plan = ctx.accounts.plan.key();
+    plan_price.mint = ctx.accounts.mint.key();
+    plan_price.bump = ctx.bumps.plan_price;
+    set_plan_price(plan_price, params)
+}
+
+pub fn edit_handler(ctx: Context<EditPlanPrice>, params: PlanPriceParams) -> Result<()> {
+    set_plan_price(&mut ctx.accounts.plan_price, params)
+}
+
+fn set_plan_price(plan_price: &mut PlanPrice, params: PlanPriceParams) -> Result<()> {
+    if params.price == 0 {
+        return Err(ErrorCode::InvalidPlan.into());
+    }
+
+    plan_price.price = params.price;
+    plan_price.active = params.active;
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetReferrer<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+        constraint = manager.authority == signer.key() @ErrorCode::IncorrectSigner,
+        constraint = manager.referrer == Pubkey::default() @ErrorCode::ReferrerAlreadySet, // referrer can only be set once
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    #[account(
+        seeds = [
+            b"referrer".as_ref(),
+            referrer.wallet.as_ref(),
+        ],
+        bump = referrer.bump,
+        constraint = referrer.wallet != signer.key() @ErrorCode::InvalidReferrer,
+    )]
+    pub referrer: Box<Account<'info, Referrer>>,
+}
+
+pub fn handler(ctx: Context<SetReferrer>) -> Result<()> {
+    ctx.accounts.manager.referrer = ctx.accounts.referrer.key();
+
+    Ok(())
+}
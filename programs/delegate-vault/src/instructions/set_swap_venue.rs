@@ -0,0 +1,107 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SwapVenueParams {
+    routes: Vec<VenueRoute>,
+    active: bool,
+}
+
+#[derive(Accounts)]
+pub struct InitSwapVenue<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        init,
+        space = SwapVenue::LEN,
+        payer = signer,
+        seeds = [
+            b"swap_venue".as_ref(),
+            swap_program.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub swap_venue: Box<Account<'info, SwapVenue>>,
+    /// CHECK: program of the swap venue, only required to be executable
+    #[account(executable)]
+    pub swap_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EditSwapVenue<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [
+            b"swap_venue".as_ref(),
+            swap_venue.program_id.as_ref(),
+        ],
+        bump = swap_venue.bump,
+    )]
+    pub swap_venue: Box<Account<'info, SwapVenue>>,
+}
+
+pub fn init_handler(ctx: Context<InitSwapVenue>, params: SwapVenueParams) -> Result<()> {
+    let swap_venue = &mut ctx.accounts.swap_venue;
+    swap_venue.program_id = ctx.accounts.swap_program.key();
+    swap_venue.bump = ctx.bumps.swap_venue;
+    set_swap_venue(swap_venue, params)
+}
+
+// Removing a route or deactivating the venue takes effect on the next swap, there is no state tied to the routes
+pub fn edit_handler(ctx: Context<EditSwapVenue>, params: SwapVenueParams) -> Result<()> {
+    set_swap_venue(&mut ctx.accounts.swap_venue, params)
+}
+
+fn set_swap_venue(swap_venue: &mut SwapVenue, params: SwapVenueParams) -> Result<()> {
+    if params.routes.len() > SwapVenue::MAX_ROUTES {
+        return Err(ErrorCode::InvalidSwapVenue.into());
+    }
+
+    for (i, route) in params.routes.iter().enumerate() {
+        if params.routes[..i].iter().any(|other| other.discriminator == route.discriminator) {
+            return Err(ErrorCode::InvalidSwapVenue.into());
+        }
+
+        // Offsets are counted from the end of the data so they have to fit the value they point to
+        if let RouteArgsLayout::Tail { in_amount_offset, slippage_offset, .. } = route.args_layout {
+            if (in_amount_offset != 0 && in_amount_offset < 8) || slippage_offset == 1 {
+                return Err(ErrorCode::InvalidSwapVenue.into());
+            }
+        }
+
+        // Only the routes placing or cancelling resting orders point to a resting order account
+        let is_resting_route = route.kind != RouteKind::Swap;
+        if is_resting_route != (route.resting_order_idx != u8::MAX) {
+            return Err(ErrorCode::InvalidSwapVenue.into());
+        }
+    }
+
+    swap_venue.routes = params.routes;
+    swap_venue.active = params.active;
+
+    Ok(())
+}
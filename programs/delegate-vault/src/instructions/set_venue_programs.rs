@@ -0,0 +1,97 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VenueProgramsParams {
+    programs: Vec<Pubkey>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: VenueProgramsParams)]
+pub struct InitVenuePrograms<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        seeds = [
+            b"swap_venue".as_ref(),
+            swap_venue.program_id.as_ref(),
+        ],
+        bump = swap_venue.bump,
+    )]
+    pub swap_venue: Box<Account<'info, SwapVenue>>,
+    #[account(
+        init,
+        space = VenuePrograms::len(params.programs.len()),
+        payer = signer,
+        seeds = [
+            b"venue_programs".as_ref(),
+            swap_venue.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub venue_programs: Box<Account<'info, VenuePrograms>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: VenueProgramsParams)]
+pub struct EditVenuePrograms<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"config".as_ref(),
+        ],
+        bump = config.bump,
+        constraint = signer.key() == config.authority 
+            @ErrorCode::IncorrectSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [
+            b"venue_programs".as_ref(),
+            venue_programs.swap_venue.as_ref(),
+        ],
+        bump = venue_programs.bump,
+        realloc = VenuePrograms::len(params.programs.len()),
+        realloc::payer = signer,
+        realloc::zero = false,
+    )]
+    pub venue_programs: Box<Account<'info, VenuePrograms>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_handler(ctx: Context<InitVenuePrograms>, params: VenueProgramsParams) -> Result<()> {
+    let venue_programs = &mut ctx.accounts.venue_programs;
+    venue_programs.swap_venue = ctx.accounts.swap_venue.key();
+    venue_programs.bump = ctx.bumps.venue_programs;
+    set_venue_programs(venue_programs, params)
+}
+
+// The list is replaced as a whole, the account is resized to it and the rent difference paid or refunded to the signer
+pub fn edit_handler(ctx: Context<EditVenuePrograms>, params: VenueProgramsParams) -> Result<()> {
+    set_venue_programs(&mut ctx.accounts.venue_programs, params)
+}
+
+fn set_venue_programs(venue_programs: &mut VenuePrograms, params: VenueProgramsParams) -> Result<()> {
+    if params.programs.len() > VenuePrograms::MAX_PROGRAMS {
+        return Err(ErrorCode::InvalidSwapVenue.into());
+    }
+
+    venue_programs.programs = params.programs;
+
+    Ok(())
+}
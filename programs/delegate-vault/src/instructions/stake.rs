@@ -0,0 +1,125 @@
+use {
+    crate::state::*,
+    crate::error::ErrorCode,
+    crate::token_extensions::transfer_checked_with_hook,
+    anchor_lang::prelude::*,
+    anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct StakeTokens<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [
+            b"manager".as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump = manager.bump,
+    )]
+    pub manager: Box<Account<'info, Manager>>,
+    #[account(
+        seeds = [b"discount_config".as_ref()],
+        bump = discount_config.bump,
+    )]
+    pub discount_config: Box<Account<'info, DiscountConfig>>,
+    #[account(
+        constraint = mint.key() == discount_config.mint @ErrorCode::IncorrectMint,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake".as_ref(),
+            manager.key().as_ref(),
+        ],
+        bump = stake.bump,
+    )]
+    pub stake: Box<Account<'info, Stake>>,
+    #[account(
+        mut,
+        seeds = [
+            b"stake_vault".as_ref(),
+            manager.key().as_ref(),
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = stake,
+        token::token_program = token_program,
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint=mint,
+        associated_token::authority=signer,
+        associated_token::token_program=token_program,
+    )]
+    pub signer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// remaining accounts (stake and unstake): extra accounts of the transfer hook of the mint (if any)
+pub fn stake_handler<'info>(ctx: Context<'_, '_, 'info, 'info, StakeTokens<'info>>, amount: u64) -> Result<()> {
+    let vault_amount_before = ctx.accounts.stake_vault.amount;
+    transfer_checked_with_hook(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.signer_ata.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.stake_vault.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.mint.decimals,
+        &[],
+    )?;
+
+    // Record the amount actually received, Token-2022 mints with transfer fee deliver less than amount
+    ctx.accounts.stake_vault.reload()?;
+    let received_amount = ctx.accounts.stake_vault.amount - vault_amount_before;
+
+    // Every stake locks the whole staked amount again
+    let stake = &mut ctx.accounts.stake;
+    stake.amount = stake.amount
+        .checked_add(received_amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    stake.unlock_at = Clock::get()?.unix_timestamp
+        .checked_add(ctx.accounts.discount_config.lock_duration)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+pub fn unstake_handler<'info>(ctx: Context<'_, '_, 'info, 'info, StakeTokens<'info>>, amount: u64) -> Result<()> {
+    let stake = &ctx.accounts.stake;
+
+    if Clock::get()?.unix_timestamp < stake.unlock_at {
+        return Err(ErrorCode::StakeLocked.into());
+    }
+
+    if amount > stake.amount {
+        return Err(ErrorCode::InsufficientStake.into());
+    }
+
+    let manager_key = ctx.accounts.manager.key();
+    let seeds = &[
+        b"stake".as_ref(),
+        manager_key.as_ref(),
+        &[stake.bump],
+    ];
+
+    transfer_checked_with_hook(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.stake_vault.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.signer_ata.to_account_info(),
+        ctx.accounts.stake.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.mint.decimals,
+        &[&seeds[..]],
+    )?;
+
+    ctx.accounts.stake.amount -= amount;
+
+    Ok(())
+}
@@ -1,11 +1,10 @@
 use {
-    crate::{error::ErrorCode, state::*},
+    crate::{error::ErrorCode, fees::{accrue_management_fee, apply_high_water_mark, apply_stake_discount, calculate_fee, performance_fee_rate, FeeTransfer}, state::*, token_extensions::{harvest_withheld_fees, transfer_checked_with_hook, transfer_fee}},
     anchor_lang::prelude::*,
     anchor_spl::{
         associated_token::AssociatedToken,
-        token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+        token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface},
     },
-    spl_math::precise_number::PreciseNumber,
 };
 
 #[derive(Accounts)]
@@ -23,11 +22,24 @@ pub struct Withdraw<'info> {
         ],
         bump,
         constraint = order.manager == manager.key() @ErrorCode::IncorrectManager,
+        constraint = order.resting_order == Pubkey::default() @ErrorCode::RestingOrderOpen,
         close = signer, // close the order vault
     )]
     pub order: Box<Account<'info, Order>>,
+    // only for the orders with a portfolio, closed with the order to return its rent
+    #[account(
+        mut,
+        seeds = [
+            b"portfolio".as_ref(),
+            order.key().as_ref(),
+        ],
+        bump = portfolio.bump,
+        close = signer,
+    )]
+    pub portfolio: Option<Box<Account<'info, Portfolio>>>,
 
     #[account(
+        mut,
         seeds = [
             b"manager".as_ref(),
             signer.key().as_ref(),
@@ -46,10 +58,6 @@ pub struct Withdraw<'info> {
     )]
     pub config: Box<Account<'info, Config>>,
 
-    /// CHECK: only validate address
-    #[account(constraint = performance_receiver.key() == config.performance_receiver @ErrorCode::IncorrectReceiver)]
-    pub performance_receiver: AccountInfo<'info>,
-
     #[account(
         mut,
         seeds = [
@@ -73,20 +81,100 @@ pub struct Withdraw<'info> {
     pub user_ata: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        associated_token::mint=deposit_mint,
-        associated_token::authority=performance_receiver,
-        associated_token::token_program=token_program,
+        seeds = [
+            b"fee_vault".as_ref(),
+            deposit_mint.key().as_ref(),
+        ],
+        bump,
+        token::mint = deposit_mint,
+        token::authority = config,
+        token::token_program = token_program,
     )]
-    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // only required when the config has the fee schedule enabled, recipients ATAs are passed as remaining accounts
     #[account(
+        seeds = [b"fee_schedule".as_ref()],
+        bump = fee_schedule.bump,
+    )]
+    pub fee_schedule: Option<Box<Account<'info, FeeSchedule>>>,
+    // always passed, the share of the referrer stays with the protocol while the referrer has no vault for the mint
+    /// CHECK: referrer vault PDA of the mint, only paid once initialized by init_referrer_vault
+    #[account(
+        mut,
+        seeds = [
+            b"referrer_vault".as_ref(),
+            manager.referrer.as_ref(),
+            deposit_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub referrer_vault: UncheckedAccount<'info>,
+    // mutable to harvest the Token-2022 transfer fees withheld on the order vault before closing it
+    #[account(
+        mut,
         constraint = order.deposit_mint == deposit_mint.key() @ErrorCode::IncorrectMint
     )]
     pub deposit_mint: Box<InterfaceAccount<'info, Mint>>,
+    // always passed, orders opened before the mint policies existed can withdraw even if the mint has none
+    /// CHECK: PDA of the deposit mint, its TVL is only reduced once initialized
+    #[account(
+        mut,
+        seeds = [
+            b"mint_policy".as_ref(),
+            deposit_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub mint_policy: UncheckedAccount<'info>,
+    // only required when the config has the high-water-mark mode enabled
+    #[account(
+        mut,
+        seeds = [
+            b"high_water_mark".as_ref(),
+            manager.key().as_ref(),
+            deposit_mint.key().as_ref(),
+        ],
+        bump = high_water_mark.bump,
+    )]
+    pub high_water_mark: Option<Box<Account<'info, HighWaterMark>>>,
+    // fee overrides for the deposit mint and the manager, always passed so an override raising the fee can not be left out
+    /// CHECK: PDA of the deposit mint, ignored while it is not initialized
+    #[account(
+        seeds = [
+            b"mint_fee_override".as_ref(),
+            deposit_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub mint_fee_override: UncheckedAccount<'info>,
+    /// CHECK: PDA of the manager, ignored while it is not initialized
+    #[account(
+        seeds = [
+            b"manager_fee_override".as_ref(),
+            manager.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub manager_fee_override: UncheckedAccount<'info>,
+    // staking discount, only if the manager staked the protocol token
+    #[account(
+        seeds = [b"discount_config".as_ref()],
+        bump = discount_config.bump,
+    )]
+    pub discount_config: Option<Box<Account<'info, DiscountConfig>>>,
+    #[account(
+        seeds = [
+            b"stake".as_ref(),
+            manager.key().as_ref(),
+        ],
+        bump = stake.bump,
+    )]
+    pub stake: Option<Box<Account<'info, Stake>>>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn handler<'info>(ctx: Context<Withdraw>) -> Result<()> {
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>) -> Result<()> {
     let manager = &ctx.accounts.manager;
     let signer_key = ctx.accounts.signer.key();
     let seeds = &[
@@ -100,95 +188,123 @@ pub fn handler<'info>(ctx: Context<Withdraw>) -> Result<()> {
         return Err(ErrorCode::EmptyOrderVault.into());
     }
     
-    let deposit_amount = ctx.accounts.order.deposit_amount;    
-    let profit = if current_amount > deposit_amount {
-        current_amount - deposit_amount
+    // Settle the management fee accrued until now before charging the performance fee
+    let current_time = Clock::get()?.unix_timestamp;
+    let order = &mut ctx.accounts.order;
+    accrue_management_fee(order, manager, &ctx.accounts.config, current_time)?;
+    let management_fee = order.management_fee_accrued.min(current_amount);
+    order.management_fee_accrued = 0;
+
+    // Fee withheld by Token-2022 transfer fee mints when the vault is emptied, a cost of the order and not a profit
+    let withheld_fee = transfer_fee(&ctx.accounts.deposit_mint.to_account_info(), current_amount - management_fee)?;
+    let net_amount = current_amount - management_fee - withheld_fee;
+
+    let deposit_amount = order.deposit_amount;
+    let mint_policy_info = ctx.accounts.mint_policy.to_account_info();
+    if let Some(mut mint_policy) = load_if_initialized::<MintPolicy>(&mint_policy_info)? {
+        mint_policy.remove_deposit(order);
+        store(&mint_policy_info, &mint_policy)?;
+    }
+    let profit = if ctx.accounts.config.high_water_mark {
+        // Losses of previous orders of this mint have to be recovered before paying performance fee again
+        let high_water_mark = ctx.accounts.high_water_mark
+            .as_mut()
+            .ok_or(ErrorCode::HighWaterMarkRequired)?;
+        let pnl: i64 = (net_amount as i128 - deposit_amount as i128)
+            .try_into()
+            .map_err(|_| ErrorCode::NumericalOverflow)?;
+        apply_high_water_mark(high_water_mark, pnl)?
     } else {
-        0
+        net_amount.saturating_sub(deposit_amount)
     };
     
-    // Constants for fee calculations
-    const BASIS_POINTS: u128 = 10000;  // 100% = 10000 basis points
-    
     // Calculate performance fee only if there is profit
     let performance_fee = if profit > 0 {
-        // Check if user has active subscription
-        let current_time = Clock::get()?.unix_timestamp;
-        
-        // Determine fee rate based on subscription status
-        let fee_rate: u16 = if manager.end_subscription > current_time {
-            // User has active subscription, use subscribed rate
-            ctx.accounts.config.subscribed_performance_fee
-        } else {
-            // User not subscribed, use regular rate
-            ctx.accounts.config.performance_fee
-        };
-
-        // Convert profit and fee rate to PreciseNumber for accurate calculation
-        let profit_precise = PreciseNumber::new(profit as u128)
-            .ok_or(ErrorCode::NumericalOverflow)?;
-        
-        // Convert fee rate from basis points (e.g., 250 = 2.50%) to decimal
-        let fee_rate_precise = PreciseNumber::new(fee_rate as u128)
-            .ok_or(ErrorCode::NumericalOverflow)?;
-            
-        let basis_points_precise = PreciseNumber::new(BASIS_POINTS)
-            .ok_or(ErrorCode::NumericalOverflow)?;
-
-        // Calculate fee rate in decimal form (divide by 10000)
-        let fee_rate_decimal = fee_rate_precise
-            .checked_div(&basis_points_precise)
-            .ok_or(ErrorCode::NumericalOverflow)?;
-
-        // Calculate fee amount: profit * fee_rate
-        let fee_amount_precise = profit_precise
-            .checked_mul(&fee_rate_decimal)
-            .ok_or(ErrorCode::NumericalOverflow)?;
-
-        // Convert back to u64
-        fee_amount_precise
-            .to_imprecise()
-            .ok_or(ErrorCode::NumericalOverflow)?
-            .try_into()
-            .map_err(|_| ErrorCode::NumericalOverflow)?
+        // Determine fee rate based on the fee overrides and subscription status
+        let mint_fee_override = load_if_initialized::<FeeOverride>(&ctx.accounts.mint_fee_override)?;
+        let manager_fee_override = load_if_initialized::<FeeOverride>(&ctx.accounts.manager_fee_override)?;
+        let fee_rate = performance_fee_rate(
+            &ctx.accounts.config,
+            manager,
+            mint_fee_override.as_ref(),
+            manager_fee_override.as_ref(),
+            current_time,
+        );
+        let fee_rate = apply_stake_discount(
+            fee_rate,
+            ctx.accounts.discount_config.as_deref().map(|discount_config| &**discount_config),
+            ctx.accounts.stake.as_deref().map(|stake| &**stake),
+        )?;
+
+        calculate_fee(profit, fee_rate)?
     } else {
         0
     };
-    
-    let withdraw_amount = current_amount - performance_fee;
 
-    transfer_checked(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            TransferChecked {
-                from: ctx.accounts.order_vault.to_account_info(),
-                to: ctx.accounts.user_ata.to_account_info(),
-                authority: ctx.accounts.manager.to_account_info(),
-                mint: ctx.accounts.deposit_mint.to_account_info(),
-            },
-            &[&seeds[..]],
-        ),
+    let total_fee = management_fee
+        .checked_add(performance_fee)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    let withdraw_amount = current_amount - total_fee;
+
+    // Fee schedule recipients go first on the remaining accounts, then the extra accounts of the transfer hook
+    let recipients_count = match &ctx.accounts.fee_schedule {
+        Some(fee_schedule) if ctx.accounts.config.fee_schedule_enabled => fee_schedule.recipients.len(),
+        _ => 0,
+    };
+    if ctx.remaining_accounts.len() < recipients_count {
+        return Err(ErrorCode::InvalidRemainingAccounts.into());
+    }
+    let (recipients, extra_accounts) = ctx.remaining_accounts.split_at(recipients_count);
+
+    transfer_checked_with_hook(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+        ctx.accounts.deposit_mint.to_account_info(),
+        ctx.accounts.user_ata.to_account_info(),
+        ctx.accounts.manager.to_account_info(),
+        extra_accounts,
         withdraw_amount,
         ctx.accounts.deposit_mint.decimals,
+        &[&seeds[..]],
     )?;
 
-    if performance_fee > 0 {
-        transfer_checked(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                TransferChecked {
-                    from: ctx.accounts.order_vault.to_account_info(),
-                    to: ctx.accounts.fee_vault.to_account_info(),
-                    authority: ctx.accounts.manager.to_account_info(),
-                    mint: ctx.accounts.deposit_mint.to_account_info(),
-                },
-                &[&seeds[..]],
-            ),
-            performance_fee,
-            ctx.accounts.deposit_mint.decimals,
-        )?;
+    let fee_transfer = FeeTransfer {
+        token_program: ctx.accounts.token_program.to_account_info(),
+        from: ctx.accounts.order_vault.to_account_info(),
+        authority: ctx.accounts.manager.to_account_info(),
+        mint: ctx.accounts.deposit_mint.to_account_info(),
+        decimals: ctx.accounts.deposit_mint.decimals,
+        signer_seeds: &[&seeds[..]],
+        extra_accounts,
+    };
+
+    // The referrer gets its share of the performance fee, the rest goes to the protocol
+    let referral_fee = fee_transfer.share_with_referrer(
+        manager,
+        ctx.accounts.referrer_vault.to_account_info(),
+        performance_fee,
+        ctx.accounts.config.referral_fee_share,
+    )?;
+
+    fee_transfer.distribute(
+        &ctx.accounts.config,
+        ctx.accounts.fee_schedule.as_deref().map(|fee_schedule| &**fee_schedule),
+        ctx.accounts.fee_vault.as_ref().map(|fee_vault| fee_vault.to_account_info()),
+        recipients,
+        total_fee - referral_fee,
+    )?;
+
+    // Recorded so the subscription period used at the subscribed rates is not refunded to the manager
+    if ctx.accounts.manager.is_subscribed(current_time) {
+        ctx.accounts.manager.subscribed_withdrawal_at = current_time;
     }
 
+    harvest_withheld_fees(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.deposit_mint.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+    )?;
+
     close_account(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
@@ -1,7 +1,13 @@
 pub mod state;
 pub mod error;
+pub mod events;
 mod instructions;
 mod permission;
+mod math;
+mod oracle;
+mod fees;
+mod ed25519;
+mod token_extensions;
 use {
     anchor_lang::prelude::*,
     instructions::*,
@@ -18,36 +24,252 @@ pub mod delegate_vault {
         init::handler(ctx, params)
     }
 
-    pub fn edit_config(ctx: Context<EditConfig>, performance_fee: u16) -> Result<()> {
-        edit_config::handler(ctx, performance_fee)
+    pub fn edit_config(ctx: Context<EditConfig>, params: EditConfigParams) -> Result<()> {
+        edit_config::handler(ctx, params)
+    }
+
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        migrate_config::handler(ctx)
+    }
+
+    pub fn migrate_manager(ctx: Context<MigrateManager>, params: MigrateManagerParams) -> Result<()> {
+        migrate_manager::handler(ctx, params)
+    }
+
+    pub fn migrate_order(ctx: Context<MigrateOrder>) -> Result<()> {
+        migrate_order::handler(ctx)
+    }
+
+    pub fn init_fee_vault(ctx: Context<InitFeeVault>) -> Result<()> {
+        init_fee_vault::handler(ctx)
+    }
+
+    pub fn claim_fees<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimFees<'info>>, shares: Vec<u16>) -> Result<()> {
+        claim_fees::handler(ctx, shares)
+    }
+
+    pub fn claim_sol_fees<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimSolFees<'info>>, shares: Vec<u16>) -> Result<()> {
+        claim_sol_fees::handler(ctx, shares)
+    }
+
+    pub fn init_fee_schedule(ctx: Context<InitFeeSchedule>, params: FeeScheduleParams) -> Result<()> {
+        set_fee_schedule::init_handler(ctx, params)
+    }
+
+    pub fn edit_fee_schedule(ctx: Context<EditFeeSchedule>, params: FeeScheduleParams) -> Result<()> {
+        set_fee_schedule::edit_handler(ctx, params)
+    }
+
+    pub fn init_fee_override(ctx: Context<InitFeeOverride>, params: FeeOverrideParams) -> Result<()> {
+        set_fee_override::init_handler(ctx, params)
+    }
+
+    pub fn edit_fee_override(ctx: Context<EditFeeOverride>, params: FeeOverrideParams) -> Result<()> {
+        set_fee_override::edit_handler(ctx, params)
+    }
+
+    pub fn close_fee_override(ctx: Context<CloseFeeOverride>) -> Result<()> {
+        set_fee_override::close_handler(ctx)
+    }
+
+    pub fn init_discount_config(ctx: Context<InitDiscountConfig>, params: DiscountConfigParams) -> Result<()> {
+        set_discount_config::init_handler(ctx, params)
+    }
+
+    pub fn edit_discount_config(ctx: Context<EditDiscountConfig>, params: DiscountConfigParams) -> Result<()> {
+        set_discount_config::edit_handler(ctx, params)
+    }
+
+    pub fn init_mint_policy(ctx: Context<InitMintPolicy>, params: MintPolicyParams) -> Result<()> {
+        set_mint_policy::init_handler(ctx, params)
+    }
+
+    pub fn edit_mint_policy(ctx: Context<EditMintPolicy>, params: MintPolicyParams) -> Result<()> {
+        set_mint_policy::edit_handler(ctx, params)
+    }
+
+    pub fn init_swap_venue(ctx: Context<InitSwapVenue>, params: SwapVenueParams) -> Result<()> {
+        set_swap_venue::init_handler(ctx, params)
+    }
+
+    pub fn edit_swap_venue(ctx: Context<EditSwapVenue>, params: SwapVenueParams) -> Result<()> {
+        set_swap_venue::edit_handler(ctx, params)
+    }
+
+    pub fn init_venue_programs(ctx: Context<InitVenuePrograms>, params: VenueProgramsParams) -> Result<()> {
+        set_venue_programs::init_handler(ctx, params)
+    }
+
+    pub fn edit_venue_programs(ctx: Context<EditVenuePrograms>, params: VenueProgramsParams) -> Result<()> {
+        set_venue_programs::edit_handler(ctx, params)
+    }
+
+    pub fn init_mint_oracle(ctx: Context<InitMintOracle>, params: MintOracleParams) -> Result<()> {
+        set_mint_oracle::init_handler(ctx, params)
+    }
+
+    pub fn edit_mint_oracle(ctx: Context<EditMintOracle>, params: MintOracleParams) -> Result<()> {
+        set_mint_oracle::edit_handler(ctx, params)
+    }
+
+    pub fn init_hook_program(ctx: Context<InitHookProgram>, params: HookProgramParams) -> Result<()> {
+        set_hook_program::init_handler(ctx, params)
+    }
+
+    pub fn edit_hook_program(ctx: Context<EditHookProgram>, params: HookProgramParams) -> Result<()> {
+        set_hook_program::edit_handler(ctx, params)
     }
 
     pub fn init_manager(ctx: Context<InitManager>) -> Result<()> {
         init_manager::handler(ctx)
     }
 
-    pub fn pay_subscription(ctx: Context<PaySubscription>, amount: u64) -> Result<()> {
-        pay_subscription::handler(ctx, amount)
+    pub fn init_high_water_mark(ctx: Context<InitHighWaterMark>) -> Result<()> {
+        init_high_water_mark::handler(ctx)
+    }
+
+    pub fn init_stake(ctx: Context<InitStake>) -> Result<()> {
+        init_stake::handler(ctx)
+    }
+
+    pub fn stake<'info>(ctx: Context<'_, '_, 'info, 'info, StakeTokens<'info>>, amount: u64) -> Result<()> {
+        stake::stake_handler(ctx, amount)
+    }
+
+    pub fn unstake<'info>(ctx: Context<'_, '_, 'info, 'info, StakeTokens<'info>>, amount: u64) -> Result<()> {
+        stake::unstake_handler(ctx, amount)
+    }
+
+    pub fn init_referrer(ctx: Context<InitReferrer>) -> Result<()> {
+        init_referrer::handler(ctx)
+    }
+
+    pub fn init_referrer_vault(ctx: Context<InitReferrerVault>) -> Result<()> {
+        init_referrer_vault::handler(ctx)
+    }
+
+    pub fn set_referrer(ctx: Context<SetReferrer>) -> Result<()> {
+        set_referrer::handler(ctx)
+    }
+
+    pub fn claim_referral_rewards<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimReferralRewards<'info>>) -> Result<()> {
+        claim_referral_rewards::handler(ctx)
+    }
+
+    pub fn init_plan(ctx: Context<InitPlan>, params: PlanParams) -> Result<()> {
+        set_plan::init_handler(ctx, params)
+    }
+
+    pub fn edit_plan(ctx: Context<EditPlan>, params: PlanParams) -> Result<()> {
+        set_plan::edit_handler(ctx, params)
+    }
+
+    pub fn init_plan_price(ctx: Context<InitPlanPrice>, params: PlanPriceParams) -> Result<()> {
+        set_plan_price::init_handler(ctx, params)
+    }
+
+    pub fn edit_plan_price(ctx: Context<EditPlanPrice>, params: PlanPriceParams) -> Result<()> {
+        set_plan_price::edit_handler(ctx, params)
+    }
+
+    pub fn pay_subscription<'info>(ctx: Context<'_, '_, 'info, 'info, PaySubscription<'info>>) -> Result<()> {
+        pay_subscription::handler(ctx)
+    }
+
+    pub fn gift_subscription<'info>(ctx: Context<'_, '_, 'info, 'info, GiftSubscription<'info>>) -> Result<()> {
+        gift_subscription::handler(ctx)
+    }
+
+    pub fn cancel_subscription(ctx: Context<CancelSubscription>) -> Result<()> {
+        cancel_subscription::handler(ctx)
+    }
+
+    pub fn init_refund_reserve(ctx: Context<InitRefundReserve>) -> Result<()> {
+        refund_reserve::init_handler(ctx)
+    }
+
+    pub fn release_refund(ctx: Context<ReleaseRefund>) -> Result<()> {
+        refund_reserve::release_handler(ctx)
+    }
+
+    pub fn set_auto_renew(ctx: Context<SetAutoRenew>, allowance: u64) -> Result<()> {
+        set_auto_renew::handler(ctx, allowance)
+    }
+
+    pub fn renew_subscription<'info>(ctx: Context<'_, '_, 'info, 'info, RenewSubscription<'info>>) -> Result<()> {
+        renew_subscription::handler(ctx)
+    }
+
+    pub fn redeem_voucher<'info>(ctx: Context<'_, '_, 'info, 'info, RedeemVoucher<'info>>, voucher: state::Voucher) -> Result<()> {
+        redeem_voucher::handler(ctx, voucher)
+    }
+
+    pub fn pay_subscription_sol<'info>(ctx: Context<'_, '_, 'info, 'info, PaySubscriptionSol<'info>>) -> Result<()> {
+        pay_subscription_sol::handler(ctx)
     }
 
     pub fn init_token_vault(ctx: Context<InitTokenVault>) -> Result<()> {
         init_token_vault::handler(ctx)
     }
 
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    pub fn deposit<'info>(ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>, amount: u64) -> Result<()> {
         deposit::handler(ctx, amount)
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+    pub fn withdraw<'info>(ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>) -> Result<()> {
         withdraw::handler(ctx)
     }
 
+    pub fn collect_management_fee<'info>(ctx: Context<'_, '_, 'info, 'info, CollectManagementFee<'info>>) -> Result<()> {
+        collect_management_fee::handler(ctx)
+    }
+
+    pub fn set_chunked_liquidation(ctx: Context<SetChunkedLiquidation>, max_amount: u64, interval: i64) -> Result<()> {
+        set_chunked_liquidation::handler(ctx, max_amount, interval)
+    }
+
+    pub fn set_max_slippage(ctx: Context<SetMaxSlippage>, max_slippage_bps: u16) -> Result<()> {
+        set_max_slippage::handler(ctx, max_slippage_bps)
+    }
+
+    pub fn set_bracket(ctx: Context<SetBracket>, params: BracketParams) -> Result<()> {
+        set_bracket::handler(ctx, params)
+    }
+
+    pub fn set_grid(ctx: Context<SetGrid>, params: GridParams) -> Result<()> {
+        set_grid::handler(ctx, params)
+    }
+
+    pub fn init_portfolio<'info>(ctx: Context<'_, '_, 'info, 'info, InitPortfolio<'info>>, target_weights: Vec<u16>) -> Result<()> {
+        init_portfolio::handler(ctx, target_weights)
+    }
+
     // JUP
     pub fn jup_swap(ctx: Context<Swap>, data: Vec<u8>) -> Result<()> {
         jup::swap::handler(ctx, data)
     }
 
-    pub fn jup_liquidate(ctx: Context<JupLiquidate>, data: Vec<u8>) -> Result<()> {
-        jup::liquidate::handler(ctx, data)
+    pub fn jup_liquidate<'info>(ctx: Context<'_, '_, 'info, 'info, JupLiquidate<'info>>, legs: Vec<RouteLeg>) -> Result<()> {
+        jup::liquidate::handler(ctx, legs)
+    }
+
+    pub fn jup_grid_swap(ctx: Context<JupGridSwap>, level: u8, data: Vec<u8>) -> Result<()> {
+        jup::grid_swap::handler(ctx, level, data)
+    }
+
+    pub fn jup_rebalance<'info>(ctx: Context<'_, '_, 'info, 'info, JupRebalance<'info>>, data: Vec<u8>) -> Result<()> {
+        jup::rebalance::handler(ctx, data)
+    }
+
+    pub fn jup_open_resting_order(ctx: Context<OpenRestingOrder>, data: Vec<u8>) -> Result<()> {
+        jup::open_resting_order::handler(ctx, data)
+    }
+
+    pub fn jup_cancel_resting_order(ctx: Context<CancelRestingOrder>, data: Vec<u8>) -> Result<()> {
+        jup::cancel_resting_order::handler(ctx, data)
+    }
+
+    pub fn clear_resting_order(ctx: Context<ClearRestingOrder>) -> Result<()> {
+        clear_resting_order::handler(ctx)
     }
 }
\ No newline at end of file
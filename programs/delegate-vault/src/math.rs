@@ -0,0 +1,49 @@
+use {
+    crate::error::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+// Prices stored on-chain are expressed as quote base units (deposit mint) per base unit of the token, scaled by this factor
+pub const PRICE_PRECISION: u128 = 1_000_000_000_000;
+
+/// Returns the price paid or received in a swap, quote_amount of the deposit mint for base_amount of the token
+pub fn execution_price(quote_amount: u64, base_amount: u64) -> Result<u64> {
+    if base_amount == 0 {
+        return Err(ErrorCode::InvalidExecutionPrice.into());
+    }
+
+    (quote_amount as u128)
+        .checked_mul(PRICE_PRECISION)
+        .ok_or(ErrorCode::NumericalOverflow)?
+        .checked_div(base_amount as u128)
+        .ok_or(ErrorCode::NumericalOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::NumericalOverflow.into())
+}
+
+/// Lowest execution price accepted for a swap against a reference price, max_slippage_bps below it
+pub fn min_execution_price(reference_price: u64, max_slippage_bps: u16) -> Result<u64> {
+    let max_slippage_bps = (max_slippage_bps as u128).min(10000);
+
+    ((reference_price as u128) * (10000 - max_slippage_bps) / 10000)
+        .try_into()
+        .map_err(|_| ErrorCode::NumericalOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execution_price_is_quote_per_base() {
+        assert_eq!(execution_price(150, 1_000).unwrap(), 150_000_000_000);
+        assert!(execution_price(150, 0).is_err());
+    }
+
+    #[test]
+    fn min_execution_price_applies_the_slippage() {
+        assert_eq!(min_execution_price(1_000_000, 300).unwrap(), 970_000);
+        assert_eq!(min_execution_price(1_000_000, 0).unwrap(), 1_000_000);
+        assert_eq!(min_execution_price(1_000_000, 20_000).unwrap(), 0);
+    }
+}
@@ -0,0 +1,174 @@
+use {
+    crate::{error::ErrorCode, math::PRICE_PRECISION, state::MintOracle},
+    anchor_lang::prelude::*,
+};
+
+// Pyth solana receiver program, owner of the PriceUpdateV2 accounts used to value the vaults
+pub const PYTH_RECEIVER_PROGRAM: Pubkey = pubkey!("rec5EKMGg6MxZYaYsfnQUm4fZgs8H2wBHC4hq7BmvR4");
+pub const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+// Max seconds since the price was published
+pub const MAX_PRICE_AGE: i64 = 60;
+// Values returned by get_value are USD with 6 decimals
+pub const VALUE_DECIMALS: i32 = 6;
+
+// Layout of the Pyth PriceUpdateV2 account, decoded by hand to avoid pulling the pyth sdk
+#[derive(AnchorDeserialize)]
+enum VerificationLevel {
+    Partial { _num_signatures: u8 },
+    Full,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceFeedMessage {
+    feed_id: [u8; 32],
+    price: i64,
+    _conf: u64,
+    exponent: i32,
+    publish_time: i64,
+    _prev_publish_time: i64,
+    _ema_price: i64,
+    _ema_conf: u64,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceUpdateV2 {
+    _write_authority: Pubkey,
+    verification_level: VerificationLevel,
+    price_message: PriceFeedMessage,
+    _posted_slot: u64,
+}
+
+pub struct OraclePrice {
+    pub feed_id: [u8; 32],
+    pub price: u64,
+    pub exponent: i32,
+}
+
+/// Reads a fully verified and fresh price from a Pyth PriceUpdateV2 account
+pub fn get_price(price_feed: &AccountInfo, current_time: i64) -> Result<OraclePrice> {
+    if price_feed.owner != &PYTH_RECEIVER_PROGRAM {
+        return Err(ErrorCode::InvalidPriceFeed.into());
+    }
+
+    let data = price_feed.try_borrow_data()?;
+    if data.len() < 8 || data[0..8] != PRICE_UPDATE_V2_DISCRIMINATOR {
+        return Err(ErrorCode::InvalidPriceFeed.into());
+    }
+
+    let update = PriceUpdateV2::deserialize(&mut &data[8..])
+        .map_err(|_| ErrorCode::InvalidPriceFeed)?;
+
+    if !matches!(update.verification_level, VerificationLevel::Full) {
+        return Err(ErrorCode::InvalidPriceFeed.into());
+    }
+
+    let message = update.price_message;
+    if message.price <= 0 {
+        return Err(ErrorCode::InvalidPriceFeed.into());
+    }
+
+    if message.publish_time.saturating_add(MAX_PRICE_AGE) < current_time {
+        return Err(ErrorCode::StalePrice.into());
+    }
+
+    Ok(OraclePrice {
+        feed_id: message.feed_id,
+        price: message.price as u64,
+        exponent: message.exponent,
+    })
+}
+
+/// Reads the price of a mint, the feed has to be the one bound to the mint by its mint oracle
+pub fn get_mint_price(price_feed: &AccountInfo, mint_oracle: &MintOracle, current_time: i64) -> Result<OraclePrice> {
+    let price = get_price(price_feed, current_time)?;
+    if price.feed_id != mint_oracle.feed_id {
+        return Err(ErrorCode::InvalidPriceFeed.into());
+    }
+
+    Ok(price)
+}
+
+/// Converts two USD prices into the price of a raw unit of base in raw units of quote, scaled by PRICE_PRECISION
+/// as the execution prices of the swaps
+pub fn exchange_price(base: &OraclePrice, base_decimals: u8, quote: &OraclePrice, quote_decimals: u8) -> Result<u64> {
+    // (base.price * 10^base.exponent / 10^base_decimals) / (quote.price * 10^quote.exponent / 10^quote_decimals)
+    let scale = base.exponent - quote.exponent - base_decimals as i32 + quote_decimals as i32;
+    let factor = 10u128
+        .checked_pow(scale.unsigned_abs())
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    let numerator = (base.price as u128)
+        .checked_mul(PRICE_PRECISION)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    let price = if scale >= 0 {
+        numerator
+            .checked_mul(factor)
+            .ok_or(ErrorCode::NumericalOverflow)?
+            .checked_div(quote.price as u128)
+    } else {
+        numerator.checked_div(
+            (quote.price as u128)
+                .checked_mul(factor)
+                .ok_or(ErrorCode::NumericalOverflow)?,
+        )
+    };
+
+    price
+        .ok_or(ErrorCode::NumericalOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::NumericalOverflow.into())
+}
+
+/// Converts a token amount to its USD value with VALUE_DECIMALS
+pub fn get_value(amount: u64, decimals: u8, price: &OraclePrice) -> Result<u128> {
+    let raw_value = (amount as u128)
+        .checked_mul(price.price as u128)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    // amount * price * 10^(exponent - decimals) scaled to VALUE_DECIMALS
+    let scale = VALUE_DECIMALS + price.exponent - decimals as i32;
+    let factor = 10u128
+        .checked_pow(scale.unsigned_abs())
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    if scale >= 0 {
+        raw_value.checked_mul(factor).ok_or(ErrorCode::NumericalOverflow.into())
+    } else {
+        Ok(raw_value / factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd_price(price: u64, exponent: i32) -> OraclePrice {
+        OraclePrice { feed_id: [0; 32], price, exponent }
+    }
+
+    #[test]
+    fn exchange_price_converts_between_raw_units() {
+        // 150 USD per SOL (9 decimals) against 1 USD per USDC (6 decimals): 0.15 USDC units per lamport
+        let sol = usd_price(15_000_000_000, -8);
+        let usdc = usd_price(100_000_000, -8);
+        assert_eq!(exchange_price(&sol, 9, &usdc, 6).unwrap(), 150_000_000_000);
+
+        // The other way around: 1 / 0.15 lamports per USDC unit
+        assert_eq!(exchange_price(&usdc, 6, &sol, 9).unwrap(), 6_666_666_666_666);
+    }
+
+    #[test]
+    fn exchange_price_handles_different_exponents() {
+        // Same prices published with different exponents
+        let sol = usd_price(150_000, -3);
+        let usdc = usd_price(100_000_000, -8);
+        assert_eq!(exchange_price(&sol, 9, &usdc, 6).unwrap(), 150_000_000_000);
+    }
+
+    #[test]
+    fn get_value_scales_to_value_decimals() {
+        let sol = usd_price(15_000_000_000, -8);
+        // 2 SOL are worth 300 USD
+        assert_eq!(get_value(2_000_000_000, 9, &sol).unwrap(), 300_000_000);
+    }
+}
@@ -1,5 +1,13 @@
 use {
-    crate::{error::ErrorCode, state::*},
+    crate::{
+        error::ErrorCode,
+        fees::BASIS_POINTS,
+        jupiter_aggregator::client::args::{
+            ExactOutRoute, Route, RouteWithTokenLedger, SharedAccountsExactOutRoute, SharedAccountsRoute,
+            SharedAccountsRouteWithTokenLedger,
+        },
+        state::*,
+    },
     anchor_lang::prelude::*,
     anchor_spl::token_interface::TokenAccount,
 };
@@ -27,6 +35,236 @@ pub fn is_jupiter_instruction(data: &[u8]) -> bool {
         || discriminator == JUPITER_SHARED_ACCOUNTS_EXACT_OUT_ROUTE_DISCRIMINATOR
 }
 
+// Policy relevant args of a route
+// max_in_amount: max amount spent from the source token account (None when the route does not carry it, e.g. token ledger routes)
+pub struct RouteArgs {
+    pub max_in_amount: Option<u64>,
+    pub slippage_bps: u16,
+    pub platform_fee_bps: u8,
+}
+
+/// Max amount spent by an exact out route: quoted in amount plus the slippage
+fn exact_out_max_in_amount(quoted_in_amount: u64, slippage_bps: u16) -> Result<u64> {
+    (quoted_in_amount as u128 * (BASIS_POINTS + slippage_bps as u128) / BASIS_POINTS)
+        .try_into()
+        .map_err(|_| ErrorCode::NumericalOverflow.into())
+}
+
+/// Decodes the args of the Jupiter routes with the types generated from the bundled IDL
+fn decode_jupiter_route_args(data: &[u8]) -> Result<RouteArgs> {
+    if !is_jupiter_instruction(data) {
+        return Err(ErrorCode::InvalidJupiterRoute.into());
+    }
+
+    let (discriminator, mut args) = data.split_at(8);
+    let route_args = if discriminator == JUPITER_ROUTE_DISCRIMINATOR {
+        let route = Route::deserialize(&mut args)?;
+        RouteArgs {
+            max_in_amount: Some(route.in_amount),
+            slippage_bps: route.slippage_bps,
+            platform_fee_bps: route.platform_fee_bps,
+        }
+    } else if discriminator == JUPITER_ROUTE_WITH_TOKEN_LEDGER_DISCRIMINATOR {
+        let route = RouteWithTokenLedger::deserialize(&mut args)?;
+        RouteArgs {
+            max_in_amount: None,
+            slippage_bps: route.slippage_bps,
+            platform_fee_bps: route.platform_fee_bps,
+        }
+    } else if discriminator == JUPITER_EXACT_OUT_ROUTE_DISCRIMINATOR {
+        let route = ExactOutRoute::deserialize(&mut args)?;
+        RouteArgs {
+            max_in_amount: Some(exact_out_max_in_amount(route.quoted_in_amount, route.slippage_bps)?),
+            slippage_bps: route.slippage_bps,
+            platform_fee_bps: route.platform_fee_bps,
+        }
+    } else if discriminator == JUPITER_SHARED_ACCOUNTS_ROUTE_DISCRIMINATOR {
+        let route = SharedAccountsRoute::deserialize(&mut args)?;
+        RouteArgs {
+            max_in_amount: Some(route.in_amount),
+            slippage_bps: route.slippage_bps,
+            platform_fee_bps: route.platform_fee_bps,
+        }
+    } else if discriminator == JUPITER_SHARED_ACCOUNTS_ROUTE_WITH_TOKEN_LEDGER_DISCRIMINATOR {
+        let route = SharedAccountsRouteWithTokenLedger::deserialize(&mut args)?;
+        RouteArgs {
+            max_in_amount: None,
+            slippage_bps: route.slippage_bps,
+            platform_fee_bps: route.platform_fee_bps,
+        }
+    } else {
+        let route = SharedAccountsExactOutRoute::deserialize(&mut args)?;
+        RouteArgs {
+            max_in_amount: Some(exact_out_max_in_amount(route.quoted_in_amount, route.slippage_bps)?),
+            slippage_bps: route.slippage_bps,
+            platform_fee_bps: route.platform_fee_bps,
+        }
+    };
+
+    Ok(route_args)
+}
+
+/// Reads the args stored at fixed offsets from the end of the data (0 = not present)
+fn decode_tail_route_args(data: &[u8], in_amount_offset: u8, slippage_offset: u8, platform_fee_offset: u8) -> Result<RouteArgs> {
+    let read = |offset: u8, len: usize| -> Result<&[u8]> {
+        let start = data.len()
+            .checked_sub(offset as usize)
+            .filter(|start| *start >= 8)
+            .ok_or(ErrorCode::InvalidRouteArgs)?;
+        data.get(start..start + len).ok_or(ErrorCode::InvalidRouteArgs.into())
+    };
+
+    let max_in_amount = match in_amount_offset {
+        0 => None,
+        offset => Some(u64::from_le_bytes(read(offset, 8)?.try_into().unwrap())),
+    };
+    let slippage_bps = match slippage_offset {
+        0 => 0,
+        offset => u16::from_le_bytes(read(offset, 2)?.try_into().unwrap()),
+    };
+    let platform_fee_bps = match platform_fee_offset {
+        0 => 0,
+        offset => read(offset, 1)?[0],
+    };
+
+    Ok(RouteArgs {
+        max_in_amount,
+        slippage_bps,
+        platform_fee_bps,
+    })
+}
+
+pub fn decode_route_args(route: &VenueRoute, data: &[u8]) -> Result<RouteArgs> {
+    match route.args_layout {
+        RouteArgsLayout::Jupiter => decode_jupiter_route_args(data),
+        RouteArgsLayout::Tail { in_amount_offset, slippage_offset, platform_fee_offset } => {
+            decode_tail_route_args(data, in_amount_offset, slippage_offset, platform_fee_offset)
+        }
+    }
+}
+
+/// Key of the route account at idx, failing if the route does not have enough accounts
+pub fn route_account_key(route_accounts: &[AccountInfo], idx: u8) -> Result<Pubkey> {
+    route_accounts
+        .get(idx as usize)
+        .map(|account| account.key())
+        .ok_or(ErrorCode::InvalidRemainingAccounts.into())
+}
+
+/// Enforces the order policy on the route args: the amount spent has to be within the source vault balance,
+/// the slippage within the order limit and the platform fee (if any) has to go to the fee vault of the output mint
+/// The slippage of the route is relative to the quote picked by the signer, it is only a sanity bound: the delegate
+/// executions are bounded on their realised output (oracle price on liquidations, level prices on grids, portfolio value on rebalances)
+pub fn verify_route_args(
+    route: &VenueRoute,
+    data: &[u8],
+    route_accounts: &[AccountInfo],
+    max_slippage_bps: u16,
+) -> Result<()> {
+    let route_args = decode_route_args(route, data)?;
+
+    if let Some(max_in_amount) = route_args.max_in_amount {
+        let source_token_account = route_accounts
+            .get(route.source_idx as usize)
+            .ok_or(ErrorCode::InvalidRemainingAccounts)?;
+        let source_token_account = TokenAccount::try_deserialize(&mut &source_token_account.try_borrow_data()?[..])?;
+        if max_in_amount > source_token_account.amount {
+            return Err(ErrorCode::RouteAmountExceedsBalance.into());
+        }
+    }
+
+    if route_args.slippage_bps > max_slippage_bps {
+        return Err(ErrorCode::SlippageTooHigh.into());
+    }
+
+    if route_args.platform_fee_bps > 0 {
+        let platform_fee_account = route_accounts
+            .get(route.platform_fee_account_idx as usize)
+            .ok_or(ErrorCode::InvalidPlatformFeeAccount)?;
+
+        // The platform fee is taken in the output mint, it can only go to the fee vault of that mint
+        let destination_mint = route_account_key(route_accounts, route.destination_mint_idx)?;
+        let (fee_vault, _) = Pubkey::find_program_address(
+            &[b"fee_vault".as_ref(), destination_mint.as_ref()],
+            &crate::ID,
+        );
+        if platform_fee_account.key() != fee_vault {
+            return Err(ErrorCode::InvalidPlatformFeeAccount.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Confines the manager signature on a route: every writable token account owned by the manager has to be one of the
+/// allowed vaults of the order and every program referenced has to be allowed by the swap venue
+pub fn verify_route_accounts(
+    route_accounts: &[AccountInfo],
+    manager: &Pubkey,
+    allowed_vaults: &[Pubkey],
+    swap_venue: &SwapVenue,
+    venue_programs: Option<&VenuePrograms>,
+) -> Result<()> {
+    for account in route_accounts {
+        if account.executable {
+            if !swap_venue.is_allowed_program(venue_programs, account.key) {
+                return Err(ErrorCode::UnexpectedProgram.into());
+            }
+            continue;
+        }
+
+        if !account.is_writable || allowed_vaults.contains(account.key) {
+            continue;
+        }
+
+        if *account.owner != anchor_spl::token::ID && *account.owner != anchor_spl::token_2022::ID {
+            continue;
+        }
+
+        // Mints and other token program accounts fail to deserialize as token accounts
+        if let Ok(token_account) = TokenAccount::try_deserialize(&mut &account.try_borrow_data()?[..]) {
+            if token_account.owner == *manager {
+                return Err(ErrorCode::UnauthorizedVault.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Token vault of the order for a mint, created with init_token_vault
+pub fn token_vault_address(manager: &Account<Manager>, order: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"token_vault".as_ref(),
+            manager.authority.as_ref(),
+            manager.key().as_ref(),
+            order.as_ref(),
+            mint.as_ref(),
+        ],
+        &crate::ID,
+    ).0
+}
+
+/// The deposit vault has to be the order vault and the token vault the one of the order for its mint,
+/// the vaults of other orders of the manager are rejected even if the manager owns them
+pub fn verify_order_vaults(
+    deposit_vault: &InterfaceAccount<'_, TokenAccount>,
+    token_vault: &InterfaceAccount<'_, TokenAccount>,
+    manager: &Account<Manager>,
+    order: &Account<Order>,
+) -> Result<()> {
+    if deposit_vault.key() != order.order_vault {
+        return Err(ErrorCode::IncorrectOrderVault.into());
+    }
+
+    if token_vault.key() != token_vault_address(manager, &order.key(), &token_vault.mint) {
+        return Err(ErrorCode::IncorrectTokenVault.into());
+    }
+
+    Ok(())
+}
+
 pub fn verify_permission(
     signer: &Signer<'_>,
     deposit_vault: &InterfaceAccount<'_, TokenAccount>,
@@ -83,4 +321,69 @@ pub fn verify_deposit_mint(
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // discriminator, in amount (u64), slippage (u16), platform fee (u8)
+    fn tail_route_data() -> Vec<u8> {
+        let mut data = vec![1; 8];
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&50u16.to_le_bytes());
+        data.push(3);
+        data
+    }
+
+    #[test]
+    fn decode_tail_route_args_reads_from_the_end_of_the_data() {
+        let route_args = decode_tail_route_args(&tail_route_data(), 11, 3, 1).unwrap();
+        assert_eq!(route_args.max_in_amount, Some(1_000));
+        assert_eq!(route_args.slippage_bps, 50);
+        assert_eq!(route_args.platform_fee_bps, 3);
+
+        // A zero offset means the route has no such arg
+        let route_args = decode_tail_route_args(&tail_route_data(), 0, 0, 0).unwrap();
+        assert_eq!(route_args.max_in_amount, None);
+        assert_eq!(route_args.slippage_bps, 0);
+        assert_eq!(route_args.platform_fee_bps, 0);
+    }
+
+    #[test]
+    fn decode_tail_route_args_rejects_offsets_outside_the_args() {
+        // Reaching into the discriminator or past the data
+        assert!(decode_tail_route_args(&tail_route_data(), 12, 3, 1).is_err());
+        assert!(decode_tail_route_args(&tail_route_data(), 11, 30, 1).is_err());
+        assert!(decode_tail_route_args(&[1; 8], 0, 0, 1).is_err());
+    }
+
+    #[test]
+    fn verify_route_args_sends_the_platform_fee_to_the_fee_vault_of_the_output_mint() {
+        let route = VenueRoute {
+            discriminator: [1; 8],
+            kind: RouteKind::Swap,
+            transfer_authority_idx: 0,
+            source_idx: 0,
+            destination_idx: 0,
+            destination_mint_idx: 0,
+            platform_fee_account_idx: 1,
+            resting_order_idx: 0,
+            args_layout: RouteArgsLayout::Tail { in_amount_offset: 0, slippage_offset: 3, platform_fee_offset: 1 },
+        };
+        let output_mint = Pubkey::new_unique();
+        let (fee_vault, _) = Pubkey::find_program_address(&[b"fee_vault".as_ref(), output_mint.as_ref()], &crate::ID);
+        let other_mint = Pubkey::new_unique();
+        let (other_fee_vault, _) = Pubkey::find_program_address(&[b"fee_vault".as_ref(), other_mint.as_ref()], &crate::ID);
+        let owner = anchor_spl::token::ID;
+        let (mut mint_lamports, mut fee_vault_lamports, mut other_lamports) = (0, 0, 0);
+        let (mut mint_data, mut fee_vault_data, mut other_data) = (vec![], vec![], vec![]);
+
+        let mint_info = AccountInfo::new(&output_mint, false, false, &mut mint_lamports, &mut mint_data, &owner, false, 0);
+        let fee_vault_info = AccountInfo::new(&fee_vault, false, true, &mut fee_vault_lamports, &mut fee_vault_data, &owner, false, 0);
+        let other_info = AccountInfo::new(&other_fee_vault, false, true, &mut other_lamports, &mut other_data, &owner, false, 0);
+
+        assert!(verify_route_args(&route, &tail_route_data(), &[mint_info.clone(), fee_vault_info], 100).is_ok());
+        // A vault of the config for another mint does not receive the fee
+        assert!(verify_route_args(&route, &tail_route_data(), &[mint_info, other_info], 100).is_err());
+    }
+}
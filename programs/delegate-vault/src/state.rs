@@ -1,23 +1,242 @@
 use anchor_lang::prelude::*;
 
+// Resizes a PDA of the program to len, the payer tops up the rent of a larger account and gets back the excess of a smaller one
+pub fn resize<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    len: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(len);
+    let lamports = account.lamports();
+    if rent > lamports {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent - lamports,
+        )?;
+    } else {
+        let excess = lamports - rent;
+        **account.try_borrow_mut_lamports()? -= excess;
+        **payer.try_borrow_mut_lamports()? += excess;
+    }
+
+    account.realloc(len, true).map_err(Into::into)
+}
+
 // This pda is used to manage the multiple vaults, used to control instructions or action permissions
 // authority: user, unique entity that can withdraw from order_vault
 // delegate: wallet on crank service to trigger liquidations on SL/TP/Time
 // end_subscription: if user has the subscription, it will reduce fee performance
+// referrer: referrer PDA bound to the manager (default pubkey if none), it can only be set once
+// subscription_performance_fee: performance fee rate of the plan paid while the subscription is active
+// refund_mint, refundable_amount, refundable_start, refundable_end: last period paid by the manager itself (gifts are not refundable),
+// used to prorate the refund on cancellation, refund_mint is the default pubkey for payments in native SOL
+// only the part of the payments that reached the fee vault (or the config account for SOL) is refundable, never the referrer share
+// auto_renew_plan: plan renewed with renew_subscription from the authority token account approved to the config (default pubkey if disabled)
+// subscribed_withdrawal_at: last withdrawal charged at the subscribed rates, the manager can not get a refund of a period used this way
 #[account]
 pub struct Manager {
     pub authority: Pubkey,
     pub delegate: Pubkey,
     pub end_subscription: i64,
     pub bump: u8,
+    pub referrer: Pubkey,
+    pub subscription_performance_fee: u16,
+    pub refund_mint: Pubkey,
+    pub refundable_amount: u64,
+    pub refundable_start: i64,
+    pub refundable_end: i64,
+    pub auto_renew_plan: Pubkey,
+    pub subscribed_withdrawal_at: i64,
 }
 
 impl Manager {
-    pub const LEN: usize = 8 + 32 + 32 + 32 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 1 + 32 + 2 + 32 + 8 + 8 + 8 + 32 + 8;
+    pub const RENEWAL_WINDOW: i64 = 3 * 24 * 60 * 60;
+    // size of the managers of the first deployment, the fields added since are appended after bump by migrate_manager
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 32 + 1;
+
+    pub fn is_subscribed(&self, current_time: i64) -> bool {
+        self.end_subscription > current_time
+    }
+
+    /// Extends the subscription with the plan duration, an active subscription can only be extended with the same fee rate
+    /// (switching plans requires the current one to end or be cancelled), returns the start time of the extended period
+    pub fn extend_subscription(&mut self, plan: &Plan, current_time: i64) -> Result<i64> {
+        self.extend_subscription_for(plan.duration, plan.performance_fee, current_time)
+    }
+
+    pub fn extend_subscription_for(&mut self, duration: i64, performance_fee: u16, current_time: i64) -> Result<i64> {
+        let start_time = if self.is_subscribed(current_time) {
+            if self.subscription_performance_fee != performance_fee {
+                return Err(crate::error::ErrorCode::SubscriptionRateMismatch.into());
+            }
+            self.end_subscription
+        } else {
+            self.subscription_performance_fee = performance_fee;
+            current_time
+        };
+
+        self.end_subscription = start_time
+            .checked_add(duration)
+            .ok_or(crate::error::ErrorCode::ArithmeticOverflow)?;
+
+        Ok(start_time)
+    }
+
+    /// Records a payment of the manager for the period starting at start_time, it is added to the refundable period
+    /// when it is paid in the same mint right after it, otherwise the refundable period starts again with this payment
+    /// returns the refundable amount of the previous period dropped by the payment, in the previous refund mint
+    pub fn record_payment(&mut self, mint: Pubkey, amount: u64, start_time: i64) -> Result<u64> {
+        let is_contiguous = self.refundable_amount > 0
+            && self.refund_mint == mint
+            && self.refundable_end == start_time;
+
+        let dropped_amount = if is_contiguous {
+            self.refundable_amount = self.refundable_amount
+                .checked_add(amount)
+                .ok_or(crate::error::ErrorCode::ArithmeticOverflow)?;
+            0
+        } else {
+            let dropped_amount = self.refundable_amount;
+            self.refund_mint = mint;
+            self.refundable_amount = amount;
+            self.refundable_start = start_time;
+            dropped_amount
+        };
+        self.refundable_end = self.end_subscription;
+
+        Ok(dropped_amount)
+    }
+
+    /// Unused portion of the refundable period, prorated by time
+    pub fn unused_refund(&self, current_time: i64) -> Result<u64> {
+        if self.refundable_amount == 0 || current_time >= self.refundable_end {
+            return Ok(0);
+        }
+
+        let period = (self.refundable_end - self.refundable_start) as u128;
+        let unused = (self.refundable_end - current_time.max(self.refundable_start)) as u128;
+
+        (self.refundable_amount as u128 * unused / period)
+            .try_into()
+            .map_err(|_| crate::error::ErrorCode::ArithmeticOverflow.into())
+    }
+
+    pub fn clear_refund(&mut self) {
+        self.refund_mint = Pubkey::default();
+        self.refundable_amount = 0;
+        self.refundable_start = 0;
+        self.refundable_end = 0;
+    }
+}
+
+// This PDA is a subscription plan created by the config authority (seeds: plan + id)
+// price: amount of the payment mint paid for each period of duration seconds
+// performance_fee: performance fee rate while subscribed to the plan
+// active: only active plans can be paid, disabling a plan keeps the current subscriptions
+#[account]
+pub struct Plan {
+    pub id: u16,
+    pub price: u64,
+    pub payment_mint: Pubkey,
+    pub duration: i64,
+    pub performance_fee: u16,
+    pub active: bool,
+    pub bump: u8,
+}
+
+impl Plan {
+    pub const LEN: usize = 8 + 2 + 8 + 32 + 8 + 2 + 1 + 1;
+}
+
+// This PDA is an extra payment option of a plan (seeds: plan_price + plan + mint), the plan payment_mint and price are the default one
+// mint: native mint for payments in SOL, done with a system transfer instead of a token transfer
+// price: amount of the mint paid for each period of the plan duration
+#[account]
+pub struct PlanPrice {
+    pub plan: Pubkey,
+    pub mint: Pubkey,
+    pub price: u64,
+    pub active: bool,
+    pub bump: u8,
+}
+
+impl PlanPrice {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1 + 1;
+}
+
+// Promo voucher signed off-chain by the config promo signer, the message signed is the borsh serialized voucher
+// manager: manager allowed to redeem it (default pubkey if anyone can redeem it)
+// duration: seconds added to the subscription (0 = plan duration), discount: in basis points over the plan price (10000 = free trial)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Voucher {
+    pub nonce: u64,
+    pub manager: Pubkey,
+    pub plan: Pubkey,
+    pub duration: i64,
+    pub discount: u16,
+    pub expires_at: i64,
+}
+
+// This PDA makes the vouchers single-use (seeds: voucher + nonce)
+#[account]
+pub struct VoucherRedemption {
+    pub manager: Pubkey,
+    pub redeemed_at: i64,
+    pub bump: u8,
+}
+
+impl VoucherRedemption {
+    pub const LEN: usize = 8 + 32 + 8 + 1;
+}
+
+// This PDA identifies a referrer, it receives a share of the fees paid by the managers bound to it on its vaults (one per mint)
+// wallet: unique entity that can claim the rewards of the referrer vaults
+#[account]
+pub struct Referrer {
+    pub wallet: Pubkey,
+    pub bump: u8,
+}
+
+impl Referrer {
+    pub const LEN: usize = 8 + 32 + 1;
+}
+
+// This PDA tracks the realized PnL of a manager across all the orders of a deposit mint, used on high-water-mark mode
+// cumulative_pnl: sum of the realized PnL of every withdrawn order
+// high_water_mark: highest cumulative_pnl on which the performance fee was already charged
+#[account]
+pub struct HighWaterMark {
+    pub manager: Pubkey,
+    pub mint: Pubkey,
+    pub cumulative_pnl: i64,
+    pub high_water_mark: i64,
+    pub bump: u8,
+}
+
+impl HighWaterMark {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1;
 }
 
 // This PDA is used to store the order details, validate on liquidation the mint deposited and to calculate the performance fee each position represents an order, 
 // have its own vaults, that store the tokens deposited or swapped by the user, so we can track the user inventory on-chain easier
+// chunk_max_amount: if set, the delegate can only liquidate the token vault in chunks of at most this amount (0 = disabled)
+// chunk_interval: minimum seconds the delegate has to wait between two liquidation chunks
+// last_chunk_at: timestamp of the last liquidation chunk executed by the delegate
+// bracket: stop-loss and take-profit attached to the order, once one of them fires the other is disarmed
+// grid: price levels where the delegate is allowed to buy and sell the token vault, turns the order into a market-making bot
+// management_fee_accrued: management fee accrued over the deposited amount and not yet settled, last_accrual_at: last accrual timestamp
+// max_slippage_bps: max slippage allowed on the routes executed for the order
+// resting_order: limit order or DCA account placed by the manager for the order on resting_venue (default = none), the order can not be
+// liquidated or withdrawn while it is open
+// policy_deposit: amount counted in the TVL of the mint policy, 0 for the orders opened before the mint policies
 #[account]
 pub struct Order {
     pub id: Pubkey,
@@ -26,27 +245,572 @@ pub struct Order {
     pub order_vault: Pubkey,
     pub deposit_amount: u64,
     pub bump: u8,
+    pub chunk_max_amount: u64,
+    pub chunk_interval: i64,
+    pub last_chunk_at: i64,
+    pub bracket: Bracket,
+    pub grid: Grid,
+    pub created_at: i64,
+    pub management_fee_accrued: u64,
+    pub last_accrual_at: i64,
+    pub max_slippage_bps: u16,
+    pub resting_order: Pubkey,
+    pub resting_venue: Pubkey,
+    pub policy_deposit: u64,
 }
 
 impl Order {
-    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 1 + 8 + 8 + 8 + Bracket::LEN + Grid::LEN + 8 + 8 + 8 + 2 + 32 + 32 + 8;
+    pub const DEFAULT_MAX_SLIPPAGE_BPS: u16 = 300;
+    // size of the orders of the first deployment, the fields added since are appended after bump by migrate_order
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 1;
+}
+
+// One-cancels-other triggers, prices are deposit mint units per token unit scaled by PRICE_PRECISION (0 = disarmed)
+// expires_at: after this timestamp the delegate is allowed to exit the position at any price (0 = no expiry)
+// fired: leg that triggered the delegate liquidation, only the first one is recorded
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct Bracket {
+    pub stop_loss_price: u64,
+    pub take_profit_price: u64,
+    pub expires_at: i64,
+    pub fired: TriggerLeg,
 }
 
-// This PDA is used to store the config details, have authority over the fee vaults (SOL & USDC)
+impl Bracket {
+    pub const LEN: usize = 8 + 8 + 8 + 1;
+
+    pub fn is_armed(&self) -> bool {
+        self.stop_loss_price > 0 || self.take_profit_price > 0 || self.expires_at > 0
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TriggerLeg {
+    #[default]
+    None,
+    StopLoss,
+    TakeProfit,
+    Expiry,
+}
+
+// This PDA is used to store the config details, have authority over the fee vaults (one per mint, seeds: fee_vault + mint)
+// fees are collected on the fee vaults and distributed by the authority with claim_fees
 // to-do: realloc the get extra space to store more new fields
+// the config of the first deployment (LegacyConfig) is rewritten in this layout with migrate_config
 #[account]
 pub struct Config {
+    pub authority: Pubkey,
+    pub performance_fee: u16, // user not subscribed performance fee, a value of 250 corresponds to a fee of 2.5% (subscribed rate comes from the plan)
+    pub bump: u8,
+    pub subscribed_management_fee: u16, // annual fee over the deposited amount, a value of 100 corresponds to 1% per year
+    pub management_fee: u16, // user not subscribed management fee
+    pub high_water_mark: bool, // if enabled performance fee is only charged on the net new profit of the manager per deposit mint
+    pub fee_schedule_enabled: bool, // if enabled fees are split between the fee schedule recipients instead of going to the fee vaults
+    pub referral_fee_share: u16, // share of the performance fee sent to the referrer, a value of 2000 corresponds to 20%
+    pub referral_subscription_share: u16, // share of the subscription payments sent to the referrer
+    pub promo_signer: Pubkey, // signs off-chain the promo vouchers redeemed with redeem_voucher (default pubkey disables vouchers)
+}
+
+impl Config {
+    pub const LEN: usize = 8 + 32 + 2 + 1 + 2 + 2 + 1 + 1 + 2 + 2 + 32;
+}
+
+// This PDA overrides the performance fee rates of the config for a deposit mint (seeds: mint_fee_override + mint)
+// or for a manager (seeds: manager_fee_override + manager), e.g. lower fees on stablecoin orders or fee-exempt partners
+// precedence: manager override > mint override > plan rate (subscribed) or config (not subscribed)
+#[account]
+pub struct FeeOverride {
+    pub target: Pubkey,
+    pub subscribed_performance_fee: u16,
+    pub performance_fee: u16,
+    pub bump: u8,
+}
+
+impl FeeOverride {
+    pub const LEN: usize = 8 + 32 + 2 + 2 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FeeOverrideKind {
+    Mint,
+    Manager,
+}
+
+impl FeeOverrideKind {
+    pub fn seed(&self) -> &'static [u8] {
+        match self {
+            FeeOverrideKind::Mint => b"mint_fee_override",
+            FeeOverrideKind::Manager => b"manager_fee_override",
+        }
+    }
+}
+
+// This PDA configures the performance fee discount for staking the protocol token (seeds: discount_config)
+// tiers: sorted by min_amount, the highest tier reached by the staked amount applies, discount in basis points over the performance fee rate
+// lock_duration: seconds the tokens stay locked after each stake, so the discount can not be borrowed for a single withdrawal
+#[account]
+pub struct DiscountConfig {
+    pub mint: Pubkey,
+    pub lock_duration: i64,
+    pub tiers: Vec<DiscountTier>,
+    pub bump: u8,
+}
+
+impl DiscountConfig {
+    pub const MAX_TIERS: usize = 8;
+    pub const LEN: usize = 8 + 32 + 8 + 4 + Self::MAX_TIERS * DiscountTier::LEN + 1;
+
+    pub fn discount(&self, staked_amount: u64) -> u16 {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| staked_amount >= tier.min_amount)
+            .map(|tier| tier.discount)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct DiscountTier {
+    pub min_amount: u64,
+    pub discount: u16,
+}
+
+impl DiscountTier {
+    pub const LEN: usize = 8 + 2;
+}
+
+// This PDA tracks the protocol tokens staked by a manager (seeds: stake + manager), held on its stake vault (seeds: stake_vault + manager)
+// unlock_at: timestamp after which the staked tokens can be unstaked
+#[account]
+pub struct Stake {
+    pub manager: Pubkey,
+    pub amount: u64,
+    pub unlock_at: i64,
+    pub bump: u8,
+}
+
+impl Stake {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 1;
+}
+
+// This PDA allows a mint on the program (seeds: mint_policy + mint), set by the config authority
+// allow_deposit: the mint can be deposited, allow_target: the mint can be held on the token vaults and bought with jup_swap
+// min_deposit: min amount per deposit, max_tvl: max amount deposited across all the open orders (0 = no limit)
+// tvl: amount deposited on the open orders, increased on deposit and decreased on withdraw
+#[account]
+pub struct MintPolicy {
+    pub mint: Pubkey,
+    pub allow_deposit: bool,
+    pub allow_target: bool,
+    pub min_deposit: u64,
+    pub max_tvl: u64,
+    pub tvl: u64,
+    pub bump: u8,
+}
+
+impl MintPolicy {
+    pub const LEN: usize = 8 + 32 + 1 + 1 + 8 + 8 + 8 + 1;
+
+    pub fn add_deposit(&mut self, order: &mut Order, amount: u64) -> Result<()> {
+        let tvl = self.tvl
+            .checked_add(amount)
+            .ok_or(crate::error::ErrorCode::ArithmeticOverflow)?;
+
+        if self.max_tvl > 0 && tvl > self.max_tvl {
+            return Err(crate::error::ErrorCode::MaxTvlExceeded.into());
+        }
+
+        self.tvl = tvl;
+        order.policy_deposit = amount;
+
+        Ok(())
+    }
+
+    // Only what the order added is removed, the orders opened before the policy were never counted
+    pub fn remove_deposit(&mut self, order: &mut Order) {
+        self.tvl = self.tvl.saturating_sub(order.policy_deposit);
+        order.policy_deposit = 0;
+    }
+}
+
+// This PDA keeps the refundable subscription payments of a mint out of claim_fees and claim_sol_fees (seeds: refund_reserve + mint),
+// the mint is the default pubkey for the payments in native SOL, payments are only refundable once it is initialized with init_refund_reserve
+// amount: refundable amounts of the managers in the mint, released on cancellation or with release_refund once their period ended
+#[account]
+pub struct RefundReserve {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl RefundReserve {
+    pub const LEN: usize = 8 + 32 + 8 + 1;
+
+    pub fn reserve(&mut self, amount: u64) -> Result<()> {
+        self.amount = self.amount
+            .checked_add(amount)
+            .ok_or(crate::error::ErrorCode::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn release(&mut self, amount: u64) {
+        self.amount = self.amount.saturating_sub(amount);
+    }
+}
+
+// This PDA binds a mint to its Pyth price feed (seeds: mint_oracle + mint), set by the config authority
+// feed_id: id of the Pyth feed, a PriceUpdateV2 account is only accepted for the mint if it carries this feed
+// decimals: decimals of the mint, to convert the USD prices into an exchange rate between raw amounts
+#[account]
+pub struct MintOracle {
+    pub mint: Pubkey,
+    pub feed_id: [u8; 32],
+    pub decimals: u8,
+    pub bump: u8,
+}
+
+impl MintOracle {
+    pub const LEN: usize = 8 + 32 + 32 + 1 + 1;
+}
+
+// This PDA allows the Token-2022 mints hooked to a transfer hook program (seeds: hook_program + program id), set by the config authority
+// allowed: mints of the program can be deposited and used as target, disabling it rejects new deposits and token vaults
+#[account]
+pub struct HookProgram {
+    pub program_id: Pubkey,
+    pub allowed: bool,
+    pub bump: u8,
+}
+
+impl HookProgram {
+    pub const LEN: usize = 8 + 32 + 1 + 1;
+}
+
+// This PDA registers a swap venue (seeds: swap_venue + program id), set by the config authority
+// routes: instructions of the venue allowed on the swaps, identified by their discriminator
+#[account]
+pub struct SwapVenue {
+    pub program_id: Pubkey,
+    pub routes: Vec<VenueRoute>,
+    pub active: bool,
+    pub bump: u8,
+}
+
+impl SwapVenue {
+    pub const MAX_ROUTES: usize = 16;
+    pub const LEN: usize = 8 + 32 + 4 + Self::MAX_ROUTES * VenueRoute::LEN + 1 + 1;
+
+    // The venue program and the token, system and associated token programs are always allowed, any other one
+    // (e.g. the AMMs of an aggregator) has to be listed on the venue programs
+    pub fn is_allowed_program(&self, venue_programs: Option<&VenuePrograms>, program: &Pubkey) -> bool {
+        *program == self.program_id
+            || *program == anchor_spl::token::ID
+            || *program == anchor_spl::token_2022::ID
+            || *program == anchor_lang::system_program::ID
+            || *program == anchor_spl::associated_token::ID
+            || venue_programs.is_some_and(|venue_programs| venue_programs.programs.contains(program))
+    }
+
+    pub fn route(&self, data: &[u8], kind: RouteKind) -> Result<&VenueRoute> {
+        if !self.active {
+            return Err(crate::error::ErrorCode::InactiveSwapVenue.into());
+        }
+
+        self.routes
+            .iter()
+            .find(|route| data.len() >= 8 && route.discriminator == data[0..8] && route.kind == kind)
+            .ok_or(crate::error::ErrorCode::UnknownRoute.into())
+    }
+}
+
+// This PDA lists the programs the routes of a swap venue can reference (seeds: venue_programs + swap venue), set by the config authority
+// sized to the list, it is resized on every edit so it can grow up to MAX_PROGRAMS
+#[account]
+pub struct VenuePrograms {
+    pub swap_venue: Pubkey,
+    pub bump: u8,
+    pub programs: Vec<Pubkey>,
+}
+
+impl VenuePrograms {
+    pub const MAX_PROGRAMS: usize = 256;
+
+    pub fn len(programs: usize) -> usize {
+        8 + 32 + 1 + 4 + programs * 32
+    }
+}
+
+// Positions of the route accounts validated by the program, platform_fee_account_idx is u8::MAX if the route has none
+// resting_order_idx: position of the resting order account (limit order, DCA) on the open and cancel routes, u8::MAX for swaps
+// On cancel routes the transfer authority is the maker and the destination the account refunded with the unfilled input
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct VenueRoute {
+    pub discriminator: [u8; 8],
+    pub kind: RouteKind,
+    pub transfer_authority_idx: u8,
+    pub source_idx: u8,
+    pub destination_idx: u8,
+    pub destination_mint_idx: u8,
+    pub platform_fee_account_idx: u8,
+    pub resting_order_idx: u8,
+    pub args_layout: RouteArgsLayout,
+}
+
+impl VenueRoute {
+    pub const LEN: usize = 8 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + RouteArgsLayout::LEN;
+}
+
+// Swap: instant route, OpenRestingOrder / CancelRestingOrder: places or cancels an order filled later by the venue
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RouteKind {
+    Swap,
+    OpenRestingOrder,
+    CancelRestingOrder,
+}
+
+// How the policy relevant args are read from the route data
+// Jupiter: decoded with the types of the bundled Jupiter IDL
+// Tail: little endian values at fixed offsets from the end of the data (0 = not present, a missing slippage or platform fee is read as 0)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum RouteArgsLayout {
+    Jupiter,
+    Tail {
+        in_amount_offset: u8,
+        slippage_offset: u8,
+        platform_fee_offset: u8,
+    },
+}
+
+impl RouteArgsLayout {
+    pub const LEN: usize = 1 + 3;
+}
+
+// This PDA splits the protocol fees (performance fee and subscription payments) between the protocol and its partners
+// share: in basis points, the shares of all the recipients add up to 10000
+// dust_recipient: index of the recipient that receives the rounding dust
+#[account]
+pub struct FeeSchedule {
+    pub recipients: Vec<FeeRecipient>,
+    pub dust_recipient: u8,
+    pub bump: u8,
+}
+
+impl FeeSchedule {
+    pub const MAX_RECIPIENTS: usize = 8;
+    pub const LEN: usize = 8 + 4 + Self::MAX_RECIPIENTS * FeeRecipient::LEN + 1 + 1;
+
+    pub fn shares(&self) -> Vec<u16> {
+        self.recipients.iter().map(|recipient| recipient.share).collect()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct FeeRecipient {
+    pub wallet: Pubkey,
+    pub share: u16,
+}
+
+impl FeeRecipient {
+    pub const LEN: usize = 32 + 2;
+}
+
+// Layout of the config of the first deployment, only read by migrate_config to rewrite it in the current layout
+// the receivers moved to the fee vaults, the payments to the plans and the subscribed rate to the plan of each subscription,
+// so only authority, performance_fee and bump are kept
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyConfig {
     pub authority: Pubkey,
     pub payment_mint: Pubkey,
     pub payment_receiver: Pubkey,
     pub performance_receiver: Pubkey,
     pub monthly_amount: u64,
     pub yearly_amount: u64,
-    pub subscribed_performance_fee: u16, // a value of 250 corresponds to a fee of 2.5%
-    pub performance_fee: u16, // user not subscribed performance fee
+    pub subscribed_performance_fee: u16,
+    pub performance_fee: u16,
     pub bump: u8,
 }
 
-impl Config {
+impl LegacyConfig {
     pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 2 + 2 + 1;
-}
\ No newline at end of file
+}
+
+// Grid levels are spread evenly between lower_price and upper_price (both included), prices use PRICE_PRECISION as the bracket
+// level_size: max amount of the deposit mint spent when buying at a level
+// filled: bitmap of the levels where the delegate bought and is waiting to sell one level above
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct Grid {
+    pub lower_price: u64,
+    pub upper_price: u64,
+    pub count: u8,
+    pub level_size: u64,
+    pub filled: u64,
+}
+
+impl Grid {
+    pub const LEN: usize = 8 + 8 + 1 + 8 + 8;
+    pub const MAX_LEVELS: u8 = 64;
+
+    pub fn is_enabled(&self) -> bool {
+        self.count > 0
+    }
+
+    pub fn is_filled(&self, level: u8) -> bool {
+        self.filled & (1u64 << level) != 0
+    }
+
+    pub fn level_price(&self, level: u8) -> Option<u64> {
+        if level >= self.count {
+            return None;
+        }
+
+        let step = (self.upper_price - self.lower_price) as u128 * level as u128 / (self.count - 1) as u128;
+        (self.lower_price as u128 + step).try_into().ok()
+    }
+}
+
+// This PDA turns an order into a portfolio of up to MAX_ASSETS vaults, the first asset is always the order vault
+// the delegate can only rebalance between these vaults and towards the target weights, valued with the price feeds
+#[account]
+pub struct Portfolio {
+    pub order: Pubkey,
+    pub assets: Vec<PortfolioAsset>,
+    pub bump: u8,
+}
+
+impl Portfolio {
+    pub const MAX_ASSETS: usize = 8;
+    pub const LEN: usize = 8 + 32 + 4 + Self::MAX_ASSETS * PortfolioAsset::LEN + 1;
+}
+
+// target_weight: in basis points, the weights of all the assets add up to 10000
+// price_feed: Pyth PriceUpdateV2 account used to value the vault
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PortfolioAsset {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub price_feed: Pubkey,
+    pub decimals: u8,
+    pub target_weight: u16,
+}
+
+impl PortfolioAsset {
+    pub const LEN: usize = 32 + 32 + 32 + 1 + 2;
+}
+
+// Reads a PDA passed unconditionally to keep its lookup deterministic, None while it is not initialized
+pub fn load_if_initialized<T: AccountDeserialize + Owner>(account: &AccountInfo) -> Result<Option<T>> {
+    if account.owner != &T::owner() || account.data_is_empty() {
+        return Ok(None);
+    }
+    T::try_deserialize(&mut &account.try_borrow_data()?[..]).map(Some)
+}
+
+// Writes back a PDA read with load_if_initialized
+pub fn store<T: AccountSerialize>(account: &AccountInfo, data: &T) -> Result<()> {
+    let mut account_data = account.try_borrow_mut_data()?;
+    data.try_serialize(&mut &mut account_data[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> Manager {
+        Manager {
+            authority: Pubkey::new_unique(),
+            delegate: Pubkey::new_unique(),
+            end_subscription: 0,
+            bump: 255,
+            referrer: Pubkey::default(),
+            subscription_performance_fee: 0,
+            refund_mint: Pubkey::default(),
+            refundable_amount: 0,
+            refundable_start: 0,
+            refundable_end: 0,
+            auto_renew_plan: Pubkey::default(),
+            subscribed_withdrawal_at: 0,
+        }
+    }
+
+    #[test]
+    fn extend_subscription_stacks_plans_of_the_same_rate() {
+        let mut manager = manager();
+        assert_eq!(manager.extend_subscription_for(100, 500, 1_000).unwrap(), 1_000);
+        assert_eq!(manager.extend_subscription_for(100, 500, 1_050).unwrap(), 1_100);
+        assert_eq!(manager.end_subscription, 1_200);
+        assert_eq!(manager.subscription_performance_fee, 500);
+    }
+
+    #[test]
+    fn extend_subscription_rejects_a_different_rate_while_subscribed() {
+        let mut manager = manager();
+        manager.extend_subscription_for(100, 500, 1_000).unwrap();
+        assert!(manager.extend_subscription_for(100, 100, 1_050).is_err());
+        assert_eq!(manager.end_subscription, 1_100);
+
+        // Once the subscription ended any plan can be paid
+        assert_eq!(manager.extend_subscription_for(100, 100, 1_100).unwrap(), 1_100);
+        assert_eq!(manager.subscription_performance_fee, 100);
+    }
+
+    #[test]
+    fn unused_refund_is_prorated_over_the_paid_period() {
+        let mut manager = manager();
+        manager.refundable_amount = 1_000;
+        manager.refundable_start = 100;
+        manager.refundable_end = 200;
+        assert_eq!(manager.unused_refund(50).unwrap(), 1_000);
+        assert_eq!(manager.unused_refund(100).unwrap(), 1_000);
+        assert_eq!(manager.unused_refund(175).unwrap(), 250);
+        assert_eq!(manager.unused_refund(200).unwrap(), 0);
+
+        manager.clear_refund();
+        assert_eq!(manager.unused_refund(150).unwrap(), 0);
+    }
+
+    #[test]
+    fn record_payment_returns_the_refundable_amount_it_drops() {
+        let mut manager = manager();
+        let mint = Pubkey::new_unique();
+        manager.end_subscription = 200;
+        assert_eq!(manager.record_payment(mint, 1_000, 100).unwrap(), 0);
+
+        // Paid right after the period in the same mint, the period is extended
+        manager.end_subscription = 300;
+        assert_eq!(manager.record_payment(mint, 500, 200).unwrap(), 0);
+        assert_eq!(manager.refundable_amount, 1_500);
+        assert_eq!(manager.refundable_start, 100);
+
+        // Paid in another mint, the refundable period starts again
+        manager.end_subscription = 400;
+        assert_eq!(manager.record_payment(Pubkey::new_unique(), 700, 300).unwrap(), 1_500);
+        assert_eq!(manager.refundable_amount, 700);
+        assert_eq!(manager.refundable_start, 300);
+    }
+
+    #[test]
+    fn level_price_spreads_the_levels_over_the_range() {
+        let grid = Grid { lower_price: 100, upper_price: 200, count: 5, level_size: 10, filled: 0 };
+        assert_eq!(grid.level_price(0), Some(100));
+        assert_eq!(grid.level_price(1), Some(125));
+        assert_eq!(grid.level_price(4), Some(200));
+        assert_eq!(grid.level_price(5), None);
+    }
+
+    #[test]
+    fn venue_programs_extend_the_programs_allowed_on_the_routes() {
+        let swap_venue = SwapVenue { program_id: Pubkey::new_unique(), routes: vec![], active: true, bump: 255 };
+        let amm = Pubkey::new_unique();
+        assert!(swap_venue.is_allowed_program(None, &swap_venue.program_id));
+        assert!(swap_venue.is_allowed_program(None, &anchor_spl::token::ID));
+        assert!(!swap_venue.is_allowed_program(None, &amm));
+
+        let venue_programs = VenuePrograms { swap_venue: Pubkey::new_unique(), bump: 255, programs: vec![amm] };
+        assert!(swap_venue.is_allowed_program(Some(&venue_programs), &amm));
+        assert!(!swap_venue.is_allowed_program(Some(&venue_programs), &Pubkey::new_unique()));
+        assert_eq!(VenuePrograms::len(1), 8 + venue_programs.try_to_vec().unwrap().len());
+    }
+}
@@ -0,0 +1,184 @@
+use {
+    crate::{error::ErrorCode, state::HookProgram},
+    anchor_lang::prelude::*,
+    anchor_spl::token_2022_extensions::transfer_fee::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint},
+    anchor_spl::token_interface::TokenAccount,
+    anchor_spl::token_2022::spl_token_2022::{
+        self,
+        extension::{
+            default_account_state::DefaultAccountState,
+            non_transferable::NonTransferable,
+            permanent_delegate::PermanentDelegate,
+            transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+            transfer_hook::TransferHook,
+            BaseStateWithExtensions,
+            StateWithExtensions,
+        },
+        onchain::invoke_transfer_checked,
+        state::{Account as TokenAccountState, AccountState, Mint},
+    },
+};
+
+/// Rejects the Token-2022 mints with extensions that are not safe for the order vaults:
+/// a permanent delegate can drain the vaults, non-transferable tokens can not be withdrawn and default frozen accounts can not receive them
+/// transfer hooks are supported when the hook program is allowed by the config authority, their extra accounts are forwarded on the transfers of the program
+pub fn check_mint_extensions(mint: &AccountInfo, hook_program: Option<&HookProgram>) -> Result<()> {
+    let Some(hook_program_id) = transfer_hook_program(mint)? else {
+        return check_vault_extensions(mint);
+    };
+
+    match hook_program {
+        Some(hook_program) if hook_program.program_id == hook_program_id && hook_program.allowed => check_vault_extensions(mint),
+        _ => Err(ErrorCode::TransferHookNotAllowed.into()),
+    }
+}
+
+/// Rejects the mints with a transfer hook as payment mints, the subscription payments do not forward hook accounts
+pub fn check_payment_mint(mint: &AccountInfo) -> Result<()> {
+    if transfer_hook_program(mint)?.is_some() {
+        return Err(ErrorCode::PaymentMintHookNotAllowed.into());
+    }
+
+    Ok(())
+}
+
+/// Program of the transfer hook of a Token-2022 mint, None for any other mint
+fn transfer_hook_program(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+
+    Ok(mint
+        .get_extension::<TransferHook>()
+        .ok()
+        .and_then(|transfer_hook| Option::<Pubkey>::from(transfer_hook.program_id)))
+}
+
+fn check_vault_extensions(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+
+    if let Ok(permanent_delegate) = mint.get_extension::<PermanentDelegate>() {
+        if Option::<Pubkey>::from(permanent_delegate.delegate).is_some() {
+            return Err(ErrorCode::PermanentDelegateNotAllowed.into());
+        }
+    }
+
+    if mint.get_extension::<NonTransferable>().is_ok() {
+        return Err(ErrorCode::NonTransferableNotAllowed.into());
+    }
+
+    if let Ok(default_account_state) = mint.get_extension::<DefaultAccountState>() {
+        if default_account_state.state == AccountState::Frozen as u8 {
+            return Err(ErrorCode::DefaultFrozenNotAllowed.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// transfer_checked that forwards the extra accounts required by the transfer hook of the mint (if any)
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_hook<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_transfer_checked(
+        token_program.key,
+        from,
+        mint,
+        to,
+        authority,
+        extra_accounts,
+        amount,
+        decimals,
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// Whether the mint is a Token-2022 mint with the transfer fee extension
+fn has_transfer_fee(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(false);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+
+    Ok(mint.get_extension::<TransferFeeConfig>().is_ok())
+}
+
+/// Moves the transfer fees withheld on the token account to its mint, required before closing it
+pub fn harvest_withheld_fees<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    token_account: AccountInfo<'info>,
+) -> Result<()> {
+    if !has_transfer_fee(&mint)? {
+        return Ok(());
+    }
+
+    harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            token_program.clone(),
+            HarvestWithheldTokensToMint {
+                token_program_id: token_program,
+                mint,
+            },
+        ),
+        vec![token_account],
+    )
+}
+
+/// Fee withheld by a Token-2022 mint with the transfer fee extension when transferring amount, 0 for any other mint
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+
+    let epoch = Clock::get()?.epoch;
+    transfer_fee_config
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(ErrorCode::NumericalOverflow.into())
+}
+
+/// Balance of the token account including the transfer fees withheld on it,
+/// the swapped amounts are measured on it so the fee of the mint is not counted as slippage of the route
+pub fn gross_balance(token_account: &InterfaceAccount<TokenAccount>) -> Result<u64> {
+    let account_info = token_account.to_account_info();
+    if *account_info.owner != spl_token_2022::ID {
+        return Ok(token_account.amount);
+    }
+
+    let account_data = account_info.try_borrow_data()?;
+    let account = StateWithExtensions::<TokenAccountState>::unpack(&account_data)?;
+    let withheld_amount = account
+        .get_extension::<TransferFeeAmount>()
+        .map_or(0, |transfer_fee_amount| u64::from(transfer_fee_amount.withheld_amount));
+
+    token_account
+        .amount
+        .checked_add(withheld_amount)
+        .ok_or(ErrorCode::NumericalOverflow.into())
+}
@@ -20,6 +20,9 @@ import {
   ASSOCIATED_TOKEN_PROGRAM,
   JUPITER_PROGRAM,
   SQUADS_PERFORMANCE_ADDRESS,
+  SOL_USD_PRICE_FEED,
+  USDC_USD_PRICE_FEED,
+  JUP_MINT,
 } from "../utils/solana/constants";
 import {
   getBase64EncodedWireTransaction,
@@ -37,7 +40,13 @@ import {
   getConfigAddress,
   getAtaAddress,
   getManagerAddress,
+  getFeeVaultAddress,
+  getMintPolicyAddress,
+  getSwapVenueAddress,
+  getVenueProgramsAddress,
+  getMintOracleAddress,
 } from "../utils/solana/pda";
+import { setMintPolicy, setupProgram } from "../utils/solana/setup";
 import { generateKeyPair, Address } from "@solana/kit";
 import { confirmTransaction } from "../utils/solana/transaction/confirm";
 import * as fs from "fs";
@@ -80,6 +89,8 @@ describe("delegate-vault", () => {
     const provider = anchor.AnchorProvider.env();
     anchor.setProvider(provider);
     program = anchor.workspace.DelegateVault as Program<DelegateVault>;
+    // Mint policies and swap venue required by the deposits and swaps
+    await setupProgram(program);
     connection = provider.connection;
 
     // Load keypairs from file
@@ -113,7 +124,7 @@ describe("delegate-vault", () => {
     // Derive PDAs
     config = await getConfigAddress();
     manager = await getManagerAddress(userAddress);
-    feeVault = await getAtaAddress(config, usdcMint);
+    feeVault = await getFeeVaultAddress(usdcMint);
     userUsdcAta = await getAtaAddress(userAddress, usdcMint);
     userSolAta = await getAtaAddress(userAddress, solMint);
 
@@ -255,7 +266,10 @@ describe("delegate-vault", () => {
           manager: translateAddress(manager),
           managerVaultA: translateAddress(orderVaultPda),
           managerVaultB: translateAddress(tokenVaultPda),
-          jupiterProgram: translateAddress(JUPITER_PROGRAM),
+          mintPolicy: translateAddress(await getMintPolicyAddress(solMint)),
+          swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+          venuePrograms: translateAddress(await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM))),
+          swapProgram: translateAddress(JUPITER_PROGRAM),
         })
         .remainingAccounts(swapRemainingAccounts)
         .instruction();
@@ -347,7 +361,9 @@ describe("delegate-vault", () => {
           isWritable: account.isWritable,
         }));
         const liquidateInstruction = await program.methods
-          .jupLiquidate(liquidationSerializedData)
+          .jupLiquidate([
+            { data: liquidationSerializedData, accountsLen: liquidationRemainingAccounts.length },
+          ])
           .accountsPartial({
             signer: translateAddress(hackerAddress),
             user: translateAddress(userAddress),
@@ -356,7 +372,14 @@ describe("delegate-vault", () => {
             manager: translateAddress(manager),
             managerVaultA: translateAddress(tokenVaultPda),
             managerVaultB: translateAddress(orderVaultPda),
-            jupiterProgram: translateAddress(JUPITER_PROGRAM),
+            tokenMint: translateAddress(solMint),
+            swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+            venuePrograms: translateAddress(await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM))),
+            swapProgram: translateAddress(JUPITER_PROGRAM),
+            tokenMintOracle: translateAddress(await getMintOracleAddress(SOL_MINT)),
+            tokenPriceFeed: null,
+            depositMintOracle: translateAddress(await getMintOracleAddress(USDC_MINT)),
+            depositPriceFeed: null,
             tokenProgram: TOKEN_PROGRAM_ID,
           })
           .remainingAccounts(liquidationRemainingAccounts)
@@ -456,7 +479,9 @@ describe("delegate-vault", () => {
         isWritable: account.isWritable,
       }));
       const liquidateInstruction = await program.methods
-        .jupLiquidate(liquidationSerializedData)
+        .jupLiquidate([
+          { data: liquidationSerializedData, accountsLen: liquidationRemainingAccounts.length },
+        ])
         .accountsPartial({
           signer: translateAddress(delegateAddress),
           user: translateAddress(userAddress),
@@ -465,7 +490,14 @@ describe("delegate-vault", () => {
           manager: translateAddress(manager),
           managerVaultA: translateAddress(tokenVaultPda),
           managerVaultB: translateAddress(orderVaultPda),
-          jupiterProgram: translateAddress(JUPITER_PROGRAM),
+          tokenMint: translateAddress(solMint),
+          swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+          venuePrograms: translateAddress(await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM))),
+          swapProgram: translateAddress(JUPITER_PROGRAM),
+          tokenMintOracle: translateAddress(await getMintOracleAddress(SOL_MINT)),
+          tokenPriceFeed: translateAddress(SOL_USD_PRICE_FEED),
+          depositMintOracle: translateAddress(await getMintOracleAddress(USDC_MINT)),
+          depositPriceFeed: translateAddress(USDC_USD_PRICE_FEED),
           tokenProgram: TOKEN_PROGRAM_ID,
         })
         .remainingAccounts(liquidationRemainingAccounts)
@@ -500,8 +532,7 @@ describe("delegate-vault", () => {
 
     test("Withdraw funds (should fail for delegate)", async () => {
       try {
-        const performanceReceiver = address(SQUADS_PERFORMANCE_ADDRESS);
-        const feeVault = await getAtaAddress(performanceReceiver, usdcMint);
+        const feeVault = await getFeeVaultAddress(usdcMint);
   
         const withdrawInstruction = await program.methods
           .withdraw()
@@ -509,12 +540,12 @@ describe("delegate-vault", () => {
             signer: translateAddress(delegateAddress),
             id: translateAddress(ephemeralKeyAddress),
             order: translateAddress(orderPda),
+            portfolio: null,
             manager: translateAddress(manager),
             config: translateAddress(config),
             depositMint: translateAddress(usdcMint),
             userAta: translateAddress(userUsdcAta),
             orderVault: translateAddress(orderVaultPda),
-            performanceReceiver: translateAddress(performanceReceiver),
             feeVault: translateAddress(feeVault),
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -538,8 +569,7 @@ describe("delegate-vault", () => {
     });
   });
   test("Withdraw funds", async () => {
-    const performanceReceiver = address(SQUADS_PERFORMANCE_ADDRESS);
-    const feeVault = await getAtaAddress(performanceReceiver, usdcMint);
+    const feeVault = await getFeeVaultAddress(usdcMint);
 
     const withdrawInstruction = await program.methods
       .withdraw()
@@ -547,12 +577,12 @@ describe("delegate-vault", () => {
         signer: translateAddress(userAddress),
         id: translateAddress(ephemeralKeyAddress),
         order: translateAddress(orderPda),
+        portfolio: null,
         manager: translateAddress(manager),
         config: translateAddress(config),
         depositMint: translateAddress(usdcMint),
         userAta: translateAddress(userUsdcAta),
         orderVault: translateAddress(orderVaultPda),
-        performanceReceiver: translateAddress(performanceReceiver),
         feeVault: translateAddress(feeVault),
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM,
@@ -579,4 +609,209 @@ describe("delegate-vault", () => {
     console.log("Transaction signature (withdraw):", withdrawSignature);
     await new Promise((res) => setTimeout(res, 2000));
   });
+
+  // JUP has no mint oracle, the delegate liquidations of its orders are only bounded by the route args
+  describe("Delegate liquidation without a mint oracle", () => {
+    let orderId: Address;
+    let order: Address;
+    let orderVault: Address;
+    let tokenVault: Address;
+
+    async function send(
+      instructions: anchor.web3.TransactionInstruction[],
+      signer: CryptoKeyPair,
+      lookupTableAddresses: string[] = []
+    ) {
+      const transaction = await prepareTransaction(
+        instructions.map(toInstruction),
+        await getAddressFromPublicKey(signer.publicKey),
+        await getLookupTables(lookupTableAddresses)
+      );
+      const decoded = transactionDecoder.decode(base64Encoder.encode(transaction));
+      const signed = await signTransaction([signer], decoded);
+      return confirmTransaction(getBase64EncodedWireTransaction(signed));
+    }
+
+    // Direct Jupiter route of the manager from inputVault to outputVault
+    async function fetchRoute(
+      inputMint: Address,
+      outputMint: Address,
+      amount: string,
+      inputVault: Address,
+      outputVault: Address
+    ) {
+      const quoteResponse = await ky
+        .get("https://api.jup.ag/swap/v1/quote", {
+          searchParams: {
+            inputMint: inputMint.toString(),
+            outputMint: outputMint.toString(),
+            amount,
+            onlyDirectRoutes: true,
+          },
+          headers: { "x-api-key": process.env.JUPITER_API_KEY },
+        })
+        .json<JupiterQuoteResponse>();
+      const data = await ky
+        .post("https://api.jup.ag/swap/v1/swap-instructions", {
+          json: {
+            quoteResponse,
+            userPublicKey: manager,
+            skipUserAccountsRpcCalls: true,
+            wrapAndUnwrapSol: true,
+            dynamicComputeUnitLimit: true,
+            dynamicSlippage: true,
+            destinationTokenAccount: outputVault.toString(),
+            restrictDestinationTokenAccount: true,
+            onlyDirectRoutes: true,
+            useSharedAccounts: true,
+          },
+          headers: { "x-api-key": process.env.JUPITER_API_KEY },
+        })
+        .json<JupiterSwapData>();
+      const possibleInputAta = await getAtaAddress(manager, inputMint);
+      const possibleOutputAta = await getAtaAddress(manager, outputMint);
+      return {
+        data: Buffer.from(data.swapInstruction.data, "base64"),
+        remainingAccounts: (data.swapInstruction.accounts || []).map((account) => ({
+          pubkey: translateAddress(
+            account.pubkey == possibleInputAta
+              ? inputVault
+              : account.pubkey == possibleOutputAta
+              ? outputVault
+              : account.pubkey == userAddress || account.pubkey == delegateAddress
+              ? manager
+              : account.pubkey
+          ),
+          isSigner: false,
+          isWritable: account.isWritable,
+        })),
+        lookupTableAddresses: data.addressLookupTableAddresses,
+      };
+    }
+
+    async function setBracket(stopLossPrice: number, takeProfitPrice: number) {
+      const instruction = await program.methods
+        .setBracket({
+          stopLossPrice: new BN(stopLossPrice),
+          takeProfitPrice: new BN(takeProfitPrice),
+          expiresAt: new BN(0),
+        })
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+        })
+        .instruction();
+      await send([instruction], user);
+    }
+
+    async function delegateLiquidateInstruction() {
+      const { value } = await connection.getTokenAccountBalance(translateAddress(tokenVault));
+      const route = await fetchRoute(JUP_MINT, usdcMint, value.amount, tokenVault, orderVault);
+      const instruction = await program.methods
+        .jupLiquidate([{ data: route.data, accountsLen: route.remainingAccounts.length }])
+        .accountsPartial({
+          signer: translateAddress(delegateAddress),
+          user: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+          managerVaultA: translateAddress(tokenVault),
+          managerVaultB: translateAddress(orderVault),
+          tokenMint: translateAddress(JUP_MINT),
+          swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+          venuePrograms: translateAddress(await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM))),
+          swapProgram: translateAddress(JUPITER_PROGRAM),
+          tokenMintOracle: translateAddress(await getMintOracleAddress(JUP_MINT)),
+          tokenPriceFeed: null,
+          depositMintOracle: translateAddress(await getMintOracleAddress(usdcMint)),
+          depositPriceFeed: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(route.remainingAccounts)
+        .instruction();
+      return { instruction, lookupTableAddresses: route.lookupTableAddresses };
+    }
+
+    beforeAll(async () => {
+      await setMintPolicy(program, JUP_MINT, {
+        allowDeposit: false,
+        allowTarget: true,
+        minDeposit: 0,
+        maxTvl: 0,
+      });
+
+      const orderIdKeyPair = await generateKeyPair();
+      orderId = await getAddressFromPublicKey(orderIdKeyPair.publicKey);
+      order = await getOrderAddress(manager, orderId);
+      orderVault = await getOrderVaultAddress(userAddress, manager, order, usdcMint);
+      tokenVault = await getTokenVaultAddress(userAddress, manager, order, JUP_MINT);
+
+      const depositInstruction = await program.methods
+        .deposit(new BN(depositAmount))
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+          depositMint: translateAddress(usdcMint),
+          userAta: translateAddress(userUsdcAta),
+          orderVault: translateAddress(orderVault),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+      const initTokenVaultInstruction = await program.methods
+        .initTokenVault()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+          mint: translateAddress(JUP_MINT),
+          tokenVault: translateAddress(tokenVault),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+      await send([depositInstruction, initTokenVaultInstruction], user);
+
+      const route = await fetchRoute(usdcMint, JUP_MINT, depositAmount.toString(), orderVault, tokenVault);
+      const swapInstruction = await program.methods
+        .jupSwap(route.data)
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+          managerVaultA: translateAddress(orderVault),
+          managerVaultB: translateAddress(tokenVault),
+          mintPolicy: translateAddress(await getMintPolicyAddress(JUP_MINT)),
+          swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+          venuePrograms: translateAddress(await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM))),
+          swapProgram: translateAddress(JUPITER_PROGRAM),
+        })
+        .remainingAccounts(route.remainingAccounts)
+        .instruction();
+      await send([swapInstruction], user, route.lookupTableAddresses);
+      await new Promise((res) => setTimeout(res, 2000));
+    });
+
+    test("Delegate bracket exit requires the mint oracles", async () => {
+      await setBracket(1, 0);
+      const { instruction, lookupTableAddresses } = await delegateLiquidateInstruction();
+      await expect(send([instruction], delegate, lookupTableAddresses)).rejects.toThrow(
+        "OracleRequired"
+      );
+      await setBracket(0, 0);
+    });
+
+    test("Delegate liquidates a token without a mint oracle", async () => {
+      const { instruction, lookupTableAddresses } = await delegateLiquidateInstruction();
+      await send([instruction], delegate, lookupTableAddresses);
+
+      expect(await connection.getAccountInfo(translateAddress(tokenVault))).toBeNull();
+    });
+  });
 });
@@ -0,0 +1,749 @@
+import { describe, test, expect, beforeAll, afterAll } from "bun:test";
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN, translateAddress } from "@coral-xyz/anchor";
+import { DelegateVault } from "../target/types/delegate_vault";
+import {
+  createAssociatedTokenAccountIdempotentInstruction,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM,
+  base64Encoder,
+  transactionDecoder,
+  FRNX_MINT,
+  USDC_MINT,
+} from "../utils/solana/constants";
+import {
+  getOrderAddress,
+  getOrderVaultAddress,
+  getManagerAddress,
+  getAtaAddress,
+  getConfigAddress,
+  getHighWaterMarkAddress,
+  getFeeVaultAddress,
+  getFeeScheduleAddress,
+  getReferrerAddress,
+  getReferrerVaultAddress,
+  getFeeOverrideAddress,
+  getDiscountConfigAddress,
+  getStakeAddress,
+  getStakeVaultAddress,
+  getMintPolicyAddress,
+  getRefundReserveAddress,
+} from "../utils/solana/pda";
+import { setupProgram } from "../utils/solana/setup";
+import {
+  generateKeyPair,
+  Address,
+  address,
+  createKeyPairFromBytes,
+  getAddressFromPublicKey,
+  signTransaction,
+  getBase64EncodedWireTransaction,
+} from "@solana/kit";
+import { prepareTransaction } from "../utils/solana/transaction/prepare";
+import { confirmTransaction } from "../utils/solana/transaction/confirm";
+import { toInstruction } from "../utils/solana/transaction/instructions/toInstruction";
+import * as fs from "fs";
+import * as path from "path";
+import bs58 from "bs58";
+
+describe("fees", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.DelegateVault as Program<DelegateVault>;
+  const admin = provider.wallet.publicKey;
+
+  const DEPOSIT_AMOUNT = 10_000; // 0.01 USDC
+
+  let user: CryptoKeyPair;
+  let delegate: CryptoKeyPair;
+  let delegateAddress: Address;
+  let userAddress: Address;
+  let userUsdcAta: Address;
+  let manager: Address;
+  let config: Address;
+  let feeVault: Address;
+
+  async function send(
+    instruction: anchor.web3.TransactionInstruction,
+    signer: CryptoKeyPair = user
+  ) {
+    const transaction = await prepareTransaction(
+      [toInstruction(instruction)],
+      await getAddressFromPublicKey(signer.publicKey),
+      {}
+    );
+    const decoded = transactionDecoder.decode(base64Encoder.encode(transaction));
+    const signed = await signTransaction([signer], decoded);
+    return confirmTransaction(getBase64EncodedWireTransaction(signed));
+  }
+
+  async function editConfig(
+    params: Partial<Parameters<typeof program.methods.editConfig>[0]>
+  ) {
+    return program.methods
+      .editConfig(params as Parameters<typeof program.methods.editConfig>[0])
+      .accountsPartial({
+        signer: admin,
+        config: translateAddress(config),
+      })
+      .rpc({ commitment: "confirmed" });
+  }
+
+  // Opens a new order with a deposit of the user, returns its id, address and vault
+  async function deposit(amount: number) {
+    const orderIdKeyPair = await generateKeyPair();
+    const orderId = await getAddressFromPublicKey(orderIdKeyPair.publicKey);
+    const order = await getOrderAddress(manager, orderId);
+    const orderVault = await getOrderVaultAddress(userAddress, manager, order, USDC_MINT);
+
+    const depositInstruction = await program.methods
+      .deposit(new BN(amount))
+      .accountsPartial({
+        signer: translateAddress(userAddress),
+        id: translateAddress(orderId),
+        order: translateAddress(order),
+        manager: translateAddress(manager),
+        depositMint: translateAddress(USDC_MINT),
+        userAta: translateAddress(userUsdcAta),
+        orderVault: translateAddress(orderVault),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .instruction();
+    await send(depositInstruction);
+
+    return { orderId, order, orderVault };
+  }
+
+  async function withdrawInstruction(
+    orderId: Address,
+    order: Address,
+    orderVault: Address,
+    optional: {
+      highWaterMark?: Address;
+    } = {}
+  ) {
+    const optionalAccount = (account?: Address) =>
+      account ? translateAddress(account) : null;
+    // always passed, the referrer share stays in the fee vault while the vault of the referrer is not initialized
+    const { referrer } = await program.account.manager.fetch(translateAddress(manager));
+
+    return program.methods
+      .withdraw()
+      .accountsPartial({
+        signer: translateAddress(userAddress),
+        id: translateAddress(orderId),
+        order: translateAddress(order),
+        portfolio: null,
+        manager: translateAddress(manager),
+        config: translateAddress(config),
+        orderVault: translateAddress(orderVault),
+        userAta: translateAddress(userUsdcAta),
+        feeVault: translateAddress(feeVault),
+        feeSchedule: null,
+        depositMint: translateAddress(USDC_MINT),
+        mintPolicy: translateAddress(await getMintPolicyAddress(USDC_MINT)),
+        highWaterMark: optionalAccount(optional.highWaterMark),
+        referrerVault: translateAddress(
+          await getReferrerVaultAddress(address(referrer.toBase58()), USDC_MINT)
+        ),
+        mintFeeOverride: translateAddress(await getFeeOverrideAddress("mint", USDC_MINT)),
+        managerFeeOverride: translateAddress(await getFeeOverrideAddress("manager", manager)),
+        discountConfig: null,
+        stake: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM,
+      })
+      .instruction();
+  }
+
+  beforeAll(async () => {
+    await setupProgram(program);
+
+    const keysPath = path.join(__dirname, "..", "tests", "keys", "test-keys.json");
+    const keys = JSON.parse(fs.readFileSync(keysPath, "utf8"));
+    const userKeyPair = Keypair.fromSecretKey(bs58.decode(keys.user.secretKey));
+    user = await createKeyPairFromBytes(userKeyPair.secretKey);
+    userAddress = await getAddressFromPublicKey(user.publicKey);
+    const delegateKeyPair = Keypair.fromSecretKey(bs58.decode(keys.delegate.secretKey));
+    delegate = await createKeyPairFromBytes(delegateKeyPair.secretKey);
+    delegateAddress = await getAddressFromPublicKey(delegate.publicKey);
+
+    config = await getConfigAddress();
+    manager = await getManagerAddress(userAddress);
+    userUsdcAta = await getAtaAddress(userAddress, USDC_MINT);
+    feeVault = await getFeeVaultAddress(USDC_MINT);
+
+    if (!(await provider.connection.getAccountInfo(translateAddress(feeVault)))) {
+      await program.methods
+        .initFeeVault()
+        .accountsPartial({
+          signer: admin,
+          config: translateAddress(config),
+          mint: translateAddress(USDC_MINT),
+          feeVault: translateAddress(feeVault),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+    }
+  });
+
+  afterAll(async () => {
+    await editConfig({
+      managementFee: 0,
+      subscribedManagementFee: 0,
+      highWaterMark: false,
+    });
+  });
+
+  describe("management fee", () => {
+    test("Rejects a management fee above 100%", async () => {
+      await expect(editConfig({ managementFee: 10_001 })).rejects.toThrow(
+        "IncorrectFee"
+      );
+    });
+
+    test("Rejects config edits of other signers", async () => {
+      const instruction = await program.methods
+        .editConfig({
+          performanceFee: null,
+          managementFee: 0,
+          subscribedManagementFee: null,
+          highWaterMark: null,
+        })
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          config: translateAddress(config),
+        })
+        .instruction();
+      await expect(send(instruction)).rejects.toThrow("IncorrectSigner");
+    });
+
+    test("Accrues the management fee of the order until it is collected", async () => {
+      await editConfig({ managementFee: 10_000, subscribedManagementFee: 10_000 });
+      const { orderId, order, orderVault } = await deposit(DEPOSIT_AMOUNT);
+      const opened = await program.account.order.fetch(translateAddress(order));
+
+      await new Promise((res) => setTimeout(res, 5000));
+      const instruction = await program.methods
+        .collectManagementFee()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+          config: translateAddress(config),
+          orderVault: translateAddress(orderVault),
+          feeVault: translateAddress(feeVault),
+          feeSchedule: null,
+          depositMint: translateAddress(USDC_MINT),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM,
+        })
+        .instruction();
+      await send(instruction);
+
+      const collected = await program.account.order.fetch(translateAddress(order));
+      expect(collected.lastAccrualAt.toNumber()).toBeGreaterThan(
+        opened.lastAccrualAt.toNumber()
+      );
+      expect(collected.managementFeeAccrued.toNumber()).toBe(0);
+    });
+  });
+
+  describe("high water mark", () => {
+    let highWaterMark: Address;
+
+    beforeAll(async () => {
+      highWaterMark = await getHighWaterMarkAddress(manager, USDC_MINT);
+      if (await program.account.highWaterMark.fetchNullable(translateAddress(highWaterMark))) {
+        return;
+      }
+
+      const instruction = await program.methods
+        .initHighWaterMark()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          manager: translateAddress(manager),
+          mint: translateAddress(USDC_MINT),
+          highWaterMark: translateAddress(highWaterMark),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+      await send(instruction);
+    });
+
+    test("Creates the high water mark of the manager", async () => {
+      const fetched = await program.account.highWaterMark.fetch(
+        translateAddress(highWaterMark)
+      );
+      expect(fetched.manager.toBase58()).toBe(manager);
+      expect(fetched.mint.toBase58()).toBe(USDC_MINT);
+    });
+
+    test("Rejects withdrawals without the high water mark once enabled", async () => {
+      await editConfig({ highWaterMark: true });
+      const { orderId, order, orderVault } = await deposit(DEPOSIT_AMOUNT);
+
+      await expect(
+        send(await withdrawInstruction(orderId, order, orderVault))
+      ).rejects.toThrow("HighWaterMarkRequired");
+    });
+
+    test("Records the PnL of the withdrawals on the high water mark", async () => {
+      await editConfig({ highWaterMark: true });
+      const { orderId, order, orderVault } = await deposit(DEPOSIT_AMOUNT);
+      const before = await program.account.highWaterMark.fetch(
+        translateAddress(highWaterMark)
+      );
+
+      await send(await withdrawInstruction(orderId, order, orderVault, { highWaterMark }));
+
+      // Nothing was swapped, the PnL of the order is 0 and no performance fee is charged
+      const after = await program.account.highWaterMark.fetch(
+        translateAddress(highWaterMark)
+      );
+      expect(after.cumulativePnl.toNumber()).toBe(before.cumulativePnl.toNumber());
+      expect(after.highWaterMark.toNumber()).toBe(before.highWaterMark.toNumber());
+    });
+  });
+
+  describe("fee vaults", () => {
+    const adminAta = getAssociatedTokenAddressSync(translateAddress(USDC_MINT), admin);
+
+    async function claimFeesMethod(shares: number[], receivers: anchor.web3.PublicKey[]) {
+      return program.methods
+        .claimFees(shares)
+        .accountsPartial({
+          signer: admin,
+          config: translateAddress(config),
+          mint: translateAddress(USDC_MINT),
+          feeVault: translateAddress(feeVault),
+          refundReserve: translateAddress(await getRefundReserveAddress(USDC_MINT)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          receivers.map((receiver) => ({ pubkey: receiver, isSigner: false, isWritable: true }))
+        )
+        .preInstructions([
+          createAssociatedTokenAccountIdempotentInstruction(
+            admin,
+            adminAta,
+            admin,
+            translateAddress(USDC_MINT)
+          ),
+        ]);
+    }
+
+    test("Creates the fee vault under the config authority", async () => {
+      const account = await getAccount(provider.connection, translateAddress(feeVault));
+      expect(account.owner.toBase58()).toBe(config);
+      expect(account.mint.toBase58()).toBe(USDC_MINT);
+    });
+
+    test("Rejects shares not adding up to 100%", async () => {
+      await expect(
+        (await claimFeesMethod([5_000], [adminAta])).rpc({ commitment: "confirmed" })
+      ).rejects.toThrow("InvalidShares");
+    });
+
+    test("Rejects a receiver count different from the shares", async () => {
+      await expect(
+        (await claimFeesMethod([5_000, 5_000], [adminAta])).rpc({ commitment: "confirmed" })
+      ).rejects.toThrow("InvalidRemainingAccounts");
+    });
+
+    test("Rejects claims of other signers", async () => {
+      const instruction = await program.methods
+        .claimFees([10_000])
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          config: translateAddress(config),
+          mint: translateAddress(USDC_MINT),
+          feeVault: translateAddress(feeVault),
+          refundReserve: translateAddress(await getRefundReserveAddress(USDC_MINT)),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: translateAddress(userUsdcAta), isSigner: false, isWritable: true },
+        ])
+        .instruction();
+      await expect(send(instruction)).rejects.toThrow("IncorrectSigner");
+    });
+
+    test("Claims the fee vault to the receivers", async () => {
+      await (await claimFeesMethod([10_000], [adminAta])).rpc({ commitment: "confirmed" });
+
+      const account = await getAccount(
+        provider.connection,
+        translateAddress(feeVault),
+        "confirmed"
+      );
+      expect(Number(account.amount)).toBe(0);
+    });
+  });
+
+  describe("fee schedule", () => {
+    async function setFeeSchedule(
+      recipients: { wallet: anchor.web3.PublicKey; share: number }[],
+      dustRecipient: number
+    ) {
+      const feeSchedule = translateAddress(await getFeeScheduleAddress());
+      const params = { recipients, dustRecipient, enabled: false };
+      const existing = await program.account.feeSchedule.fetchNullable(feeSchedule);
+      const method = existing
+        ? program.methods.editFeeSchedule(params)
+        : program.methods.initFeeSchedule(params);
+      return method
+        .accountsPartial({
+          signer: admin,
+          config: translateAddress(config),
+          feeSchedule,
+        })
+        .rpc({ commitment: "confirmed" });
+    }
+
+    test("Rejects an empty fee schedule", async () => {
+      await expect(setFeeSchedule([], 0)).rejects.toThrow("InvalidFeeSchedule");
+    });
+
+    test("Rejects shares not adding up to 100%", async () => {
+      await expect(
+        setFeeSchedule(
+          [
+            { wallet: admin, share: 7_000 },
+            { wallet: translateAddress(userAddress), share: 2_000 },
+          ],
+          0
+        )
+      ).rejects.toThrow("InvalidShares");
+    });
+
+    test("Rejects a dust recipient out of the recipients", async () => {
+      await expect(
+        setFeeSchedule(
+          [
+            { wallet: admin, share: 7_000 },
+            { wallet: translateAddress(userAddress), share: 3_000 },
+          ],
+          2
+        )
+      ).rejects.toThrow("InvalidShares");
+    });
+
+    test("Sets the fee schedule recipients", async () => {
+      await setFeeSchedule(
+        [
+          { wallet: admin, share: 7_000 },
+          { wallet: translateAddress(userAddress), share: 3_000 },
+        ],
+        1
+      );
+
+      const feeSchedule = await program.account.feeSchedule.fetch(
+        translateAddress(await getFeeScheduleAddress())
+      );
+      expect(feeSchedule.recipients.map((recipient) => recipient.share)).toEqual([
+        7_000, 3_000,
+      ]);
+      expect(feeSchedule.dustRecipient).toBe(1);
+
+      const configAccount = await program.account.config.fetch(translateAddress(config));
+      expect(configAccount.feeScheduleEnabled).toBe(false);
+    });
+  });
+
+  describe("referral", () => {
+    let referrer: Address;
+    let referrerVault: Address;
+
+    async function setReferrer(signer: CryptoKeyPair) {
+      const signerAddress = await getAddressFromPublicKey(signer.publicKey);
+      const instruction = await program.methods
+        .setReferrer()
+        .accountsPartial({
+          signer: translateAddress(signerAddress),
+          manager: translateAddress(await getManagerAddress(signerAddress)),
+          referrer: translateAddress(referrer),
+        })
+        .instruction();
+      return send(instruction, signer);
+    }
+
+    beforeAll(async () => {
+      referrer = await getReferrerAddress(delegateAddress);
+      referrerVault = await getReferrerVaultAddress(referrer, USDC_MINT);
+
+      const delegateManager = await getManagerAddress(delegateAddress);
+      if (!(await provider.connection.getAccountInfo(translateAddress(delegateManager)))) {
+        const instruction = await program.methods
+          .initManager()
+          .accountsPartial({
+            signer: translateAddress(delegateAddress),
+            delegate: translateAddress(userAddress),
+            manager: translateAddress(delegateManager),
+            referrer: null,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .instruction();
+        await send(instruction, delegate);
+      }
+    });
+
+    test("Registers a referrer", async () => {
+      if (!(await program.account.referrer.fetchNullable(translateAddress(referrer)))) {
+        const instruction = await program.methods
+          .initReferrer()
+          .accountsPartial({
+            signer: translateAddress(delegateAddress),
+            referrer: translateAddress(referrer),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .instruction();
+        await send(instruction, delegate);
+      }
+
+      const fetched = await program.account.referrer.fetch(translateAddress(referrer));
+      expect(fetched.wallet.toBase58()).toBe(delegateAddress);
+    });
+
+    test("Rejects referring yourself", async () => {
+      await expect(setReferrer(delegate)).rejects.toThrow("InvalidReferrer");
+    });
+
+    test("Sets the referrer of the manager only once", async () => {
+      const before = await program.account.manager.fetch(translateAddress(manager));
+      if (before.referrer.equals(anchor.web3.PublicKey.default)) {
+        await setReferrer(user);
+      }
+
+      const after = await program.account.manager.fetch(translateAddress(manager));
+      expect(after.referrer.toBase58()).toBe(referrer);
+      await expect(setReferrer(user)).rejects.toThrow("ReferrerAlreadySet");
+    });
+
+    test("Creates the referrer vault under the referrer PDA", async () => {
+      if (!(await provider.connection.getAccountInfo(translateAddress(referrerVault)))) {
+        const instruction = await program.methods
+          .initReferrerVault()
+          .accountsPartial({
+            signer: translateAddress(userAddress),
+            referrer: translateAddress(referrer),
+            mint: translateAddress(USDC_MINT),
+            referrerVault: translateAddress(referrerVault),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .instruction();
+        await send(instruction);
+      }
+
+      const account = await getAccount(
+        provider.connection,
+        translateAddress(referrerVault),
+        "confirmed"
+      );
+      expect(account.owner.toBase58()).toBe(referrer);
+    });
+
+    test("Withdraws orders of a referred manager", async () => {
+      await editConfig({ highWaterMark: false });
+      const { orderId, order, orderVault } = await deposit(DEPOSIT_AMOUNT);
+      await send(await withdrawInstruction(orderId, order, orderVault));
+
+      expect(await provider.connection.getAccountInfo(translateAddress(order))).toBeNull();
+    });
+  });
+
+  describe("fee overrides", () => {
+    let feeOverride: Address;
+
+    function feeOverrideParams(performanceFee: number) {
+      return {
+        kind: { manager: {} },
+        target: translateAddress(manager),
+        subscribedPerformanceFee: performanceFee,
+        performanceFee,
+      };
+    }
+
+    async function closeFeeOverride() {
+      await program.methods
+        .closeFeeOverride()
+        .accountsPartial({
+          signer: admin,
+          config: translateAddress(config),
+          feeOverride: translateAddress(feeOverride),
+        })
+        .rpc({ commitment: "confirmed" });
+    }
+
+    beforeAll(async () => {
+      feeOverride = await getFeeOverrideAddress("manager", manager);
+      if (await program.account.feeOverride.fetchNullable(translateAddress(feeOverride))) {
+        await closeFeeOverride();
+      }
+    });
+
+    test("Rejects an override fee above 100%", async () => {
+      await expect(
+        program.methods
+          .initFeeOverride(feeOverrideParams(10_001))
+          .accountsPartial({
+            signer: admin,
+            config: translateAddress(config),
+            feeOverride: translateAddress(feeOverride),
+          })
+          .rpc({ commitment: "confirmed" })
+      ).rejects.toThrow("IncorrectFee");
+    });
+
+    test("Rejects overrides of other signers", async () => {
+      const instruction = await program.methods
+        .initFeeOverride(feeOverrideParams(0))
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          config: translateAddress(config),
+          feeOverride: translateAddress(feeOverride),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+      await expect(send(instruction)).rejects.toThrow("IncorrectSigner");
+    });
+
+    test("Overrides the performance fee of the manager until closed", async () => {
+      await program.methods
+        .initFeeOverride(feeOverrideParams(0))
+        .accountsPartial({
+          signer: admin,
+          config: translateAddress(config),
+          feeOverride: translateAddress(feeOverride),
+        })
+        .rpc({ commitment: "confirmed" });
+
+      const fetched = await program.account.feeOverride.fetch(translateAddress(feeOverride));
+      expect(fetched.target.toBase58()).toBe(manager);
+      expect(fetched.performanceFee).toBe(0);
+
+      const { orderId, order, orderVault } = await deposit(DEPOSIT_AMOUNT);
+      await send(await withdrawInstruction(orderId, order, orderVault));
+
+      await closeFeeOverride();
+      expect(
+        await program.account.feeOverride.fetchNullable(translateAddress(feeOverride))
+      ).toBeNull();
+    });
+  });
+
+  describe("staking discount", () => {
+    let discountConfig: Address;
+
+    async function setDiscountConfig(tiers: { minAmount: number; discount: number }[]) {
+      const params = {
+        lockDuration: new BN(60),
+        tiers: tiers.map((tier) => ({ minAmount: new BN(tier.minAmount), discount: tier.discount })),
+      };
+      const existing = await program.account.discountConfig.fetchNullable(
+        translateAddress(discountConfig)
+      );
+      const method = existing
+        ? program.methods.editDiscountConfig(params).accountsPartial({
+            signer: admin,
+            config: translateAddress(config),
+            discountConfig: translateAddress(discountConfig),
+          })
+        : program.methods.initDiscountConfig(params).accountsPartial({
+            signer: admin,
+            config: translateAddress(config),
+            discountConfig: translateAddress(discountConfig),
+            mint: translateAddress(FRNX_MINT),
+          });
+      return method.rpc({ commitment: "confirmed" });
+    }
+
+    // Accounts of init_stake, stake and unstake for the manager of the user
+    async function stakeAccounts() {
+      return {
+        signer: translateAddress(userAddress),
+        manager: translateAddress(manager),
+        discountConfig: translateAddress(discountConfig),
+        mint: translateAddress(FRNX_MINT),
+        stake: translateAddress(await getStakeAddress(manager)),
+        stakeVault: translateAddress(await getStakeVaultAddress(manager)),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      };
+    }
+
+    beforeAll(async () => {
+      discountConfig = await getDiscountConfigAddress();
+    });
+
+    test("Rejects tiers not sorted by amount", async () => {
+      await expect(
+        setDiscountConfig([
+          { minAmount: 10_000_000, discount: 2_500 },
+          { minAmount: 1_000_000, discount: 1_000 },
+        ])
+      ).rejects.toThrow("InvalidDiscountTiers");
+    });
+
+    test("Rejects a discount above 100%", async () => {
+      await expect(
+        setDiscountConfig([{ minAmount: 1_000_000, discount: 10_001 }])
+      ).rejects.toThrow("InvalidDiscountTiers");
+    });
+
+    test("Sets the discount tiers", async () => {
+      await setDiscountConfig([
+        { minAmount: 1_000_000, discount: 1_000 },
+        { minAmount: 10_000_000, discount: 2_500 },
+      ]);
+
+      const fetched = await program.account.discountConfig.fetch(translateAddress(discountConfig));
+      expect(fetched.tiers.map((tier) => tier.discount)).toEqual([1_000, 2_500]);
+      expect(fetched.lockDuration.toNumber()).toBe(60);
+    });
+
+    test("Creates the stake vault of the manager", async () => {
+      const accounts = await stakeAccounts();
+      if (!(await program.account.stake.fetchNullable(accounts.stake))) {
+        const instruction = await program.methods
+          .initStake()
+          .accountsPartial({ ...accounts, systemProgram: anchor.web3.SystemProgram.programId })
+          .instruction();
+        await send(instruction);
+      }
+
+      const stakeVault = await getAccount(provider.connection, accounts.stakeVault, "confirmed");
+      expect(stakeVault.owner.toBase58()).toBe(accounts.stake.toBase58());
+      expect(stakeVault.mint.toBase58()).toBe(FRNX_MINT);
+    });
+
+    test("Rejects unstaking more than staked", async () => {
+      const accounts = await stakeAccounts();
+      const stake = await program.account.stake.fetch(accounts.stake);
+      const signerAta = getAssociatedTokenAddressSync(accounts.mint, accounts.signer);
+      await send(
+        createAssociatedTokenAccountIdempotentInstruction(
+          accounts.signer,
+          signerAta,
+          accounts.signer,
+          accounts.mint
+        )
+      );
+
+      const instruction = await program.methods
+        .unstake(stake.amount.addn(1))
+        .accountsPartial({ ...accounts, signerAta })
+        .instruction();
+      await expect(send(instruction)).rejects.toThrow("InsufficientStake");
+    });
+  });
+});
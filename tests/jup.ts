@@ -7,6 +7,8 @@ import { Keypair } from "@solana/web3.js";
 import { BN } from "@coral-xyz/anchor";
 import {
   JUPITER_PROGRAM,
+  JUPITER_AMM_PROGRAMS,
+  MEMO_PROGRAM,
   base64Encoder,
   transactionDecoder,
   ASSOCIATED_TOKEN_PROGRAM,
@@ -19,7 +21,13 @@ import {
   getTokenVaultAddress,
   getManagerAddress,
   getConfigAddress,
+  getFeeVaultAddress,
+  getMintPolicyAddress,
+  getSwapVenueAddress,
+  getMintOracleAddress,
+  getVenueProgramsAddress,
 } from "../utils/solana/pda";
+import { JUPITER_VENUE_ROUTES, setupProgram } from "../utils/solana/setup";
 import {
   generateKeyPair,
   address,
@@ -119,12 +127,95 @@ describe("jupiter operations", () => {
   let swapData: JupiterSwapData;
   let liquidationSwapData: JupiterSwapData;
   let tokenVaultAmount: string;
+  // Second order of the same manager, its vaults must not be reachable from the swaps of the first order
+  let otherOrderId: Address;
+  let otherOrderAddress: Address;
+  let otherOrderVaultAddress: Address;
+  let otherTokenVaultAddress: Address;
+
+
+  async function sendAsUser(
+    instructions: anchor.web3.TransactionInstruction[],
+    lookupTableAccounts = {}
+  ) {
+    const transaction = await prepareTransaction(
+      instructions.map(toInstruction),
+      userAddress,
+      lookupTableAccounts
+    );
+    const decoded = transactionDecoder.decode(base64Encoder.encode(transaction));
+    const signed = await signTransaction([user], decoded);
+    return confirmTransaction(getBase64EncodedWireTransaction(signed));
+  }
+
+  // Jupiter route of the manager from inputVault to outputVault, accounts remapped to the order vaults
+  async function fetchSwapRoute(
+    inputMint: Address,
+    outputMint: Address,
+    amount: string,
+    inputVault: Address,
+    outputVault: Address
+  ) {
+    const quoteResponse = await ky
+      .get("https://api.jup.ag/swap/v1/quote", {
+        searchParams: {
+          inputMint: inputMint.toString(),
+          outputMint: outputMint.toString(),
+          amount,
+        },
+        headers: { "x-api-key": process.env.JUPITER_API_KEY },
+      })
+      .json<JupiterQuoteResponse>();
+    const data = await ky
+      .post("https://api.jup.ag/swap/v1/swap-instructions", {
+        json: {
+          quoteResponse,
+          userPublicKey: manager,
+          skipUserAccountsRpcCalls: true,
+          wrapAndUnwrapSol: true,
+          dynamicComputeUnitLimit: true,
+          dynamicSlippage: true,
+          destinationTokenAccount: outputVault,
+          useSharedAccounts: true,
+          restrictDestinationTokenAccount: true,
+          onlyDirectRoutes: true,
+        },
+        headers: { "x-api-key": process.env.JUPITER_API_KEY },
+      })
+      .json<JupiterSwapData>();
+    const possibleInputAta = await getAtaAddress(manager, inputMint);
+    const possibleOutputAta = await getAtaAddress(manager, outputMint);
+    const remainingAccounts = (data.swapInstruction.accounts || []).map(
+      (account) => ({
+        pubkey: translateAddress(
+          account.pubkey == possibleInputAta
+            ? inputVault
+            : account.pubkey == possibleOutputAta
+            ? outputVault
+            : account.pubkey == userAddress
+            ? manager
+            : account.pubkey
+        ),
+        isSigner: false,
+        isWritable: account.isWritable,
+      })
+    );
+    return {
+      data: Buffer.from(data.swapInstruction.data, "base64"),
+      remainingAccounts,
+      lookupTableAccounts: await getLookupTables(
+        data.addressLookupTableAddresses
+      ),
+    };
+  }
 
   describe("Jupiter Flow (stepwise)", () => {
     const DEPOSIT_AMOUNT = 10_000; // 0.01 USDC in base units
     const DEPOSIT_MINT = usdcMint;
 
     beforeAll(async () => {
+      // Mint policies and swap venue required by the deposits and swaps
+      await setupProgram(program);
       const keysPath = path.join(
         __dirname,
         "..",
@@ -147,7 +238,7 @@ describe("jupiter operations", () => {
       // Derive PDAs
       config = await getConfigAddress();
       manager = await getManagerAddress(userAddress);
-      feeVault = await getAtaAddress(config, usdcMint);
+      feeVault = await getFeeVaultAddress(usdcMint);
       userUsdcAta = await getAtaAddress(userAddress, usdcMint);
       userSolAta = await getAtaAddress(userAddress, solMint);
       orderIdKeypair = await generateKeyPair();
@@ -238,6 +329,353 @@ describe("jupiter operations", () => {
       await new Promise((res) => setTimeout(res, 2000));
     });
 
+    async function setMaxSlippage(maxSlippageBps: number) {
+      const instruction = await program.methods
+        .setMaxSlippage(maxSlippageBps)
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(orderAddress),
+          manager: translateAddress(manager),
+        })
+        .instruction();
+      await sendAsUser([instruction]);
+      await new Promise((res) => setTimeout(res, 2000));
+    }
+
+    async function swapInstruction(
+      route: Awaited<ReturnType<typeof fetchSwapRoute>>,
+      tokenVault: Address = tokenVaultAddress
+    ) {
+      return program.methods
+        .jupSwap(route.data)
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(orderAddress),
+          manager: translateAddress(manager),
+          managerVaultA: translateAddress(orderVaultAddress),
+          managerVaultB: translateAddress(tokenVault),
+          mintPolicy: translateAddress(await getMintPolicyAddress(SOL_MINT)),
+          swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+          venuePrograms: translateAddress(await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM))),
+          swapProgram: translateAddress(JUPITER_PROGRAM),
+        })
+        .remainingAccounts(route.remainingAccounts)
+        .instruction();
+    }
+
+    // Admin edit of the Jupiter venue, the provider wallet is the config authority
+    async function editJupiterVenue(
+      routes: Parameters<typeof program.methods.editSwapVenue>[0]["routes"],
+      active: boolean
+    ) {
+      await program.methods
+        .editSwapVenue({ routes, active })
+        .accountsPartial({
+          signer: provider.wallet.publicKey,
+          config: translateAddress(config),
+          swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+        })
+        .rpc({ commitment: "confirmed" });
+    }
+
+    test("Registers the Jupiter routes on the swap venue", async () => {
+      const swapVenue = await program.account.swapVenue.fetch(
+        translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM))
+      );
+      expect(swapVenue.programId.toBase58()).toBe(JUPITER_PROGRAM);
+      expect(swapVenue.active).toBe(true);
+      expect(swapVenue.routes.length).toBe(JUPITER_VENUE_ROUTES.length);
+    });
+
+    test("Rejects swap venue edits by a non-authority", async () => {
+      const editInstruction = await program.methods
+        .editSwapVenue({ routes: JUPITER_VENUE_ROUTES, active: false })
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          config: translateAddress(config),
+          swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+        })
+        .instruction();
+      await expect(sendAsUser([editInstruction])).rejects.toThrow("IncorrectSigner");
+    });
+
+    test("Lists the AMMs of the routes on the venue programs", async () => {
+      const venuePrograms = await program.account.venuePrograms.fetch(
+        translateAddress(await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM)))
+      );
+      expect(venuePrograms.programs.map((program) => program.toBase58())).toEqual(
+        JUPITER_AMM_PROGRAMS
+      );
+    });
+
+    test("Rejects venue programs edits by a non-authority", async () => {
+      const editInstruction = await program.methods
+        .editVenuePrograms({ programs: [] })
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          config: translateAddress(config),
+          venuePrograms: translateAddress(
+            await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM))
+          ),
+        })
+        .instruction();
+      await expect(sendAsUser([editInstruction])).rejects.toThrow("IncorrectSigner");
+    });
+
+    test("Rejects duplicate routes on the swap venue", async () => {
+      await expect(
+        editJupiterVenue([...JUPITER_VENUE_ROUTES, JUPITER_VENUE_ROUTES[0]], true)
+      ).rejects.toThrow("InvalidSwapVenue");
+    });
+
+    test("Jupiter swap rejects an inactive venue", async () => {
+      await editJupiterVenue(JUPITER_VENUE_ROUTES, false);
+      const route = await fetchSwapRoute(
+        usdcMint,
+        solMint,
+        depositAmount.toString(),
+        orderVaultAddress,
+        tokenVaultAddress
+      );
+      await expect(
+        sendAsUser([await swapInstruction(route)], route.lookupTableAccounts)
+      ).rejects.toThrow("InactiveSwapVenue");
+      await editJupiterVenue(JUPITER_VENUE_ROUTES, true);
+    });
+
+    test("Jupiter swap rejects a route not registered on the venue", async () => {
+      // The routes are fetched with shared accounts, only keep the first three routes without them
+      await editJupiterVenue(JUPITER_VENUE_ROUTES.slice(0, 3), true);
+      const route = await fetchSwapRoute(
+        usdcMint,
+        solMint,
+        depositAmount.toString(),
+        orderVaultAddress,
+        tokenVaultAddress
+      );
+      await expect(
+        sendAsUser([await swapInstruction(route)], route.lookupTableAccounts)
+      ).rejects.toThrow("UnknownRoute");
+      await editJupiterVenue(JUPITER_VENUE_ROUTES, true);
+    });
+
+    test("Jupiter swap rejects a route through a program not allowed", async () => {
+      const route = await fetchSwapRoute(
+        usdcMint,
+        solMint,
+        depositAmount.toString(),
+        orderVaultAddress,
+        tokenVaultAddress
+      );
+      route.remainingAccounts.push({
+        pubkey: translateAddress(MEMO_PROGRAM),
+        isSigner: false,
+        isWritable: false,
+      });
+      await expect(
+        sendAsUser([await swapInstruction(route)], route.lookupTableAccounts)
+      ).rejects.toThrow("UnexpectedProgram");
+    });
+
+    // Resting order route of the tests, registered on the Jupiter venue only to check the registry and the route kinds
+    const RESTING_ORDER_ROUTE = {
+      discriminator: [141, 54, 37, 207, 237, 210, 250, 215],
+      kind: { openRestingOrder: {} },
+      argsLayout: { tail: { inAmountOffset: 0, slippageOffset: 0, platformFeeOffset: 0 } },
+      transferAuthorityIdx: 1,
+      sourceIdx: 3,
+      destinationIdx: 4,
+      destinationMintIdx: 6,
+      platformFeeAccountIdx: 255,
+      restingOrderIdx: 2,
+    };
+
+    test("Rejects a resting order route without a resting order account", async () => {
+      await expect(
+        editJupiterVenue(
+          [...JUPITER_VENUE_ROUTES, { ...RESTING_ORDER_ROUTE, restingOrderIdx: 255 }],
+          true
+        )
+      ).rejects.toThrow("InvalidSwapVenue");
+    });
+
+    test("Registers a resting order route on the venue", async () => {
+      await editJupiterVenue([...JUPITER_VENUE_ROUTES, RESTING_ORDER_ROUTE], true);
+
+      const swapVenue = await program.account.swapVenue.fetch(
+        translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM))
+      );
+      const route = swapVenue.routes[swapVenue.routes.length - 1];
+      expect(route.kind).toEqual({ openRestingOrder: {} });
+      expect(route.restingOrderIdx).toBe(2);
+    });
+
+    test("Open resting order rejects a swap route", async () => {
+      const route = await fetchSwapRoute(
+        usdcMint,
+        solMint,
+        depositAmount.toString(),
+        orderVaultAddress,
+        tokenVaultAddress
+      );
+      const openInstruction = await program.methods
+        .jupOpenRestingOrder(route.data)
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(orderAddress),
+          manager: translateAddress(manager),
+          managerVaultA: translateAddress(orderVaultAddress),
+          managerVaultB: translateAddress(tokenVaultAddress),
+          mintPolicy: translateAddress(await getMintPolicyAddress(SOL_MINT)),
+          swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+          venuePrograms: translateAddress(await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM))),
+          swapProgram: translateAddress(JUPITER_PROGRAM),
+        })
+        .remainingAccounts(route.remainingAccounts)
+        .instruction();
+      await expect(
+        sendAsUser([openInstruction], route.lookupTableAccounts)
+      ).rejects.toThrow("UnknownRoute");
+      await editJupiterVenue(JUPITER_VENUE_ROUTES, true);
+    });
+
+    test("Rejects a max slippage above 100%", async () => {
+      await expect(setMaxSlippage(10_001)).rejects.toThrow("InvalidMaxSlippage");
+    });
+
+    test("Sets the max slippage of the order", async () => {
+      await setMaxSlippage(100);
+      const order = await program.account.order.fetch(translateAddress(orderAddress));
+      expect(order.maxSlippageBps).toBe(100);
+    });
+
+    test("Jupiter swap rejects a route above the vault balance", async () => {
+      const route = await fetchSwapRoute(
+        usdcMint,
+        solMint,
+        (depositAmount * 2).toString(),
+        orderVaultAddress,
+        tokenVaultAddress
+      );
+      await expect(
+        sendAsUser([await swapInstruction(route)], route.lookupTableAccounts)
+      ).rejects.toThrow("RouteAmountExceedsBalance");
+    });
+
+    test("Jupiter swap rejects a slippage above the order limit", async () => {
+      await setMaxSlippage(0);
+      const route = await fetchSwapRoute(
+        usdcMint,
+        solMint,
+        depositAmount.toString(),
+        orderVaultAddress,
+        tokenVaultAddress
+      );
+      await expect(
+        sendAsUser([await swapInstruction(route)], route.lookupTableAccounts)
+      ).rejects.toThrow("SlippageTooHigh");
+      // back to the default of the deposits for the swap below
+      await setMaxSlippage(300);
+    });
+
+    test("Open a second order", async () => {
+      const otherOrderIdKeypair = await generateKeyPair();
+      otherOrderId = await getAddressFromPublicKey(otherOrderIdKeypair.publicKey);
+      otherOrderAddress = await getOrderAddress(manager, otherOrderId);
+      otherOrderVaultAddress = await getOrderVaultAddress(
+        userAddress,
+        manager,
+        otherOrderAddress,
+        DEPOSIT_MINT
+      );
+      otherTokenVaultAddress = await getTokenVaultAddress(
+        userAddress,
+        manager,
+        otherOrderAddress,
+        SOL_MINT
+      );
+      const depositInstruction = await program.methods
+        .deposit(new BN(depositAmount))
+        .accountsPartial({
+          signer: address(userAddress),
+          id: address(otherOrderId),
+          order: address(otherOrderAddress),
+          manager: address(manager),
+          depositMint: address(DEPOSIT_MINT),
+          userAta: address(userUsdcAta),
+          orderVault: address(otherOrderVaultAddress),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+      const initTokenVaultInstruction = await program.methods
+        .initTokenVault()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(otherOrderId),
+          order: translateAddress(otherOrderAddress),
+          manager: translateAddress(manager),
+          mint: translateAddress(solMint),
+          mintPolicy: translateAddress(await getMintPolicyAddress(SOL_MINT)),
+          tokenVault: translateAddress(otherTokenVaultAddress),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+      await sendAsUser([depositInstruction, initTokenVaultInstruction]);
+      await new Promise((res) => setTimeout(res, 2000));
+    });
+
+    test("Jupiter swap rejects the token vault of another order", async () => {
+      const route = await fetchSwapRoute(
+        usdcMint,
+        solMint,
+        depositAmount.toString(),
+        orderVaultAddress,
+        otherTokenVaultAddress
+      );
+      await expect(
+        sendAsUser([await swapInstruction(route, otherTokenVaultAddress)], route.lookupTableAccounts)
+      ).rejects.toThrow("IncorrectTokenVault");
+    });
+
+    test("Jupiter swap rejects a route through the vault of another order", async () => {
+      const route = await fetchSwapRoute(
+        usdcMint,
+        solMint,
+        depositAmount.toString(),
+        orderVaultAddress,
+        tokenVaultAddress
+      );
+      route.remainingAccounts.push({
+        pubkey: translateAddress(otherOrderVaultAddress),
+        isSigner: false,
+        isWritable: true,
+      });
+      await expect(
+        sendAsUser([await swapInstruction(route)], route.lookupTableAccounts)
+      ).rejects.toThrow("UnauthorizedVault");
+    });
+
+    test("Clear resting order fails without a resting order", async () => {
+      const clearInstruction = await program.methods
+        .clearRestingOrder()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(orderAddress),
+          manager: translateAddress(manager),
+          restingOrder: anchor.web3.PublicKey.default,
+        })
+        .instruction();
+      await expect(sendAsUser([clearInstruction])).rejects.toThrow(
+        "NoRestingOrder"
+      );
+    });
+
     test("Jupiter swap", async () => {
       const quoteResponse = await ky
         .get("https://api.jup.ag/swap/v1/quote", {
@@ -292,7 +730,10 @@ describe("jupiter operations", () => {
         manager: translateAddress(manager),
         managerVaultA: translateAddress(orderVaultAddress),
         managerVaultB: translateAddress(tokenVaultAddress),
-        jupiterProgram: translateAddress(JUPITER_PROGRAM),
+        mintPolicy: translateAddress(await getMintPolicyAddress(SOL_MINT)),
+        swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+        venuePrograms: translateAddress(await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM))),
+        swapProgram: translateAddress(JUPITER_PROGRAM),
       };
       const swapInstruction = await program.methods
         .jupSwap(serializedData)
@@ -322,6 +763,67 @@ describe("jupiter operations", () => {
       await new Promise((res) => setTimeout(res, 2000));
     });
 
+    async function liquidateInstruction(
+      legs: { data: Buffer; accountsLen: number }[],
+      remainingAccounts: anchor.web3.AccountMeta[]
+    ) {
+      return program.methods
+        .jupLiquidate(legs)
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          user: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(orderAddress),
+          manager: translateAddress(manager),
+          managerVaultA: translateAddress(tokenVaultAddress),
+          managerVaultB: translateAddress(orderVaultAddress),
+          tokenMint: translateAddress(SOL_MINT),
+          swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+          venuePrograms: translateAddress(await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM))),
+          swapProgram: translateAddress(JUPITER_PROGRAM),
+          tokenMintOracle: translateAddress(await getMintOracleAddress(SOL_MINT)),
+          tokenPriceFeed: null,
+          depositMintOracle: translateAddress(await getMintOracleAddress(USDC_MINT)),
+          depositPriceFeed: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(remainingAccounts)
+        .instruction();
+    }
+
+    test("Liquidate rejects an empty list of legs", async () => {
+      await expect(sendAsUser([await liquidateInstruction([], [])])).rejects.toThrow(
+        "InvalidRouteLegs"
+      );
+    });
+
+    test("Liquidate rejects legs not covering the route accounts", async () => {
+      const balance = await connection.getTokenAccountBalance(
+        translateAddress(tokenVaultAddress)
+      );
+      const route = await fetchSwapRoute(
+        solMint,
+        usdcMint,
+        balance.value.amount,
+        tokenVaultAddress,
+        orderVaultAddress
+      );
+      const instruction = await liquidateInstruction(
+        [{ data: route.data, accountsLen: route.remainingAccounts.length - 1 }],
+        route.remainingAccounts
+      );
+      await expect(
+        sendAsUser([instruction], route.lookupTableAccounts)
+      ).rejects.toThrow("InvalidRouteLegs");
+    });
+
+    test("Liquidate rejects more legs than allowed", async () => {
+      const legs = Array.from({ length: 4 }, () => ({ data: Buffer.alloc(8), accountsLen: 0 }));
+      await expect(sendAsUser([await liquidateInstruction(legs, [])])).rejects.toThrow(
+        "InvalidRouteLegs"
+      );
+    });
+
     test("Liquidate", async () => {
       const tokenVaultAccount = await connection.getTokenAccountBalance(
         new anchor.web3.PublicKey(tokenVaultAddress.toString())
@@ -382,7 +884,9 @@ describe("jupiter operations", () => {
         isWritable: account.isWritable,
       }));
       const liquidateInstruction = await program.methods
-        .jupLiquidate(liquidationSerializedData)
+        .jupLiquidate([
+          { data: liquidationSerializedData, accountsLen: liquidationRemainingAccounts.length },
+        ])
         .accountsPartial({
           signer: translateAddress(userAddress),
           user: translateAddress(userAddress),
@@ -391,7 +895,14 @@ describe("jupiter operations", () => {
           manager: translateAddress(manager),
           managerVaultA: translateAddress(tokenVaultAddress),
           managerVaultB: translateAddress(orderVaultAddress),
-          jupiterProgram: translateAddress(JUPITER_PROGRAM),
+          tokenMint: translateAddress(SOL_MINT),
+          swapVenue: translateAddress(await getSwapVenueAddress(JUPITER_PROGRAM)),
+          venuePrograms: translateAddress(await getVenueProgramsAddress(await getSwapVenueAddress(JUPITER_PROGRAM))),
+          swapProgram: translateAddress(JUPITER_PROGRAM),
+          tokenMintOracle: translateAddress(await getMintOracleAddress(SOL_MINT)),
+          tokenPriceFeed: null,
+          depositMintOracle: translateAddress(await getMintOracleAddress(USDC_MINT)),
+          depositPriceFeed: null,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
         .remainingAccounts(liquidationRemainingAccounts)
@@ -422,11 +933,12 @@ describe("jupiter operations", () => {
       );
       console.log("Transaction signature (liquidate):", liquidateSignature);
       await new Promise((res) => setTimeout(res, 2000));
+      // The token vault is closed once the final leg lands on the order vault
+      expect(await connection.getAccountInfo(translateAddress(tokenVaultAddress))).toBeNull();
     });
 
     test("Withdraw funds", async () => {
-      const performanceReceiver = address(SQUADS_PERFORMANCE_ADDRESS);
-      const feeVault = await getAtaAddress(performanceReceiver, usdcMint);
+      const feeVault = await getFeeVaultAddress(usdcMint);
 
       const withdrawInstruction = await program.methods
         .withdraw()
@@ -434,12 +946,12 @@ describe("jupiter operations", () => {
           signer: translateAddress(userAddress),
           id: translateAddress(orderId),
           order: translateAddress(orderAddress),
+          portfolio: null,
           manager: translateAddress(manager),
           config: translateAddress(config),
           depositMint: translateAddress(usdcMint),
           userAta: translateAddress(userUsdcAta),
           orderVault: translateAddress(orderVaultAddress),
-          performanceReceiver: translateAddress(performanceReceiver),
           feeVault: translateAddress(feeVault),
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM,
@@ -0,0 +1,246 @@
+import { describe, test, expect, beforeAll, afterAll } from "bun:test";
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN, translateAddress } from "@coral-xyz/anchor";
+import { DelegateVault } from "../target/types/delegate_vault";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM,
+  base64Encoder,
+  SOL_MINT,
+  transactionDecoder,
+  USDC_MINT,
+} from "../utils/solana/constants";
+import {
+  getOrderAddress,
+  getOrderVaultAddress,
+  getManagerAddress,
+  getAtaAddress,
+  getConfigAddress,
+  getFeeVaultAddress,
+  getMintPolicyAddress,
+  getTokenVaultAddress,
+} from "../utils/solana/pda";
+import { setMintPolicy, setupProgram } from "../utils/solana/setup";
+import {
+  generateKeyPair,
+  Address,
+  createKeyPairFromBytes,
+  getAddressFromPublicKey,
+  signTransaction,
+  getBase64EncodedWireTransaction,
+} from "@solana/kit";
+import { prepareTransaction } from "../utils/solana/transaction/prepare";
+import { confirmTransaction } from "../utils/solana/transaction/confirm";
+import { toInstruction } from "../utils/solana/transaction/instructions/toInstruction";
+import * as fs from "fs";
+import * as path from "path";
+import bs58 from "bs58";
+
+describe("mint policy", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.DelegateVault as Program<DelegateVault>;
+
+  const DEPOSIT_AMOUNT = 10_000; // 0.01 USDC
+
+  let user: CryptoKeyPair;
+  let userAddress: Address;
+  let userUsdcAta: Address;
+  let manager: Address;
+  let config: Address;
+  let mintPolicy: Address;
+  let orderId: Address;
+  let order: Address;
+  let orderVault: Address;
+
+  async function send(instruction: anchor.web3.TransactionInstruction) {
+    const transaction = await prepareTransaction(
+      [toInstruction(instruction)],
+      userAddress,
+      {}
+    );
+    const decoded = transactionDecoder.decode(base64Encoder.encode(transaction));
+    const signed = await signTransaction([user], decoded);
+    return confirmTransaction(getBase64EncodedWireTransaction(signed));
+  }
+
+  async function depositInstruction(amount: number) {
+    return program.methods
+      .deposit(new BN(amount))
+      .accountsPartial({
+        signer: translateAddress(userAddress),
+        id: translateAddress(orderId),
+        order: translateAddress(order),
+        manager: translateAddress(manager),
+        depositMint: translateAddress(USDC_MINT),
+        mintPolicy: translateAddress(mintPolicy),
+        userAta: translateAddress(userUsdcAta),
+        orderVault: translateAddress(orderVault),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .instruction();
+  }
+
+  async function tvl() {
+    const policy = await program.account.mintPolicy.fetch(
+      translateAddress(mintPolicy)
+    );
+    return policy.tvl.toNumber();
+  }
+
+  beforeAll(async () => {
+    await setupProgram(program);
+
+    const keysPath = path.join(__dirname, "..", "tests", "keys", "test-keys.json");
+    const keys = JSON.parse(fs.readFileSync(keysPath, "utf8"));
+    const userKeyPair = Keypair.fromSecretKey(bs58.decode(keys.user.secretKey));
+    user = await createKeyPairFromBytes(userKeyPair.secretKey);
+    userAddress = await getAddressFromPublicKey(user.publicKey);
+
+    config = await getConfigAddress();
+    manager = await getManagerAddress(userAddress);
+    userUsdcAta = await getAtaAddress(userAddress, USDC_MINT);
+    mintPolicy = await getMintPolicyAddress(USDC_MINT);
+
+    const orderIdKeyPair = await generateKeyPair();
+    orderId = await getAddressFromPublicKey(orderIdKeyPair.publicKey);
+    order = await getOrderAddress(manager, orderId);
+    orderVault = await getOrderVaultAddress(userAddress, manager, order, USDC_MINT);
+  });
+
+  afterAll(async () => {
+    await setupProgram(program);
+  });
+
+  test("Rejects deposits of a mint not allowed", async () => {
+    await setMintPolicy(program, USDC_MINT, {
+      allowDeposit: false,
+      allowTarget: true,
+      minDeposit: 0,
+      maxTvl: 0,
+    });
+    await expect(send(await depositInstruction(DEPOSIT_AMOUNT))).rejects.toThrow(
+      "MintNotAllowed"
+    );
+  });
+
+  test("Rejects deposits below the min deposit", async () => {
+    await setMintPolicy(program, USDC_MINT, {
+      allowDeposit: true,
+      allowTarget: true,
+      minDeposit: DEPOSIT_AMOUNT + 1,
+      maxTvl: 0,
+    });
+    await expect(send(await depositInstruction(DEPOSIT_AMOUNT))).rejects.toThrow(
+      "DepositTooSmall"
+    );
+  });
+
+  test("Rejects deposits above the max TVL", async () => {
+    await setMintPolicy(program, USDC_MINT, {
+      allowDeposit: true,
+      allowTarget: true,
+      minDeposit: 0,
+      maxTvl: (await tvl()) + DEPOSIT_AMOUNT - 1,
+    });
+    await expect(send(await depositInstruction(DEPOSIT_AMOUNT))).rejects.toThrow(
+      "MaxTvlExceeded"
+    );
+  });
+
+  test("Deposit up to the max TVL and withdraw releases it", async () => {
+    const tvlBefore = await tvl();
+    await setMintPolicy(program, USDC_MINT, {
+      allowDeposit: true,
+      allowTarget: true,
+      minDeposit: DEPOSIT_AMOUNT,
+      maxTvl: tvlBefore + DEPOSIT_AMOUNT,
+    });
+    await send(await depositInstruction(DEPOSIT_AMOUNT));
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    expect(await tvl()).toBe(tvlBefore + DEPOSIT_AMOUNT);
+    const fetched = await program.account.order.fetch(translateAddress(order));
+    expect(fetched.policyDeposit.toNumber()).toBe(DEPOSIT_AMOUNT);
+
+    const withdrawInstruction = await program.methods
+      .withdraw()
+      .accountsPartial({
+        signer: translateAddress(userAddress),
+        id: translateAddress(orderId),
+        order: translateAddress(order),
+        portfolio: null,
+        manager: translateAddress(manager),
+        config: translateAddress(config),
+        depositMint: translateAddress(USDC_MINT),
+        mintPolicy: translateAddress(mintPolicy),
+        userAta: translateAddress(userUsdcAta),
+        orderVault: translateAddress(orderVault),
+        feeVault: translateAddress(await getFeeVaultAddress(USDC_MINT)),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM,
+      })
+      .instruction();
+    await send(withdrawInstruction);
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    expect(await tvl()).toBe(tvlBefore);
+  });
+
+  describe("token vaults", () => {
+    async function setTargetPolicy(allowTarget: boolean) {
+      await setMintPolicy(program, SOL_MINT, {
+        allowDeposit: true,
+        allowTarget,
+        minDeposit: 0,
+        maxTvl: 0,
+      });
+    }
+
+    async function initTokenVaultInstruction() {
+      return program.methods
+        .initTokenVault()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+          mint: translateAddress(SOL_MINT),
+          mintPolicy: translateAddress(await getMintPolicyAddress(SOL_MINT)),
+          tokenVault: translateAddress(
+            await getTokenVaultAddress(userAddress, manager, order, SOL_MINT)
+          ),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+    }
+
+    // A new order as the previous one is withdrawn, the deposit tests leave a max TVL on USDC that needs room for its deposit
+    beforeAll(async () => {
+      await setupProgram(program);
+
+      const orderIdKeyPair = await generateKeyPair();
+      orderId = await getAddressFromPublicKey(orderIdKeyPair.publicKey);
+      order = await getOrderAddress(manager, orderId);
+      orderVault = await getOrderVaultAddress(userAddress, manager, order, USDC_MINT);
+      await send(await depositInstruction(DEPOSIT_AMOUNT));
+    });
+
+    test("Rejects token vaults of a mint not allowed as target", async () => {
+      await setTargetPolicy(false);
+      await expect(send(await initTokenVaultInstruction())).rejects.toThrow("MintNotAllowed");
+    });
+
+    test("Creates token vaults of a mint allowed as target", async () => {
+      await setTargetPolicy(true);
+      await send(await initTokenVaultInstruction());
+
+      const tokenVault = await getTokenVaultAddress(userAddress, manager, order, SOL_MINT);
+      expect(
+        await provider.connection.getAccountInfo(translateAddress(tokenVault))
+      ).not.toBeNull();
+    });
+  });
+});
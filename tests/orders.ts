@@ -0,0 +1,420 @@
+import { describe, test, expect, beforeAll } from "bun:test";
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN, translateAddress } from "@coral-xyz/anchor";
+import { DelegateVault } from "../target/types/delegate_vault";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM,
+  base64Encoder,
+  transactionDecoder,
+  SOL_MINT,
+  SOL_USD_PRICE_FEED,
+  USDC_MINT,
+  USDC_USD_PRICE_FEED,
+} from "../utils/solana/constants";
+import {
+  getOrderAddress,
+  getOrderVaultAddress,
+  getTokenVaultAddress,
+  getManagerAddress,
+  getAtaAddress,
+  getPortfolioAddress,
+  getConfigAddress,
+  getFeeVaultAddress,
+  getMintPolicyAddress,
+  getReferrerVaultAddress,
+  getFeeOverrideAddress,
+} from "../utils/solana/pda";
+import { setupProgram } from "../utils/solana/setup";
+import {
+  generateKeyPair,
+  Address,
+  address,
+  createKeyPairFromBytes,
+  getAddressFromPublicKey,
+  signTransaction,
+  getBase64EncodedWireTransaction,
+} from "@solana/kit";
+import { prepareTransaction } from "../utils/solana/transaction/prepare";
+import { confirmTransaction } from "../utils/solana/transaction/confirm";
+import { toInstruction } from "../utils/solana/transaction/instructions/toInstruction";
+import * as fs from "fs";
+import * as path from "path";
+import bs58 from "bs58";
+
+describe("order settings", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.DelegateVault as Program<DelegateVault>;
+
+  const DEPOSIT_AMOUNT = 10_000; // 0.01 USDC
+
+  let user: CryptoKeyPair;
+  let userAddress: Address;
+  let userUsdcAta: Address;
+  let manager: Address;
+  let orderId: Address;
+  let order: Address;
+  let orderVault: Address;
+
+  async function send(instruction: anchor.web3.TransactionInstruction) {
+    const transaction = await prepareTransaction(
+      [toInstruction(instruction)],
+      userAddress,
+      {}
+    );
+    const decoded = transactionDecoder.decode(base64Encoder.encode(transaction));
+    const signed = await signTransaction([user], decoded);
+    return confirmTransaction(getBase64EncodedWireTransaction(signed));
+  }
+
+  async function fetchOrder() {
+    return program.account.order.fetch(translateAddress(order));
+  }
+
+  beforeAll(async () => {
+    await setupProgram(program);
+
+    const keysPath = path.join(__dirname, "..", "tests", "keys", "test-keys.json");
+    const keys = JSON.parse(fs.readFileSync(keysPath, "utf8"));
+    const userKeyPair = Keypair.fromSecretKey(bs58.decode(keys.user.secretKey));
+    user = await createKeyPairFromBytes(userKeyPair.secretKey);
+    userAddress = await getAddressFromPublicKey(user.publicKey);
+
+    manager = await getManagerAddress(userAddress);
+    userUsdcAta = await getAtaAddress(userAddress, USDC_MINT);
+
+    const orderIdKeyPair = await generateKeyPair();
+    orderId = await getAddressFromPublicKey(orderIdKeyPair.publicKey);
+    order = await getOrderAddress(manager, orderId);
+    orderVault = await getOrderVaultAddress(userAddress, manager, order, USDC_MINT);
+
+    const depositInstruction = await program.methods
+      .deposit(new BN(DEPOSIT_AMOUNT))
+      .accountsPartial({
+        signer: translateAddress(userAddress),
+        id: translateAddress(orderId),
+        order: translateAddress(order),
+        manager: translateAddress(manager),
+        depositMint: translateAddress(USDC_MINT),
+        userAta: translateAddress(userUsdcAta),
+        orderVault: translateAddress(orderVault),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .instruction();
+    await send(depositInstruction);
+  });
+
+  describe("chunked liquidation", () => {
+    async function setChunkedLiquidation(maxAmount: number, interval: number) {
+      const instruction = await program.methods
+        .setChunkedLiquidation(new BN(maxAmount), new BN(interval))
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+        })
+        .instruction();
+      return send(instruction);
+    }
+
+    test("Sets the chunk size and interval", async () => {
+      await setChunkedLiquidation(5_000, 60);
+
+      const fetched = await fetchOrder();
+      expect(fetched.chunkMaxAmount.toNumber()).toBe(5_000);
+      expect(fetched.chunkInterval.toNumber()).toBe(60);
+    });
+
+    test("Rejects a negative interval", async () => {
+      await expect(setChunkedLiquidation(5_000, -1)).rejects.toThrow(
+        "InvalidChunkConfig"
+      );
+    });
+
+    test("Rejects an interval without chunk size", async () => {
+      await expect(setChunkedLiquidation(0, 60)).rejects.toThrow(
+        "InvalidChunkConfig"
+      );
+    });
+
+    test("Disables chunked liquidation", async () => {
+      await setChunkedLiquidation(0, 0);
+
+      const fetched = await fetchOrder();
+      expect(fetched.chunkMaxAmount.toNumber()).toBe(0);
+    });
+  });
+
+  describe("bracket", () => {
+    async function setBracket(
+      stopLossPrice: number,
+      takeProfitPrice: number,
+      expiresAt: number
+    ) {
+      const instruction = await program.methods
+        .setBracket({
+          stopLossPrice: new BN(stopLossPrice),
+          takeProfitPrice: new BN(takeProfitPrice),
+          expiresAt: new BN(expiresAt),
+        })
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+        })
+        .instruction();
+      return send(instruction);
+    }
+
+    test("Arms both legs", async () => {
+      const expiresAt = Math.floor(Date.now() / 1000) + 3600;
+      await setBracket(100, 200, expiresAt);
+
+      const fetched = await fetchOrder();
+      expect(fetched.bracket.stopLossPrice.toNumber()).toBe(100);
+      expect(fetched.bracket.takeProfitPrice.toNumber()).toBe(200);
+      expect(fetched.bracket.expiresAt.toNumber()).toBe(expiresAt);
+    });
+
+    test("Rejects a stop loss above the take profit", async () => {
+      await expect(setBracket(200, 100, 0)).rejects.toThrow("InvalidBracket");
+    });
+
+    test("Rejects an expiry in the past", async () => {
+      const expiresAt = Math.floor(Date.now() / 1000) - 3600;
+      await expect(setBracket(100, 200, expiresAt)).rejects.toThrow(
+        "InvalidBracket"
+      );
+    });
+
+    test("Removes the bracket", async () => {
+      await setBracket(0, 0, 0);
+
+      const fetched = await fetchOrder();
+      expect(fetched.bracket.stopLossPrice.toNumber()).toBe(0);
+      expect(fetched.bracket.takeProfitPrice.toNumber()).toBe(0);
+    });
+  });
+
+  describe("grid", () => {
+    async function setGrid(
+      lowerPrice: number,
+      upperPrice: number,
+      count: number,
+      levelSize: number
+    ) {
+      const instruction = await program.methods
+        .setGrid({
+          lowerPrice: new BN(lowerPrice),
+          upperPrice: new BN(upperPrice),
+          count,
+          levelSize: new BN(levelSize),
+        })
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+        })
+        .instruction();
+      return send(instruction);
+    }
+
+    test("Sets the grid bounds", async () => {
+      await setGrid(100, 200, 5, 1_000);
+
+      const fetched = await fetchOrder();
+      expect(fetched.grid.lowerPrice.toNumber()).toBe(100);
+      expect(fetched.grid.upperPrice.toNumber()).toBe(200);
+      expect(fetched.grid.count).toBe(5);
+      expect(fetched.grid.levelSize.toNumber()).toBe(1_000);
+      expect(fetched.grid.filled.toNumber()).toBe(0);
+    });
+
+    test("Rejects a single level", async () => {
+      await expect(setGrid(100, 200, 1, 1_000)).rejects.toThrow("InvalidGrid");
+    });
+
+    test("Rejects a lower price above the upper price", async () => {
+      await expect(setGrid(200, 100, 5, 1_000)).rejects.toThrow("InvalidGrid");
+    });
+
+    test("Rejects an empty level size", async () => {
+      await expect(setGrid(100, 200, 5, 0)).rejects.toThrow("InvalidGrid");
+    });
+
+    test("Disables the grid", async () => {
+      await setGrid(0, 0, 0, 0);
+
+      const fetched = await fetchOrder();
+      expect(fetched.grid.count).toBe(0);
+    });
+  });
+
+  describe("portfolio", () => {
+    let tokenVault: Address;
+    let portfolio: Address;
+
+    async function initPortfolio(targetWeights: number[], vaults: Address[]) {
+      const mints = [USDC_MINT, SOL_MINT];
+      const priceFeeds = [USDC_USD_PRICE_FEED, SOL_USD_PRICE_FEED];
+      const instruction = await program.methods
+        .initPortfolio(targetWeights)
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+          portfolio: translateAddress(portfolio),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts(
+          vaults.flatMap((vault, index) => [
+            { pubkey: translateAddress(vault), isSigner: false, isWritable: false },
+            { pubkey: translateAddress(mints[index]), isSigner: false, isWritable: false },
+            { pubkey: translateAddress(priceFeeds[index]), isSigner: false, isWritable: false },
+          ])
+        )
+        .instruction();
+      return send(instruction);
+    }
+
+    beforeAll(async () => {
+      tokenVault = await getTokenVaultAddress(userAddress, manager, order, SOL_MINT);
+      portfolio = await getPortfolioAddress(order);
+
+      const initTokenVaultInstruction = await program.methods
+        .initTokenVault()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+          mint: translateAddress(SOL_MINT),
+          tokenVault: translateAddress(tokenVault),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+      await send(initTokenVaultInstruction);
+    });
+
+    test("Rejects weights not adding up to 100%", async () => {
+      await expect(
+        initPortfolio([5_000, 4_000], [orderVault, tokenVault])
+      ).rejects.toThrow("InvalidPortfolio");
+    });
+
+    test("Rejects vaults not matching the mints", async () => {
+      await expect(
+        initPortfolio([5_000, 5_000], [tokenVault, orderVault])
+      ).rejects.toThrow("IncorrectMint");
+    });
+
+    test("Creates the portfolio with the target weights", async () => {
+      await initPortfolio([6_000, 4_000], [orderVault, tokenVault]);
+
+      const fetched = await program.account.portfolio.fetch(
+        translateAddress(portfolio)
+      );
+      expect(fetched.assets.map((asset) => asset.targetWeight)).toEqual([
+        6_000, 4_000,
+      ]);
+      expect(fetched.assets.map((asset) => asset.vault.toBase58())).toEqual([
+        orderVault,
+        tokenVault,
+      ]);
+    });
+  });
+
+  describe("migration", () => {
+    test("Rejects an order already in the current layout", async () => {
+      const instruction = await program.methods
+        .migrateOrder()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          manager: translateAddress(manager),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+      await expect(send(instruction)).rejects.toThrow("AccountAlreadyMigrated");
+    });
+
+    test("Rejects a manager already in the current layout", async () => {
+      await expect(
+        program.methods
+          .migrateManager({ subscriptionPerformanceFee: 0 })
+          .accountsPartial({
+            signer: provider.wallet.publicKey,
+            config: translateAddress(await getConfigAddress()),
+            user: translateAddress(userAddress),
+            manager: translateAddress(manager),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc({ commitment: "confirmed" })
+      ).rejects.toThrow("AccountAlreadyMigrated");
+    });
+
+    test("Rejects a config already in the current layout", async () => {
+      await expect(
+        program.methods
+          .migrateConfig()
+          .accountsPartial({
+            signer: provider.wallet.publicKey,
+            config: translateAddress(await getConfigAddress()),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc({ commitment: "confirmed" })
+      ).rejects.toThrow("AccountAlreadyMigrated");
+    });
+  });
+
+  describe("withdrawal", () => {
+    test("Closes the portfolio with the order", async () => {
+      const portfolio = await getPortfolioAddress(order);
+      const { referrer } = await program.account.manager.fetch(translateAddress(manager));
+
+      const instruction = await program.methods
+        .withdraw()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          portfolio: translateAddress(portfolio),
+          manager: translateAddress(manager),
+          config: translateAddress(await getConfigAddress()),
+          orderVault: translateAddress(orderVault),
+          userAta: translateAddress(userUsdcAta),
+          feeVault: translateAddress(await getFeeVaultAddress(USDC_MINT)),
+          feeSchedule: null,
+          depositMint: translateAddress(USDC_MINT),
+          mintPolicy: translateAddress(await getMintPolicyAddress(USDC_MINT)),
+          highWaterMark: null,
+          referrerVault: translateAddress(
+            await getReferrerVaultAddress(address(referrer.toBase58()), USDC_MINT)
+          ),
+          mintFeeOverride: translateAddress(await getFeeOverrideAddress("mint", USDC_MINT)),
+          managerFeeOverride: translateAddress(await getFeeOverrideAddress("manager", manager)),
+          discountConfig: null,
+          stake: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM,
+        })
+        .instruction();
+      await send(instruction);
+
+      expect(
+        await provider.connection.getAccountInfo(translateAddress(portfolio))
+      ).toBeNull();
+      expect(await provider.connection.getAccountInfo(translateAddress(order))).toBeNull();
+    });
+  });
+});
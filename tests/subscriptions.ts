@@ -0,0 +1,701 @@
+import { describe, test, expect, beforeAll, afterAll } from "bun:test";
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN, translateAddress } from "@coral-xyz/anchor";
+import { DelegateVault } from "../target/types/delegate_vault";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM,
+  base64Encoder,
+  transactionDecoder,
+  SOL_MINT,
+  USDC_MINT,
+} from "../utils/solana/constants";
+import {
+  getManagerAddress,
+  getAtaAddress,
+  getConfigAddress,
+  getFeeOverrideAddress,
+  getFeeVaultAddress,
+  getMintPolicyAddress,
+  getOrderAddress,
+  getOrderVaultAddress,
+  getPlanAddress,
+  getPlanPriceAddress,
+  getReferrerVaultAddress,
+  getRefundReserveAddress,
+  getVoucherRedemptionAddress,
+} from "../utils/solana/pda";
+import {
+  Address,
+  address,
+  createKeyPairFromBytes,
+  generateKeyPair,
+  getAddressFromPublicKey,
+  signTransaction,
+  getBase64EncodedWireTransaction,
+} from "@solana/kit";
+import { prepareTransaction } from "../utils/solana/transaction/prepare";
+import { confirmTransaction } from "../utils/solana/transaction/confirm";
+import { toInstruction } from "../utils/solana/transaction/instructions/toInstruction";
+import * as fs from "fs";
+import * as path from "path";
+import bs58 from "bs58";
+
+describe("subscriptions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.DelegateVault as Program<DelegateVault>;
+  const admin = provider.wallet.publicKey;
+
+  // Plans of the tests, all with the same fee so an active subscription can be extended with any of them
+  const PLAN_ID = 1_000;
+  const INACTIVE_PLAN_ID = 1_001;
+  const SHORT_PLAN_ID = 1_002; // shorter than the 3 days renewal window
+  const PLAN_PRICE = 1_000; // 0.001 USDC
+  const PLAN_DURATION = 3 * 24 * 60 * 60 + 30;
+  const PLAN_PERFORMANCE_FEE = 500;
+
+  let user: CryptoKeyPair;
+  let userAddress: Address;
+  let delegate: CryptoKeyPair;
+  let delegateAddress: Address;
+  let userUsdcAta: Address;
+  let manager: Address;
+  let config: Address;
+  let feeVault: Address;
+
+  async function send(
+    instructions: anchor.web3.TransactionInstruction[],
+    signer: CryptoKeyPair = user
+  ) {
+    const transaction = await prepareTransaction(
+      instructions.map(toInstruction),
+      await getAddressFromPublicKey(signer.publicKey),
+      {}
+    );
+    const decoded = transactionDecoder.decode(base64Encoder.encode(transaction));
+    const signed = await signTransaction([signer], decoded);
+    return confirmTransaction(getBase64EncodedWireTransaction(signed));
+  }
+
+  async function setPlan(
+    id: number,
+    params: { price: number; duration: number; performanceFee: number; active: boolean }
+  ) {
+    const plan = translateAddress(await getPlanAddress(id));
+    const args = {
+      id,
+      price: new BN(params.price),
+      duration: new BN(params.duration),
+      performanceFee: params.performanceFee,
+      active: params.active,
+    };
+    const existing = await program.account.plan.fetchNullable(plan);
+    const method = existing ? program.methods.editPlan(args) : program.methods.initPlan(args);
+    return method
+      .accountsPartial({
+        signer: admin,
+        config: translateAddress(config),
+        plan,
+        paymentMint: translateAddress(USDC_MINT),
+      })
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function fetchManager(managerAddress: Address = manager) {
+    return program.account.manager.fetch(translateAddress(managerAddress));
+  }
+
+  // Referrer vault of the payment mint, always passed even while the manager has no referrer
+  async function referrerVault(managerAddress: Address = manager) {
+    const fetched = await fetchManager(managerAddress);
+    return translateAddress(
+      await getReferrerVaultAddress(address(fetched.referrer.toBase58()), USDC_MINT)
+    );
+  }
+
+  // Refund reserve of the mint of the last payment of the manager, released when the payment stops being refundable
+  async function previousRefundReserve(managerAddress: Address = manager) {
+    const fetched = await fetchManager(managerAddress);
+    return translateAddress(
+      await getRefundReserveAddress(address(fetched.refundMint.toBase58()))
+    );
+  }
+
+  async function initRefundReserve(mint: Address) {
+    const refundReserve = translateAddress(await getRefundReserveAddress(mint));
+    if (await program.account.refundReserve.fetchNullable(refundReserve)) {
+      return;
+    }
+    return program.methods
+      .initRefundReserve()
+      .accountsPartial({
+        signer: admin,
+        refundReserve,
+        mint: translateAddress(mint),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function fetchRefundReserve(mint: Address) {
+    return program.account.refundReserve.fetch(
+      translateAddress(await getRefundReserveAddress(mint))
+    );
+  }
+
+  // Accounts of a token payment of the user for the subscription of the manager
+  async function paymentAccounts(managerAddress: Address, planId: number) {
+    return {
+      signer: translateAddress(userAddress),
+      manager: translateAddress(managerAddress),
+      config: translateAddress(config),
+      plan: translateAddress(await getPlanAddress(planId)),
+      planPrice: null,
+      paymentMint: translateAddress(USDC_MINT),
+      feeVault: translateAddress(feeVault),
+      feeSchedule: null,
+      referrerVault: await referrerVault(managerAddress),
+      refundReserve: translateAddress(await getRefundReserveAddress(USDC_MINT)),
+      previousRefundReserve: await previousRefundReserve(managerAddress),
+      signerAta: translateAddress(userUsdcAta),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+  }
+
+  async function paySubscription(planId: number) {
+    const instruction = await program.methods
+      .paySubscription()
+      .accountsPartial(await paymentAccounts(manager, planId))
+      .instruction();
+    return send([instruction]);
+  }
+
+  // Cancels the subscription of the manager of the user, refunded in the mint of its last payment
+  async function cancelSubscriptionInstruction(signer: anchor.web3.PublicKey) {
+    const fetched = await fetchManager();
+    const paidInToken = !fetched.refundMint.equals(anchor.web3.PublicKey.default);
+    const refundMint = address(fetched.refundMint.toBase58());
+
+    return program.methods
+      .cancelSubscription()
+      .accountsPartial({
+        signer,
+        manager: translateAddress(manager),
+        config: translateAddress(config),
+        authority: translateAddress(userAddress),
+        refundReserve: translateAddress(await getRefundReserveAddress(refundMint)),
+        refundMint: paidInToken ? translateAddress(refundMint) : null,
+        feeVault: paidInToken ? translateAddress(await getFeeVaultAddress(refundMint)) : null,
+        authorityAta: paidInToken
+          ? translateAddress(await getAtaAddress(userAddress, refundMint))
+          : null,
+        tokenProgram: paidInToken ? TOKEN_PROGRAM_ID : null,
+      })
+      .instruction();
+  }
+
+  async function cancelSubscription(signer: CryptoKeyPair = user) {
+    const instruction = await cancelSubscriptionInstruction(
+      translateAddress(await getAddressFromPublicKey(signer.publicKey))
+    );
+    return send([instruction], signer);
+  }
+
+  async function cancelIfSubscribed() {
+    const fetched = await fetchManager();
+    if (fetched.endSubscription.toNumber() > Math.floor(Date.now() / 1000)) {
+      await cancelSubscription();
+    }
+  }
+
+  beforeAll(async () => {
+    const keysPath = path.join(__dirname, "..", "tests", "keys", "test-keys.json");
+    const keys = JSON.parse(fs.readFileSync(keysPath, "utf8"));
+    const userKeyPair = Keypair.fromSecretKey(bs58.decode(keys.user.secretKey));
+    user = await createKeyPairFromBytes(userKeyPair.secretKey);
+    userAddress = await getAddressFromPublicKey(user.publicKey);
+    const delegateKeyPair = Keypair.fromSecretKey(bs58.decode(keys.delegate.secretKey));
+    delegate = await createKeyPairFromBytes(delegateKeyPair.secretKey);
+    delegateAddress = await getAddressFromPublicKey(delegate.publicKey);
+
+    config = await getConfigAddress();
+    manager = await getManagerAddress(userAddress);
+    userUsdcAta = await getAtaAddress(userAddress, USDC_MINT);
+    feeVault = await getFeeVaultAddress(USDC_MINT);
+    await initRefundReserve(USDC_MINT);
+    await initRefundReserve(address(anchor.web3.SystemProgram.programId.toBase58()));
+    await cancelIfSubscribed();
+
+    await setPlan(PLAN_ID, {
+      price: PLAN_PRICE,
+      duration: PLAN_DURATION,
+      performanceFee: PLAN_PERFORMANCE_FEE,
+      active: true,
+    });
+    await setPlan(INACTIVE_PLAN_ID, {
+      price: PLAN_PRICE,
+      duration: PLAN_DURATION,
+      performanceFee: PLAN_PERFORMANCE_FEE,
+      active: false,
+    });
+    await setPlan(SHORT_PLAN_ID, {
+      price: PLAN_PRICE,
+      duration: 60,
+      performanceFee: PLAN_PERFORMANCE_FEE,
+      active: true,
+    });
+  });
+
+  afterAll(async () => {
+    await cancelIfSubscribed();
+  });
+
+  describe("plans", () => {
+    test("Rejects a plan without duration", async () => {
+      await expect(
+        setPlan(PLAN_ID, {
+          price: PLAN_PRICE,
+          duration: 0,
+          performanceFee: PLAN_PERFORMANCE_FEE,
+          active: true,
+        })
+      ).rejects.toThrow("InvalidPlan");
+    });
+
+    test("Rejects a plan fee above 100%", async () => {
+      await expect(
+        setPlan(PLAN_ID, {
+          price: PLAN_PRICE,
+          duration: PLAN_DURATION,
+          performanceFee: 10_001,
+          active: true,
+        })
+      ).rejects.toThrow("IncorrectFee");
+    });
+
+    test("Rejects payments of an inactive plan", async () => {
+      await expect(paySubscription(INACTIVE_PLAN_ID)).rejects.toThrow("InactivePlan");
+    });
+
+    test("Subscribes the manager for the plan duration and fee", async () => {
+      const paidAt = Math.floor(Date.now() / 1000);
+      await paySubscription(PLAN_ID);
+
+      const after = await fetchManager();
+      expect(after.endSubscription.toNumber()).toBeGreaterThanOrEqual(
+        paidAt + PLAN_DURATION - 60
+      );
+      expect(after.subscriptionPerformanceFee).toBe(PLAN_PERFORMANCE_FEE);
+    });
+  });
+
+  describe("payment mints", () => {
+    const SOL_PRICE = 10_000; // lamports
+
+    let planPrice: Address;
+
+    async function setSolPrice(price: number, active: boolean) {
+      const plan = await getPlanAddress(PLAN_ID);
+      const args = { price: new BN(price), active };
+      const existing = await program.account.planPrice.fetchNullable(
+        translateAddress(planPrice)
+      );
+      const method = existing
+        ? program.methods.editPlanPrice(args).accountsPartial({
+            signer: admin,
+            config: translateAddress(config),
+            planPrice: translateAddress(planPrice),
+          })
+        : program.methods.initPlanPrice(args).accountsPartial({
+            signer: admin,
+            config: translateAddress(config),
+            plan: translateAddress(plan),
+            planPrice: translateAddress(planPrice),
+            mint: translateAddress(SOL_MINT),
+          });
+      return method.rpc({ commitment: "confirmed" });
+    }
+
+    async function paySubscriptionSol() {
+      const fetched = await fetchManager();
+      const hasReferrer = !fetched.referrer.equals(anchor.web3.PublicKey.default);
+      const referrer = hasReferrer
+        ? await program.account.referrer.fetch(fetched.referrer)
+        : null;
+
+      const instruction = await program.methods
+        .paySubscriptionSol()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          manager: translateAddress(manager),
+          config: translateAddress(config),
+          plan: translateAddress(await getPlanAddress(PLAN_ID)),
+          planPrice: translateAddress(planPrice),
+          feeSchedule: null,
+          referrer: hasReferrer ? fetched.referrer : null,
+          referrerWallet: referrer ? referrer.wallet : null,
+          refundReserve: translateAddress(
+            await getRefundReserveAddress(address(anchor.web3.SystemProgram.programId.toBase58()))
+          ),
+          previousRefundReserve: await previousRefundReserve(),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+      return send([instruction]);
+    }
+
+    beforeAll(async () => {
+      planPrice = await getPlanPriceAddress(await getPlanAddress(PLAN_ID), SOL_MINT);
+    });
+
+    test("Rejects a plan price of 0", async () => {
+      await expect(setSolPrice(0, true)).rejects.toThrow("InvalidPlan");
+    });
+
+    test("Rejects payments in SOL while the price is inactive", async () => {
+      await setSolPrice(SOL_PRICE, false);
+      await expect(paySubscriptionSol()).rejects.toThrow("InactivePlan");
+    });
+
+    test("Extends the subscription with a payment in SOL", async () => {
+      await setSolPrice(SOL_PRICE, true);
+      const before = await fetchManager();
+
+      await paySubscriptionSol();
+
+      // Still subscribed from the plan payment, the period is added at the end of the subscription
+      const after = await fetchManager();
+      expect(after.endSubscription.toNumber()).toBe(
+        before.endSubscription.toNumber() + PLAN_DURATION
+      );
+    });
+  });
+
+  describe("gifts", () => {
+    let giftedManager: Address;
+
+    async function giftSubscription(planId: number) {
+      const instruction = await program.methods
+        .giftSubscription()
+        .accountsPartial(await paymentAccounts(giftedManager, planId))
+        .instruction();
+      return send([instruction]);
+    }
+
+    beforeAll(async () => {
+      giftedManager = await getManagerAddress(delegateAddress);
+      if (!(await provider.connection.getAccountInfo(translateAddress(giftedManager)))) {
+        const instruction = await program.methods
+          .initManager()
+          .accountsPartial({
+            signer: translateAddress(delegateAddress),
+            delegate: translateAddress(userAddress),
+            manager: translateAddress(giftedManager),
+            referrer: null,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .instruction();
+        await send([instruction], delegate);
+      }
+    });
+
+    test("Rejects gifts of an inactive plan", async () => {
+      await expect(giftSubscription(INACTIVE_PLAN_ID)).rejects.toThrow("InactivePlan");
+    });
+
+    test("Extends the subscription of another manager", async () => {
+      const giftedBefore = await fetchManager(giftedManager);
+      const payerBefore = await fetchManager();
+
+      await giftSubscription(PLAN_ID);
+
+      const gifted = await fetchManager(giftedManager);
+      expect(gifted.endSubscription.toNumber()).toBeGreaterThanOrEqual(
+        giftedBefore.endSubscription.toNumber() + PLAN_DURATION
+      );
+      expect(gifted.subscriptionPerformanceFee).toBe(PLAN_PERFORMANCE_FEE);
+
+      const payer = await fetchManager();
+      expect(payer.endSubscription.toNumber()).toBe(payerBefore.endSubscription.toNumber());
+    });
+  });
+
+  describe("auto renewal", () => {
+    const ALLOWANCE = 10 * PLAN_PRICE;
+
+    async function setAutoRenew(planId: number | null) {
+      const instruction = await program.methods
+        .setAutoRenew(new BN(planId === null ? 0 : ALLOWANCE))
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          manager: translateAddress(manager),
+          config: translateAddress(config),
+          plan: planId === null ? null : translateAddress(await getPlanAddress(planId)),
+          paymentMint: translateAddress(USDC_MINT),
+          signerAta: translateAddress(userUsdcAta),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .instruction();
+      return send([instruction]);
+    }
+
+    async function renewSubscription() {
+      const accounts = await paymentAccounts(manager, PLAN_ID);
+      const instruction = await program.methods
+        .renewSubscription()
+        .accountsPartial({
+          signer: translateAddress(delegateAddress),
+          manager: accounts.manager,
+          config: accounts.config,
+          plan: accounts.plan,
+          planPrice: null,
+          paymentMint: accounts.paymentMint,
+          feeVault: accounts.feeVault,
+          feeSchedule: null,
+          referrerVault: accounts.referrerVault,
+          refundReserve: accounts.refundReserve,
+          previousRefundReserve: accounts.previousRefundReserve,
+          authorityAta: translateAddress(userUsdcAta),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .instruction();
+      return send([instruction], delegate);
+    }
+
+    test("Rejects auto renewal of plans shorter than the renewal window", async () => {
+      await expect(setAutoRenew(SHORT_PLAN_ID)).rejects.toThrow("InvalidPlan");
+    });
+
+    test("Approves the config to renew the plan", async () => {
+      await setAutoRenew(PLAN_ID);
+
+      const fetched = await fetchManager();
+      expect(fetched.autoRenewPlan.toBase58()).toBe(await getPlanAddress(PLAN_ID));
+      const ata = await getAccount(provider.connection, translateAddress(userUsdcAta));
+      expect(ata.delegate?.toBase58()).toBe(config);
+      expect(Number(ata.delegatedAmount)).toBe(ALLOWANCE);
+    });
+
+    test("Rejects renewals before the renewal window", async () => {
+      await expect(renewSubscription()).rejects.toThrow("RenewalNotDue");
+    });
+
+    test("Disables the auto renewal and revokes the approval", async () => {
+      await setAutoRenew(null);
+
+      const fetched = await fetchManager();
+      expect(fetched.autoRenewPlan.equals(anchor.web3.PublicKey.default)).toBe(true);
+      const ata = await getAccount(provider.connection, translateAddress(userUsdcAta));
+      expect(ata.delegate).toBeNull();
+      await expect(renewSubscription()).rejects.toThrow("AutoRenewDisabled");
+    });
+  });
+
+  describe("vouchers", () => {
+    const TRIAL_DURATION = 3600;
+
+    const promoSigner = Keypair.generate();
+
+    async function editPromoSigner(promoSignerKey: anchor.web3.PublicKey) {
+      return program.methods
+        .editConfig({ promoSigner: promoSignerKey } as Parameters<typeof program.methods.editConfig>[0])
+        .accountsPartial({
+          signer: admin,
+          config: translateAddress(config),
+        })
+        .rpc({ commitment: "confirmed" });
+    }
+
+    async function voucher(expiresAt: number) {
+      return {
+        nonce: new BN(Date.now()),
+        manager: translateAddress(manager),
+        plan: translateAddress(await getPlanAddress(PLAN_ID)),
+        duration: new BN(TRIAL_DURATION),
+        discount: 10_000,
+        expiresAt: new BN(expiresAt),
+      };
+    }
+
+    // Redeems the voucher signed by the signer in an ed25519 instruction placed right before redeem_voucher
+    async function redeemVoucher(redeemed: Awaited<ReturnType<typeof voucher>>, signer: Keypair) {
+      const signature = anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: program.coder.types.encode("voucher", redeemed),
+      });
+      const accounts = await paymentAccounts(manager, PLAN_ID);
+      const instruction = await program.methods
+        .redeemVoucher(redeemed)
+        .accountsPartial({
+          ...accounts,
+          redemption: translateAddress(
+            await getVoucherRedemptionAddress(BigInt(redeemed.nonce.toString()))
+          ),
+          signerAta: null,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+      return send([signature, instruction]);
+    }
+
+    beforeAll(async () => {
+      await editPromoSigner(promoSigner.publicKey);
+    });
+
+    afterAll(async () => {
+      await editPromoSigner(anchor.web3.PublicKey.default);
+    });
+
+    test("Rejects vouchers not signed by the promo signer", async () => {
+      const redeemed = await voucher(Math.floor(Date.now() / 1000) + 3600);
+      await expect(redeemVoucher(redeemed, Keypair.generate())).rejects.toThrow(
+        "InvalidVoucherSignature"
+      );
+    });
+
+    test("Rejects expired vouchers", async () => {
+      const redeemed = await voucher(Math.floor(Date.now() / 1000) - 3600);
+      await expect(redeemVoucher(redeemed, promoSigner)).rejects.toThrow("VoucherExpired");
+    });
+
+    test("Redeems a free trial voucher only once", async () => {
+      const redeemed = await voucher(Math.floor(Date.now() / 1000) + 3600);
+      const before = await fetchManager();
+
+      await redeemVoucher(redeemed, promoSigner);
+
+      const after = await fetchManager();
+      expect(after.endSubscription.toNumber()).toBe(
+        before.endSubscription.toNumber() + TRIAL_DURATION
+      );
+      const redemption = await program.account.voucherRedemption.fetch(
+        translateAddress(await getVoucherRedemptionAddress(BigInt(redeemed.nonce.toString())))
+      );
+      expect(redemption.manager.toBase58()).toBe(manager);
+
+      // The redemption account of the nonce already exists
+      await expect(redeemVoucher(redeemed, promoSigner)).rejects.toThrow();
+    });
+  });
+
+  describe("cancellation", () => {
+    const DEPOSIT_AMOUNT = 10_000; // 0.01 USDC
+
+    // Opens a new order with a deposit of the user, returns its id, address and vault
+    async function deposit(amount: number) {
+      const orderIdKeyPair = await generateKeyPair();
+      const orderId = await getAddressFromPublicKey(orderIdKeyPair.publicKey);
+      const order = await getOrderAddress(manager, orderId);
+      const orderVault = await getOrderVaultAddress(userAddress, manager, order, USDC_MINT);
+
+      const instruction = await program.methods
+        .deposit(new BN(amount))
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          manager: translateAddress(manager),
+          depositMint: translateAddress(USDC_MINT),
+          userAta: translateAddress(userUsdcAta),
+          orderVault: translateAddress(orderVault),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .instruction();
+      await send([instruction]);
+
+      return { orderId, order, orderVault };
+    }
+
+    async function withdrawInstruction(orderId: Address, order: Address, orderVault: Address) {
+      const fetched = await fetchManager();
+
+      return program.methods
+        .withdraw()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          id: translateAddress(orderId),
+          order: translateAddress(order),
+          portfolio: null,
+          manager: translateAddress(manager),
+          config: translateAddress(config),
+          orderVault: translateAddress(orderVault),
+          userAta: translateAddress(userUsdcAta),
+          feeVault: translateAddress(feeVault),
+          feeSchedule: null,
+          depositMint: translateAddress(USDC_MINT),
+          mintPolicy: translateAddress(await getMintPolicyAddress(USDC_MINT)),
+          highWaterMark: null,
+          referrerVault: translateAddress(
+            await getReferrerVaultAddress(address(fetched.referrer.toBase58()), USDC_MINT)
+          ),
+          mintFeeOverride: translateAddress(await getFeeOverrideAddress("mint", USDC_MINT)),
+          managerFeeOverride: translateAddress(await getFeeOverrideAddress("manager", manager)),
+          discountConfig: null,
+          stake: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM,
+        })
+        .instruction();
+    }
+
+    test("Rejects cancellations of other signers", async () => {
+      await expect(cancelSubscription(delegate)).rejects.toThrow("IncorrectSigner");
+    });
+
+    test("Cancels the subscription and clears the refundable payment", async () => {
+      const before = await fetchManager();
+      expect(before.refundableAmount.toNumber()).toBeGreaterThan(0);
+      const refundMint = address(before.refundMint.toBase58());
+      const reserveBefore = await fetchRefundReserve(refundMint);
+
+      await cancelSubscription();
+
+      const after = await fetchManager();
+      expect(after.endSubscription.toNumber()).toBeLessThanOrEqual(
+        Math.floor(Date.now() / 1000)
+      );
+      expect(after.refundableAmount.toNumber()).toBe(0);
+      const reserveAfter = await fetchRefundReserve(refundMint);
+      expect(reserveAfter.amount.toNumber()).toBe(
+        reserveBefore.amount.toNumber() - before.refundableAmount.toNumber()
+      );
+    });
+
+    test("Rejects cancelling without a subscription", async () => {
+      await expect(cancelSubscription()).rejects.toThrow("NotSubscribed");
+    });
+
+    test("Reserves the refundable payment from the fee claims", async () => {
+      const before = await fetchRefundReserve(USDC_MINT);
+
+      await paySubscription(PLAN_ID);
+
+      const after = await fetchRefundReserve(USDC_MINT);
+      expect(after.amount.toNumber()).toBe(before.amount.toNumber() + PLAN_PRICE);
+    });
+
+    test("Only refunds the config authority after a withdrawal at the subscribed rates", async () => {
+      const { orderId, order, orderVault } = await deposit(DEPOSIT_AMOUNT);
+      await send([await withdrawInstruction(orderId, order, orderVault)]);
+      expect((await fetchManager()).subscribedWithdrawalAt.toNumber()).toBeGreaterThan(0);
+
+      await expect(cancelSubscription()).rejects.toThrow("SubscriptionAlreadyUsed");
+
+      const before = await getAccount(provider.connection, translateAddress(userUsdcAta));
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(await cancelSubscriptionInstruction(admin)),
+        [],
+        { commitment: "confirmed" }
+      );
+
+      const after = await getAccount(provider.connection, translateAddress(userUsdcAta));
+      expect(Number(after.amount)).toBeGreaterThan(Number(before.amount));
+      expect((await fetchManager()).refundableAmount.toNumber()).toBe(0);
+    });
+  });
+});
@@ -0,0 +1,508 @@
+import { describe, test, expect, beforeAll } from "bun:test";
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN, translateAddress } from "@coral-xyz/anchor";
+import { DelegateVault } from "../target/types/delegate_vault";
+import {
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMintInstruction,
+  createInitializeNonTransferableMintInstruction,
+  createInitializePermanentDelegateInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializeTransferHookInstruction,
+  createMintToInstruction,
+  createTransferCheckedWithTransferHookInstruction,
+  ExtensionType,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getExtraAccountMetaAddress,
+  getMintLen,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM,
+  base64Encoder,
+  transactionDecoder,
+  TOKEN_PROGRAM_2022,
+  USDC_MINT,
+} from "../utils/solana/constants";
+import {
+  getOrderAddress,
+  getOrderVaultAddress,
+  getManagerAddress,
+  getAtaAddress,
+  getConfigAddress,
+  getMintPolicyAddress,
+  getFeeOverrideAddress,
+  getReferrerVaultAddress,
+  getFeeVaultAddress,
+  getHookProgramAddress,
+  getPlanAddress,
+  getPlanPriceAddress,
+  getRefundReserveAddress,
+} from "../utils/solana/pda";
+import { setMintPolicy } from "../utils/solana/setup";
+import {
+  generateKeyPair,
+  Address,
+  address,
+  createKeyPairFromBytes,
+  getAddressFromPublicKey,
+  signTransaction,
+  getBase64EncodedWireTransaction,
+} from "@solana/kit";
+import { prepareTransaction } from "../utils/solana/transaction/prepare";
+import { confirmTransaction } from "../utils/solana/transaction/confirm";
+import { toInstruction } from "../utils/solana/transaction/instructions/toInstruction";
+import { createHash } from "crypto";
+import * as fs from "fs";
+import * as path from "path";
+import bs58 from "bs58";
+
+describe("token-2022", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.DelegateVault as Program<DelegateVault>;
+  const admin = provider.wallet.publicKey;
+
+  const DECIMALS = 6;
+  const DEPOSIT_AMOUNT = 10_000;
+  const USER_BALANCE = 1_000_000;
+
+  let user: CryptoKeyPair;
+  let userAddress: Address;
+  let manager: Address;
+  let config: Address;
+
+  async function send(
+    instructions: anchor.web3.TransactionInstruction[],
+    signer: CryptoKeyPair = user
+  ) {
+    const transaction = await prepareTransaction(
+      instructions.map(toInstruction),
+      await getAddressFromPublicKey(signer.publicKey),
+      {}
+    );
+    const decoded = transactionDecoder.decode(base64Encoder.encode(transaction));
+    const signed = await signTransaction([signer], decoded);
+    return confirmTransaction(getBase64EncodedWireTransaction(signed));
+  }
+
+  // Creates a Token-2022 mint of the admin with the extensions initialized by the instructions,
+  // mints the user some tokens and lets them be deposited
+  async function createMint(
+    extensions: ExtensionType[],
+    initializeExtensions: (mint: anchor.web3.PublicKey) => anchor.web3.TransactionInstruction[]
+  ) {
+    const mint = Keypair.generate();
+    const space = getMintLen(extensions);
+    const userAta = translateAddress(
+      await getAtaAddress(userAddress, address(mint.publicKey.toBase58()), TOKEN_PROGRAM_2022)
+    );
+
+    const transaction = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: admin,
+        newAccountPubkey: mint.publicKey,
+        space,
+        lamports: await provider.connection.getMinimumBalanceForRentExemption(space),
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      ...initializeExtensions(mint.publicKey),
+      createInitializeMintInstruction(mint.publicKey, DECIMALS, admin, null, TOKEN_2022_PROGRAM_ID),
+      createAssociatedTokenAccountIdempotentInstruction(
+        admin,
+        userAta,
+        translateAddress(userAddress),
+        mint.publicKey,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createMintToInstruction(mint.publicKey, userAta, admin, USER_BALANCE, [], TOKEN_2022_PROGRAM_ID)
+    );
+    await provider.sendAndConfirm(transaction, [mint], { commitment: "confirmed" });
+
+    const mintAddress = address(mint.publicKey.toBase58());
+    await setMintPolicy(program, mintAddress, {
+      allowDeposit: true,
+      allowTarget: true,
+      minDeposit: 0,
+      maxTvl: 0,
+    });
+    return mintAddress;
+  }
+
+  // Opens a new order with a deposit of the user in the mint, returns its id, address and vault
+  async function deposit(mint: Address, tokenProgram: anchor.web3.PublicKey = TOKEN_2022_PROGRAM_ID) {
+    const orderIdKeyPair = await generateKeyPair();
+    const orderId = await getAddressFromPublicKey(orderIdKeyPair.publicKey);
+    const order = await getOrderAddress(manager, orderId);
+    const orderVault = await getOrderVaultAddress(userAddress, manager, order, mint);
+
+    const instruction = await program.methods
+      .deposit(new BN(DEPOSIT_AMOUNT))
+      .accountsPartial({
+        signer: translateAddress(userAddress),
+        id: translateAddress(orderId),
+        order: translateAddress(order),
+        manager: translateAddress(manager),
+        depositMint: translateAddress(mint),
+        userAta: translateAddress(await getAtaAddress(userAddress, mint, TOKEN_PROGRAM_2022)),
+        orderVault: translateAddress(orderVault),
+        tokenProgram,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .instruction();
+    await send([instruction]);
+
+    return { orderId, order, orderVault };
+  }
+
+  async function withdrawInstruction(
+    mint: Address,
+    { orderId, order, orderVault }: Awaited<ReturnType<typeof deposit>>
+  ) {
+    const { referrer } = await program.account.manager.fetch(translateAddress(manager));
+    return program.methods
+      .withdraw()
+      .accountsPartial({
+        signer: translateAddress(userAddress),
+        id: translateAddress(orderId),
+        order: translateAddress(order),
+        portfolio: null,
+        manager: translateAddress(manager),
+        config: translateAddress(config),
+        orderVault: translateAddress(orderVault),
+        userAta: translateAddress(await getAtaAddress(userAddress, mint, TOKEN_PROGRAM_2022)),
+        feeVault: null,
+        feeSchedule: null,
+        depositMint: translateAddress(mint),
+        mintPolicy: translateAddress(await getMintPolicyAddress(mint)),
+        highWaterMark: null,
+        referrerVault: translateAddress(
+          await getReferrerVaultAddress(address(referrer.toBase58()), mint)
+        ),
+        mintFeeOverride: translateAddress(await getFeeOverrideAddress("mint", mint)),
+        managerFeeOverride: translateAddress(await getFeeOverrideAddress("manager", manager)),
+        discountConfig: null,
+        stake: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM,
+      })
+      .instruction();
+  }
+
+  beforeAll(async () => {
+    const keysPath = path.join(__dirname, "..", "tests", "keys", "test-keys.json");
+    const keys = JSON.parse(fs.readFileSync(keysPath, "utf8"));
+    const userKeyPair = Keypair.fromSecretKey(bs58.decode(keys.user.secretKey));
+    user = await createKeyPairFromBytes(userKeyPair.secretKey);
+    userAddress = await getAddressFromPublicKey(user.publicKey);
+
+    config = await getConfigAddress();
+    manager = await getManagerAddress(userAddress);
+  });
+
+  describe("transfer fee", () => {
+    const TRANSFER_FEE_BPS = 100;
+
+    let mint: Address;
+
+    beforeAll(async () => {
+      mint = await createMint([ExtensionType.TransferFeeConfig], (mint) => [
+        createInitializeTransferFeeConfigInstruction(
+          mint,
+          admin,
+          admin,
+          TRANSFER_FEE_BPS,
+          BigInt(USER_BALANCE),
+          TOKEN_2022_PROGRAM_ID
+        ),
+      ]);
+    });
+
+    test("Rejects deposits through the legacy token program", async () => {
+      await expect(deposit(mint, TOKEN_PROGRAM_ID)).rejects.toThrow();
+    });
+
+    test("Records the deposit net of the transfer fee", async () => {
+      const { order } = await deposit(mint);
+
+      const fetched = await program.account.order.fetch(translateAddress(order));
+      expect(fetched.depositAmount.toNumber()).toBe(
+        DEPOSIT_AMOUNT - (DEPOSIT_AMOUNT * TRANSFER_FEE_BPS) / 10_000
+      );
+    });
+
+    test("Withdraws an order of a transfer fee mint and closes its vault", async () => {
+      const deposited = await deposit(mint);
+
+      await send([await withdrawInstruction(mint, deposited)]);
+
+      // The fee withheld on the order vault is harvested to the mint so the vault can be closed
+      expect(
+        await provider.connection.getAccountInfo(translateAddress(deposited.orderVault))
+      ).toBeNull();
+    });
+
+    test("Records the subscription payment net of the transfer fee", async () => {
+      const PLAN_ID = 1_003;
+      const PRICE = 10_000;
+      const plan = translateAddress(await getPlanAddress(PLAN_ID));
+      const planPrice = translateAddress(await getPlanPriceAddress(address(plan.toBase58()), mint));
+      const feeVault = translateAddress(await getFeeVaultAddress(mint));
+      const refundReserve = translateAddress(await getRefundReserveAddress(mint));
+      const userAta = translateAddress(await getAtaAddress(userAddress, mint, TOKEN_PROGRAM_2022));
+
+      if (!(await program.account.plan.fetchNullable(plan))) {
+        await program.methods
+          .initPlan({
+            id: PLAN_ID,
+            price: new BN(PRICE),
+            duration: new BN(24 * 60 * 60),
+            performanceFee: 500,
+            active: true,
+          })
+          .accountsPartial({
+            signer: admin,
+            config: translateAddress(config),
+            plan,
+            paymentMint: translateAddress(USDC_MINT),
+          })
+          .rpc({ commitment: "confirmed" });
+      }
+      await program.methods
+        .initPlanPrice({ price: new BN(PRICE), active: true })
+        .accountsPartial({
+          signer: admin,
+          config: translateAddress(config),
+          plan,
+          planPrice,
+          mint: translateAddress(mint),
+        })
+        .rpc({ commitment: "confirmed" });
+      await program.methods
+        .initFeeVault()
+        .accountsPartial({
+          signer: admin,
+          config: translateAddress(config),
+          mint: translateAddress(mint),
+          feeVault,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+      await program.methods
+        .initRefundReserve()
+        .accountsPartial({
+          signer: admin,
+          refundReserve,
+          mint: translateAddress(mint),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+
+      const before = await program.account.manager.fetch(translateAddress(manager));
+      const payInstruction = await program.methods
+        .paySubscription()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          manager: translateAddress(manager),
+          config: translateAddress(config),
+          plan,
+          planPrice,
+          paymentMint: translateAddress(mint),
+          feeVault,
+          feeSchedule: null,
+          referrerVault: translateAddress(
+            await getReferrerVaultAddress(address(before.referrer.toBase58()), mint)
+          ),
+          refundReserve,
+          previousRefundReserve: translateAddress(
+            await getRefundReserveAddress(address(before.refundMint.toBase58()))
+          ),
+          signerAta: userAta,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .instruction();
+      await send([payInstruction]);
+
+      // Only what reached the fee vault can be refunded from it
+      const netPrice = PRICE - (PRICE * TRANSFER_FEE_BPS) / 10_000;
+      const after = await program.account.manager.fetch(translateAddress(manager));
+      expect(after.refundableAmount.toNumber()).toBe(netPrice);
+      expect((await getAccount(provider.connection, feeVault, "confirmed", TOKEN_2022_PROGRAM_ID)).amount).toBe(
+        BigInt(netPrice)
+      );
+      expect((await program.account.refundReserve.fetch(refundReserve)).amount.toNumber()).toBe(netPrice);
+
+      const cancelInstruction = await program.methods
+        .cancelSubscription()
+        .accountsPartial({
+          signer: translateAddress(userAddress),
+          manager: translateAddress(manager),
+          config: translateAddress(config),
+          authority: translateAddress(userAddress),
+          refundReserve,
+          refundMint: translateAddress(mint),
+          feeVault,
+          authorityAta: userAta,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .instruction();
+      await send([cancelInstruction]);
+    });
+  });
+
+  // Requires a deployed spl-transfer-hook-example program, its address is read from TRANSFER_HOOK_PROGRAM
+  describe.skipIf(!process.env.TRANSFER_HOOK_PROGRAM)("transfer hook", () => {
+    const FEE_AMOUNT = 1_000;
+
+    let hookProgram: anchor.web3.PublicKey;
+    let mint: Address;
+    let feeVault: Address;
+    let extraAccountMetas: anchor.web3.PublicKey;
+
+    beforeAll(async () => {
+      hookProgram = new anchor.web3.PublicKey(process.env.TRANSFER_HOOK_PROGRAM!);
+      mint = await createMint([ExtensionType.TransferHook], (mint) => [
+        createInitializeTransferHookInstruction(mint, admin, hookProgram, TOKEN_2022_PROGRAM_ID),
+      ]);
+      feeVault = await getFeeVaultAddress(mint);
+
+      // Empty list of extra accounts, the hook only checks the accounts are transferring
+      extraAccountMetas = getExtraAccountMetaAddress(translateAddress(mint), hookProgram);
+      const discriminator = createHash("sha256")
+        .update("spl-transfer-hook-interface:initialize-extra-account-metas")
+        .digest()
+        .subarray(0, 8);
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          new anchor.web3.TransactionInstruction({
+            programId: hookProgram,
+            keys: [
+              { pubkey: extraAccountMetas, isSigner: false, isWritable: true },
+              { pubkey: translateAddress(mint), isSigner: false, isWritable: false },
+              { pubkey: admin, isSigner: true, isWritable: false },
+              { pubkey: anchor.web3.SystemProgram.programId, isSigner: false, isWritable: false },
+            ],
+            data: Buffer.concat([discriminator, Buffer.alloc(4)]),
+          })
+        ),
+        [],
+        { commitment: "confirmed" }
+      );
+
+      const hookProgramPda = translateAddress(
+        await getHookProgramAddress(address(hookProgram.toBase58()))
+      );
+      if (!(await provider.connection.getAccountInfo(hookProgramPda))) {
+        await program.methods
+          .initHookProgram({ allowed: true })
+          .accountsPartial({
+            signer: admin,
+            config: translateAddress(config),
+            hookProgram: hookProgramPda,
+            program: hookProgram,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc({ commitment: "confirmed" });
+      }
+
+      await program.methods
+        .initFeeVault()
+        .accountsPartial({
+          signer: admin,
+          config: translateAddress(config),
+          mint: translateAddress(mint),
+          feeVault: translateAddress(feeVault),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+
+      await send([
+        await createTransferCheckedWithTransferHookInstruction(
+          provider.connection,
+          translateAddress(await getAtaAddress(userAddress, mint, TOKEN_PROGRAM_2022)),
+          translateAddress(mint),
+          translateAddress(feeVault),
+          translateAddress(userAddress),
+          BigInt(FEE_AMOUNT),
+          DECIMALS,
+          [],
+          "confirmed",
+          TOKEN_2022_PROGRAM_ID
+        ),
+      ]);
+    });
+
+    test("Claims the fee vault of a transfer hook mint", async () => {
+      const adminAta = getAssociatedTokenAddressSync(
+        translateAddress(mint),
+        admin,
+        false,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await program.methods
+        .claimFees([10_000])
+        .accountsPartial({
+          signer: admin,
+          config: translateAddress(config),
+          mint: translateAddress(mint),
+          feeVault: translateAddress(feeVault),
+          refundReserve: translateAddress(await getRefundReserveAddress(mint)),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: adminAta, isSigner: false, isWritable: true },
+          // Extra accounts of the transfer hook, after the receivers
+          { pubkey: hookProgram, isSigner: false, isWritable: false },
+          { pubkey: extraAccountMetas, isSigner: false, isWritable: false },
+        ])
+        .preInstructions([
+          createAssociatedTokenAccountIdempotentInstruction(
+            admin,
+            adminAta,
+            admin,
+            translateAddress(mint),
+            TOKEN_2022_PROGRAM_ID
+          ),
+        ])
+        .rpc({ commitment: "confirmed" });
+
+      const vault = await getAccount(
+        provider.connection,
+        translateAddress(feeVault),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      expect(Number(vault.amount)).toBe(0);
+      const receiver = await getAccount(
+        provider.connection,
+        adminAta,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      expect(Number(receiver.amount)).toBe(FEE_AMOUNT);
+    });
+  });
+
+  describe("extension policy", () => {
+    test("Rejects a mint with a permanent delegate", async () => {
+      const mint = await createMint([ExtensionType.PermanentDelegate], (mint) => [
+        createInitializePermanentDelegateInstruction(mint, admin, TOKEN_2022_PROGRAM_ID),
+      ]);
+      await expect(deposit(mint)).rejects.toThrow("PermanentDelegateNotAllowed");
+    });
+
+    test("Rejects a non-transferable mint", async () => {
+      const mint = await createMint([ExtensionType.NonTransferable], (mint) => [
+        createInitializeNonTransferableMintInstruction(mint, TOKEN_2022_PROGRAM_ID),
+      ]);
+      await expect(deposit(mint)).rejects.toThrow("NonTransferableNotAllowed");
+    });
+  });
+});
@@ -18,7 +18,9 @@ import {
   getManagerAddress,
   getAtaAddress,
   getConfigAddress,
+  getFeeVaultAddress,
 } from "../utils/solana/pda";
+import { setupProgram } from "../utils/solana/setup";
 import {
   generateKeyPair,
   address,
@@ -65,6 +67,8 @@ describe("user operations", () => {
   let delegateAddress: Address;
 
   beforeAll(async () => {
+    // Mint policies and swap venue required by the deposits and swaps
+    await setupProgram(program);
     // Load keypairs from file
     const keysPath = path.join(
       __dirname,
@@ -92,7 +96,7 @@ describe("user operations", () => {
     // Derive PDAs
     config = await getConfigAddress();
     manager = await getManagerAddress(userAddress);
-    feeVault = await getAtaAddress(SQUADS_PERFORMANCE_ADDRESS, usdcMint);
+    feeVault = await getFeeVaultAddress(usdcMint);
     userUsdcAta = await getAtaAddress(userAddress, usdcMint);
     userSolAta = await getAtaAddress(userAddress, solMint);
 
@@ -264,8 +268,7 @@ describe("user operations", () => {
     });
 
     test("Withdraws funds from the vault", async () => {
-      const performanceReceiver = address(SQUADS_PERFORMANCE_ADDRESS);
-      const feeVault = await getAtaAddress(performanceReceiver, usdcMint);
+      const feeVault = await getFeeVaultAddress(usdcMint);
 
       const withdrawInstruction = await program.methods
         .withdraw()
@@ -273,12 +276,12 @@ describe("user operations", () => {
           signer: translateAddress(userAddress),
           id: translateAddress(ephemeralKeyAddress),
           order: translateAddress(orderPda),
+          portfolio: null,
           manager: translateAddress(manager),
           config: translateAddress(config),
           depositMint: translateAddress(usdcMint),
           userAta: translateAddress(userUsdcAta),
           orderVault: translateAddress(orderVaultPda),
-          performanceReceiver: translateAddress(performanceReceiver),
           feeVault: translateAddress(feeVault),
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM,
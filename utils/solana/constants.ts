@@ -16,6 +16,8 @@ export const FRNX_MINT =
   "2uJGDsUKq4T3AUn2UXrnzyKz644GxXy7z333WcXdvirt" as Address;
 export const PYUSD_MINT =
   "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo" as Address;
+export const JUP_MINT =
+  "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN" as Address;
 export const VIRTUALS_MINT =
   "3iQL8BFS2vE7mww4ehAqQHAsbmRNCrPxizWAT2Zfyr9y" as Address;
 
@@ -81,6 +83,32 @@ export const REFERRAL_ADMIN_ADDRESS =
 
 export const JUPITER_PROGRAM =
   "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4" as Address;
+// AMMs the Jupiter routes of the tests go through
+export const JUPITER_AMM_PROGRAMS = [
+  "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc",
+  "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8",
+  "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK",
+  "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C",
+  "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo",
+  "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB",
+  "PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY",
+  "SoLFiHG9TfgtdUXUjWAxi3LtvYuFyDLVhBWxdMZxyCe",
+  "obriQD1zbpyLz95G5n7nJe6a4DPjpFwa5XYPoNm113y",
+] as Address[];
+export const MEMO_PROGRAM =
+  "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr" as Address;
+// Pyth sponsored PriceUpdateV2 accounts (shard 0)
+export const SOL_USD_PRICE_FEED =
+  "7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE" as Address;
+export const USDC_USD_PRICE_FEED =
+  "Dpw1EAVrSB1ibxiDQyTAW6Zip3J4Btk2x4SgApQCeFbX" as Address;
+
+// Pyth feed ids of the sponsored PriceUpdateV2 accounts above
+export const SOL_USD_FEED_ID =
+  "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+export const USDC_USD_FEED_ID =
+  "eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a";
+
 export const DELEGATE_VAULT_PROGRAM =
   "frnxh6RXdbpvTbhQ8yRtEbLNnXKmbGEqwfwMpZaBRw9" as Address;
 
@@ -1,4 +1,4 @@
-import { address, Address, getAddressEncoder, getProgramDerivedAddress, getU16Encoder } from '@solana/kit';
+import { address, Address, getAddressEncoder, getProgramDerivedAddress, getU16Encoder, getU64Encoder } from '@solana/kit';
 import { DELEGATE_VAULT_PROGRAM, TOKEN_PROGRAM } from './constants';
 import { findAssociatedTokenPda } from '@solana-program/token';
 import { WHIRLPOOL_PROGRAM_ADDRESS } from '@orca-so/whirlpools-client';
@@ -57,6 +57,192 @@ export async function getTokenVaultAddress(signer: Address, manager: Address, or
     }))[0];
 }
 
+export async function getPortfolioAddress(order: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('portfolio'), 
+            getAddressEncoder().encode(order)
+        ],
+    }))[0];
+}
+
+export async function getHighWaterMarkAddress(manager: Address, mint: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('high_water_mark'), 
+            getAddressEncoder().encode(manager), 
+            getAddressEncoder().encode(mint)
+        ],
+    }))[0];
+}
+
+export async function getFeeVaultAddress(mint: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('fee_vault'), 
+            getAddressEncoder().encode(mint)
+        ],
+    }))[0];
+}
+
+export async function getRefundReserveAddress(mint: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('refund_reserve'), 
+            getAddressEncoder().encode(mint)
+        ],
+    }))[0];
+}
+
+export async function getFeeScheduleAddress() {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [Buffer.from('fee_schedule')],
+    }))[0];
+}
+
+export async function getReferrerAddress(wallet: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('referrer'), 
+            getAddressEncoder().encode(wallet)
+        ],
+    }))[0];
+}
+
+export async function getReferrerVaultAddress(referrer: Address, mint: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('referrer_vault'), 
+            getAddressEncoder().encode(referrer), 
+            getAddressEncoder().encode(mint)
+        ],
+    }))[0];
+}
+
+export async function getFeeOverrideAddress(kind: 'mint' | 'manager', target: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from(`${kind}_fee_override`), 
+            getAddressEncoder().encode(target)
+        ],
+    }))[0];
+}
+
+export async function getPlanAddress(id: number) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('plan'), 
+            getU16Encoder().encode(id)
+        ],
+    }))[0];
+}
+
+export async function getPlanPriceAddress(plan: Address, mint: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('plan_price'), 
+            getAddressEncoder().encode(plan), 
+            getAddressEncoder().encode(mint)
+        ],
+    }))[0];
+}
+
+export async function getVoucherRedemptionAddress(nonce: bigint) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('voucher'), 
+            getU64Encoder().encode(nonce)
+        ],
+    }))[0];
+}
+
+export async function getDiscountConfigAddress() {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [Buffer.from('discount_config')],
+    }))[0];
+}
+
+export async function getStakeAddress(manager: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('stake'), 
+            getAddressEncoder().encode(manager)
+        ],
+    }))[0];
+}
+
+export async function getStakeVaultAddress(manager: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('stake_vault'), 
+            getAddressEncoder().encode(manager)
+        ],
+    }))[0];
+}
+
+export async function getMintPolicyAddress(mint: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('mint_policy'), 
+            getAddressEncoder().encode(mint)
+        ],
+    }))[0];
+}
+
+export async function getSwapVenueAddress(program: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('swap_venue'), 
+            getAddressEncoder().encode(program)
+        ],
+    }))[0];
+}
+
+export async function getVenueProgramsAddress(swapVenue: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('venue_programs'), 
+            getAddressEncoder().encode(swapVenue)
+        ],
+    }))[0];
+}
+
+export async function getMintOracleAddress(mint: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('mint_oracle'), 
+            getAddressEncoder().encode(mint)
+        ],
+    }))[0];
+}
+
+export async function getHookProgramAddress(hookProgram: Address) {
+    return (await getProgramDerivedAddress({
+        programAddress: DELEGATE_VAULT_PROGRAM,
+        seeds: [
+            Buffer.from('hook_program'), 
+            getAddressEncoder().encode(hookProgram)
+        ],
+    }))[0];
+}
 
 export async function getTickArrayAddress(whirlpool: Address, startTickIndex: number) {
     return (await getProgramDerivedAddress({
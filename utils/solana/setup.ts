@@ -0,0 +1,150 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program, translateAddress } from "@coral-xyz/anchor";
+import { Address } from "@solana/kit";
+import { DelegateVault } from "../../target/types/delegate_vault";
+import {
+  JUPITER_AMM_PROGRAMS,
+  JUPITER_PROGRAM,
+  SOL_MINT,
+  SOL_USD_FEED_ID,
+  USDC_MINT,
+  USDC_USD_FEED_ID,
+} from "./constants";
+import {
+  getMintOracleAddress,
+  getMintPolicyAddress,
+  getSwapVenueAddress,
+  getVenueProgramsAddress,
+} from "./pda";
+
+export type MintPolicyParams = {
+  allowDeposit: boolean;
+  allowTarget: boolean;
+  minDeposit: number;
+  maxTvl: number;
+};
+
+const NO_ACCOUNT = 255;
+
+// Jupiter v6 routes, account positions taken from the Jupiter IDL
+const JUPITER_ROUTE_ACCOUNTS = {
+  transferAuthorityIdx: 1,
+  sourceIdx: 2,
+  destinationIdx: 3,
+  destinationMintIdx: 5,
+  platformFeeAccountIdx: 6,
+  restingOrderIdx: NO_ACCOUNT,
+};
+const JUPITER_SHARED_ACCOUNTS_ROUTE_ACCOUNTS = {
+  transferAuthorityIdx: 2,
+  sourceIdx: 3,
+  destinationIdx: 6,
+  destinationMintIdx: 8,
+  platformFeeAccountIdx: 9,
+  restingOrderIdx: NO_ACCOUNT,
+};
+
+export const JUPITER_VENUE_ROUTES = [
+  // route
+  { discriminator: [229, 23, 203, 151, 122, 227, 173, 42], ...JUPITER_ROUTE_ACCOUNTS },
+  // route_with_token_ledger
+  { discriminator: [150, 86, 71, 116, 167, 93, 14, 104], ...JUPITER_ROUTE_ACCOUNTS },
+  // exact_out_route, the source mint comes before the destination mint
+  {
+    discriminator: [208, 51, 239, 151, 123, 43, 237, 92],
+    ...JUPITER_ROUTE_ACCOUNTS,
+    destinationMintIdx: 6,
+    platformFeeAccountIdx: 7,
+  },
+  // shared_accounts_route
+  { discriminator: [193, 32, 155, 51, 65, 214, 156, 129], ...JUPITER_SHARED_ACCOUNTS_ROUTE_ACCOUNTS },
+  // shared_accounts_route_with_token_ledger
+  { discriminator: [230, 121, 143, 80, 119, 159, 106, 170], ...JUPITER_SHARED_ACCOUNTS_ROUTE_ACCOUNTS },
+  // shared_accounts_exact_out_route
+  { discriminator: [176, 209, 105, 168, 154, 125, 69, 62], ...JUPITER_SHARED_ACCOUNTS_ROUTE_ACCOUNTS },
+].map((route) => ({ ...route, kind: { swap: {} }, argsLayout: { jupiter: {} } }));
+
+// Creates or updates the mint policy, the provider wallet has to be the config authority
+export async function setMintPolicy(
+  program: Program<DelegateVault>,
+  mint: Address,
+  params: MintPolicyParams
+) {
+  const signer = (program.provider as anchor.AnchorProvider).wallet.publicKey;
+  const mintPolicy = translateAddress(await getMintPolicyAddress(mint));
+  const args = {
+    allowDeposit: params.allowDeposit,
+    allowTarget: params.allowTarget,
+    minDeposit: new BN(params.minDeposit),
+    maxTvl: new BN(params.maxTvl),
+  };
+  const existing = await program.account.mintPolicy.fetchNullable(mintPolicy);
+  const method = existing
+    ? program.methods.editMintPolicy(args)
+    : program.methods.initMintPolicy(args);
+  await method
+    .accountsPartial({ signer, mintPolicy, mint: translateAddress(mint) })
+    .rpc({ commitment: "confirmed" });
+}
+
+// Registers the Jupiter program as a swap venue with its six swap routes and the AMMs of the tests, if not done before
+export async function setupJupiterVenue(program: Program<DelegateVault>) {
+  const signer = (program.provider as anchor.AnchorProvider).wallet.publicKey;
+  const swapVenueAddress = await getSwapVenueAddress(JUPITER_PROGRAM);
+  const swapVenue = translateAddress(swapVenueAddress);
+  if (!(await program.account.swapVenue.fetchNullable(swapVenue))) {
+    await program.methods
+      .initSwapVenue({ routes: JUPITER_VENUE_ROUTES, active: true })
+      .accountsPartial({
+        signer,
+        swapVenue,
+        swapProgram: translateAddress(JUPITER_PROGRAM),
+      })
+      .rpc({ commitment: "confirmed" });
+  }
+
+  const venuePrograms = translateAddress(await getVenueProgramsAddress(swapVenueAddress));
+  if (!(await program.account.venuePrograms.fetchNullable(venuePrograms))) {
+    await program.methods
+      .initVenuePrograms({ programs: JUPITER_AMM_PROGRAMS.map(translateAddress) })
+      .accountsPartial({ signer, swapVenue, venuePrograms })
+      .rpc({ commitment: "confirmed" });
+  }
+}
+
+// Binds the mint to its Pyth feed, the provider wallet has to be the config authority
+export async function setMintOracle(
+  program: Program<DelegateVault>,
+  mint: Address,
+  feedId: string
+) {
+  const signer = (program.provider as anchor.AnchorProvider).wallet.publicKey;
+  const mintOracle = translateAddress(await getMintOracleAddress(mint));
+  const args = { feedId: Array.from(Buffer.from(feedId, "hex")) };
+  const existing = await program.account.mintOracle.fetchNullable(mintOracle);
+  const method = existing
+    ? program.methods.editMintOracle(args)
+    : program.methods.initMintOracle(args);
+  await method
+    .accountsPartial({ signer, mintOracle, mint: translateAddress(mint) })
+    .rpc({ commitment: "confirmed" });
+}
+
+// Default setup of the test suites: USDC can be deposited and SOL bought without limits through the Jupiter venue, both priced by Pyth
+export async function setupProgram(program: Program<DelegateVault>) {
+  await setMintPolicy(program, USDC_MINT, {
+    allowDeposit: true,
+    allowTarget: true,
+    minDeposit: 0,
+    maxTvl: 0,
+  });
+  await setMintPolicy(program, SOL_MINT, {
+    allowDeposit: true,
+    allowTarget: true,
+    minDeposit: 0,
+    maxTvl: 0,
+  });
+  await setMintOracle(program, USDC_MINT, USDC_USD_FEED_ID);
+  await setMintOracle(program, SOL_MINT, SOL_USD_FEED_ID);
+  await setupJupiterVenue(program);
+}
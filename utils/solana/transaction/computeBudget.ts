@@ -107,6 +107,14 @@ async function getComputeUnits(
       }
     }
 
+    // Surface the program error name, e.g. "Error Code: MintNotAllowed"
+    for (const log of simulation.value.logs) {
+      const anchorError = log.match(/Error Code: (\w+)/);
+      if (anchorError) {
+        throw new Error(anchorError[1]);
+      }
+    }
+
     throw new Error("Transaction simulation error");
   }
 